@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+use crate::clock;
+use crate::models::GetApiResponse;
+
+/// An in-memory cache of api information, keyed by api id.
+pub(crate) struct ApiCache {
+    /// How long each entry stays fresh.
+    ttl: Duration,
+
+    /// The cached responses, along with the time they were inserted.
+    entries: Mutex<HashMap<String, (Duration, GetApiResponse)>>,
+}
+
+impl ApiCache {
+    /// Creates a new empty api cache.
+    ///
+    /// # Arguments
+    /// - `ttl`: How long each entry stays fresh.
+    ///
+    /// # Returns
+    /// The new api cache.
+    pub fn new(ttl: Duration) -> Self {
+        let entries = Mutex::new(HashMap::new());
+
+        Self { ttl, entries }
+    }
+
+    /// Gets the cached response for the api, if it is still fresh.
+    ///
+    /// # Arguments
+    /// - `api_id`: The id of the api.
+    ///
+    /// # Returns
+    /// The cached response, if any.
+    pub fn get(&self, api_id: &str) -> Option<GetApiResponse> {
+        self.get_at(api_id, clock::now())
+    }
+
+    /// Gets the cached response for the api if it is fresh at the given
+    /// time, evicting it otherwise.
+    ///
+    /// # Arguments
+    /// - `api_id`: The id of the api.
+    /// - `now`: The current time.
+    ///
+    /// # Returns
+    /// The cached response, if any.
+    fn get_at(&self, api_id: &str, now: Duration) -> Option<GetApiResponse> {
+        let mut entries = self.entries();
+
+        match entries.get(api_id) {
+            Some((inserted, res)) if now < inserted.saturating_add(self.ttl) => Some(res.clone()),
+            Some(_) => {
+                entries.remove(api_id);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Caches the response for its api.
+    ///
+    /// # Arguments
+    /// - `res`: The response to cache.
+    pub fn insert(&self, res: GetApiResponse) {
+        self.insert_at(res, clock::now());
+    }
+
+    /// Caches the response for its api as of the given time.
+    ///
+    /// # Arguments
+    /// - `res`: The response to cache.
+    /// - `now`: The current time.
+    fn insert_at(&self, res: GetApiResponse, now: Duration) {
+        self.entries().insert(res.api_id.clone(), (now, res));
+    }
+
+    /// Removes the cached response for the api, if any.
+    ///
+    /// # Arguments
+    /// - `api_id`: The id of the api.
+    pub fn invalidate(&self, api_id: &str) {
+        self.entries().remove(api_id);
+    }
+
+    /// Locks the cached entries.
+    fn entries(&self) -> std::sync::MutexGuard<'_, HashMap<String, (Duration, GetApiResponse)>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl std::fmt::Debug for ApiCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiCache")
+            .field("ttl", &self.ttl)
+            .field("entries", &self.entries().len())
+            .finish()
+    }
+}
+
+impl Clone for ApiCache {
+    fn clone(&self) -> Self {
+        let ttl = self.ttl;
+        let entries = Mutex::new(self.entries().clone());
+
+        Self { ttl, entries }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::cache::ApiCache;
+    use crate::models::GetApiResponse;
+
+    fn response(api_id: &str) -> GetApiResponse {
+        let body = format!(r#"{{"id":"{api_id}","name":"test","workspaceId":"ws_123"}}"#);
+        serde_json::from_str(&body).unwrap()
+    }
+
+    #[test]
+    fn cache_hit() {
+        let cache = ApiCache::new(Duration::from_secs(60));
+        cache.insert_at(response("api_123"), Duration::from_secs(100));
+
+        let hit = cache.get_at("api_123", Duration::from_secs(159));
+
+        assert_eq!(hit, Some(response("api_123")));
+    }
+
+    #[test]
+    fn cache_miss() {
+        let cache = ApiCache::new(Duration::from_secs(60));
+        cache.insert_at(response("api_123"), Duration::from_secs(100));
+
+        assert_eq!(cache.get_at("api_456", Duration::from_secs(100)), None);
+    }
+
+    #[test]
+    fn cache_expiry() {
+        let cache = ApiCache::new(Duration::from_secs(60));
+        cache.insert_at(response("api_123"), Duration::from_secs(100));
+
+        assert_eq!(cache.get_at("api_123", Duration::from_secs(160)), None);
+        assert!(cache.entries().is_empty());
+    }
+
+    #[test]
+    fn cache_invalidate() {
+        let cache = ApiCache::new(Duration::from_secs(60));
+        cache.insert(response("api_123"));
+        cache.insert(response("api_456"));
+        cache.invalidate("api_123");
+
+        assert_eq!(cache.get("api_123"), None);
+        assert_eq!(cache.get("api_456"), Some(response("api_456")));
+    }
+}
@@ -1,26 +1,99 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::Stream;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+#[cfg(feature = "cache")]
+use crate::cache::ApiCache;
+use crate::clock;
+use crate::fetch;
+use crate::logging;
+use crate::logging::LogLevel;
+use crate::models::AddPermissionsRequest;
 use crate::models::ApiKey;
+use crate::models::ApiVersion;
+use crate::models::CreateIdentityRequest;
+use crate::models::CreateIdentityResponse;
 use crate::models::CreateKeyRequest;
 use crate::models::CreateKeyResponse;
 use crate::models::DeleteApiRequest;
+use crate::models::DeleteIdentityRequest;
+use crate::models::DeleteKeyRequest;
+use crate::models::Error;
+use crate::models::ErrorCode;
 use crate::models::GetApiRequest;
 use crate::models::GetApiResponse;
+use crate::models::GetIdentityRequest;
 use crate::models::GetKeyRequest;
+use crate::models::GetUsageNumbersRequest;
+use crate::models::GetUsageNumbersResponse;
+use crate::models::HttpError;
+use crate::models::Identity;
+use crate::models::ListApisRequest;
+use crate::models::ListApisResponse;
+use crate::models::ListIdentitiesRequest;
+use crate::models::ListIdentitiesResponse;
 use crate::models::ListKeysRequest;
 use crate::models::ListKeysResponse;
-use crate::models::RevokeKeyRequest;
+use crate::models::MigrateKeyRequest;
+use crate::models::MigrateKeyResponse;
+use crate::models::RatelimitHeaders;
+use crate::models::RatelimitRequest;
+use crate::models::RatelimitResponse;
+use crate::models::RemovePermissionsRequest;
+use crate::models::RetryPolicy;
+use crate::models::UpdateApiRequest;
 use crate::models::UpdateKeyRequest;
 use crate::models::UpdateRemainingRequest;
 use crate::models::UpdateRemainingResponse;
 use crate::models::VerifyKeyRequest;
 use crate::models::VerifyKeyResponse;
+use crate::parse_empty_response;
+use crate::parse_response;
+use crate::routes;
+use crate::routes::CompiledRoute;
 use crate::services::ApiService;
 use crate::services::HttpService;
+use crate::services::IdentityService;
 use crate::services::KeyService;
+use crate::services::RatelimitService;
 
-#[allow(unused_imports)]
-use crate::models::HttpError;
+/// The maximum number of revoke requests in flight at once for
+/// [`Client::revoke_keys`].
+const REVOKE_CONCURRENCY: usize = 10;
+
+/// The root api key used by [`Client::mock`], known to be a valid header
+/// value.
+#[cfg(any(test, feature = "mock"))]
+const MOCK_KEY: &str = "unkey_mock";
+
+/// The non-routable base url used by [`Client::mock`], so requests fail
+/// immediately instead of reaching a real api.
+#[cfg(any(test, feature = "mock"))]
+const MOCK_URL: &str = "http://0.0.0.0:0";
 
 /// The client used to make requests to the unkey api.
+///
+/// ## Note
+/// The client is `Send + Sync`, so a single client can be shared across
+/// threads and async tasks by wrapping it in an [`Arc`]. Every holder of the
+/// [`Arc`] sees changes made through [`Client::set_key`] or
+/// [`Client::set_url`]. Cloning the client instead reuses the same
+/// connection pool, but gives the clone its own key, url and headers.
+///
+/// # Example
+/// ```
+/// # use std::sync::Arc;
+/// # use unkey::Client;
+/// let c = Arc::new(Client::new("unkey_ghj"));
+/// let shared = Arc::clone(&c);
+///
+/// std::thread::spawn(move || shared.set_key("unkey_rotated"))
+///     .join()
+///     .unwrap();
+/// ```
 #[derive(Debug, Clone)]
 pub struct Client {
     /// The internal http service sending and receiving requests.
@@ -31,11 +104,53 @@ pub struct Client {
 
     /// The api service handling api related requests.
     apis: ApiService,
+
+    /// The ratelimit service handling standalone ratelimit requests.
+    ratelimits: RatelimitService,
+
+    /// The identity service handling identity related requests.
+    identities: IdentityService,
+
+    /// The cache of api information, if enabled.
+    #[cfg(feature = "cache")]
+    cache: Option<ApiCache>,
 }
 
+// The client is shared across async tasks, so this must keep compiling
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Client>();
+};
+
 impl Client {
+    /// Creates a new client around the given http service, with every
+    /// other service in its default state.
+    ///
+    /// # Arguments
+    /// - `http`: The http service to send requests with.
+    ///
+    /// # Returns
+    /// The new client.
+    fn from_http(http: HttpService) -> Self {
+        Self {
+            http,
+            keys: KeyService,
+            apis: ApiService,
+            ratelimits: RatelimitService,
+            identities: IdentityService,
+            #[cfg(feature = "cache")]
+            cache: None,
+        }
+    }
+
     /// Creates a new client.
     ///
+    /// ## Note
+    /// The client uses the production unkey api url, unless the
+    /// `UNKEY_BASE_URL` environment variable is set, i.e. to
+    /// `http://localhost:3000` for local development. An explicit url, as
+    /// given to [`Client::with_url`], always takes precedence.
+    ///
     /// # Arguments
     /// - `key`: The root api key the client should send with requests.
     ///
@@ -47,13 +162,59 @@ impl Client {
     /// # use unkey::Client;
     /// let c = Client::new("unkey_ghj");
     /// ```
+    ///
+    /// # Panics
+    /// If the key is not a valid header value, see [`Client::try_new`].
     #[must_use]
     pub fn new(key: &str) -> Self {
-        let http = HttpService::new(key);
-        let keys = KeyService;
-        let apis = ApiService;
+        Self::try_new(key).expect("the root api key must be a valid header value")
+    }
+
+    /// Creates a new client, failing if the key can't be sent as a header.
+    ///
+    /// # Arguments
+    /// - `key`: The root api key the client should send with requests.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the new client, or an error.
+    ///
+    /// # Errors
+    /// An [`Error::Api`] with [`ErrorCode::BadRequest`] if the key contains
+    /// characters not allowed in a header, i.e. a newline.
+    ///
+    /// [`ErrorCode::BadRequest`]: crate::models::ErrorCode::BadRequest
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::Client;
+    /// # use unkey::models::ErrorCode;
+    /// assert!(Client::try_new("unkey_ghj").is_ok());
+    ///
+    /// let err = Client::try_new("unkey_ghj\n").unwrap_err();
+    /// assert_eq!(err.code(), ErrorCode::BadRequest);
+    /// ```
+    pub fn try_new(key: &str) -> Result<Self, Error> {
+        Ok(Self::from_http(HttpService::try_new(key)?))
+    }
 
-        Self { http, keys, apis }
+    /// Creates a new builder for configuring a client.
+    ///
+    /// # Returns
+    /// The new client builder.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::time::Duration;
+    /// # use unkey::Client;
+    /// let c = Client::builder()
+    ///     .key("unkey_ghj")
+    ///     .url("http://localhost:3000")
+    ///     .timeout(Duration::from_secs(10))
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
     }
 
     /// Creates a new client with a different base url than the production
@@ -62,7 +223,7 @@ impl Client {
     /// # Arguments
     /// - `key`: The root api key the client should send with requests.
     /// - `url`: The base url to use, excluding trailing slash.
-    ///     i.e. `http://localhost:3000`.
+    ///   i.e. `http://localhost:3000`.
     ///
     /// # Returns
     /// The new client.
@@ -74,11 +235,92 @@ impl Client {
     /// ```
     #[must_use]
     pub fn with_url(key: &str, url: &str) -> Self {
-        let http = HttpService::with_url(key, url);
-        let keys = KeyService;
-        let apis = ApiService;
+        Self::from_http(HttpService::with_url(key, url))
+    }
+
+    /// Creates a new client pinned to the given unkey api version.
+    ///
+    /// # Arguments
+    /// - `key`: The root api key the client should send with requests.
+    /// - `version`: The [`ApiVersion`] to use.
+    ///
+    /// # Returns
+    /// The new client.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::Client;
+    /// # use unkey::models::ApiVersion;
+    /// let c = Client::with_version("unkey_ghj", ApiVersion::V1);
+    /// ```
+    #[must_use]
+    pub fn with_version(key: &str, version: ApiVersion) -> Self {
+        Self::from_http(HttpService::with_version(key, version))
+    }
+
+    /// Creates a new client using a preconfigured [`reqwest::Client`], i.e.
+    /// one with custom proxy or tls settings.
+    ///
+    /// The authorization and sdk headers are still sent with every request.
+    ///
+    /// # Arguments
+    /// - `key`: The root api key the client should send with requests.
+    /// - `url`: The base url to use, excluding trailing slash.
+    /// - `client`: The request client to send requests with.
+    ///
+    /// # Returns
+    /// The new client.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::Client;
+    /// let client = reqwest::Client::new();
+    /// let c = Client::with_client("unkey_ghj", "https://api.unkey.dev/v1", client);
+    /// ```
+    #[must_use]
+    pub fn with_client(key: &str, url: &str, client: reqwest::Client) -> Self {
+        Self::from_http(HttpService::with_client(key, url, client))
+    }
+
+    /// Creates a new client that retries failed idempotent requests using
+    /// the given retry policy.
+    ///
+    /// # Arguments
+    /// - `key`: The root api key the client should send with requests.
+    /// - `policy`: The [`RetryPolicy`] to use for failed requests.
+    ///
+    /// # Returns
+    /// The new client.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::Client;
+    /// # use unkey::models::RetryPolicy;
+    /// let c = Client::with_retry_policy("unkey_ghj", RetryPolicy::default());
+    /// ```
+    #[must_use]
+    pub fn with_retry_policy(key: &str, policy: RetryPolicy) -> Self {
+        let mut http = HttpService::new(key);
+        http.set_retry_policy(Some(policy));
 
-        Self { http, keys, apis }
+        Self::from_http(http)
+    }
+
+    /// Creates a deterministic client for tests and examples, using a fixed
+    /// root api key and a non-routable base url.
+    ///
+    /// Requests made with this client fail with an [`Error::Network`]
+    /// rather than reaching the unkey api.
+    ///
+    /// ## Note
+    /// This is only available with the `mock` feature enabled.
+    ///
+    /// # Returns
+    /// The mock client.
+    #[must_use]
+    #[cfg(any(test, feature = "mock"))]
+    pub fn mock() -> Self {
+        Self::with_url(MOCK_KEY, MOCK_URL)
     }
 
     /// Updates the root api key for the client.
@@ -86,17 +328,24 @@ impl Client {
     /// # Arguments
     /// - `key`: The new root api key the client should send with requests.
     ///
+    /// ## Note
+    /// This only requires a shared reference, so the key can be rotated
+    /// while the client is shared across tasks, i.e. in an `Arc<Client>`.
+    /// Requests already in flight keep using the previous key.
+    ///
     /// # Example
     /// ```
+    /// # use std::sync::Arc;
     /// # use unkey::Client;
-    /// let mut c = Client::new("unkey_ghj");
+    /// let c = Arc::new(Client::new("unkey_ghj"));
     /// c.set_key("unkey_abc");
     /// ```
-    pub fn set_key(&mut self, key: &str) {
+    pub fn set_key(&self, key: &str) {
         self.http.set_key(key);
     }
 
-    /// Sets the url the client will send requests to.
+    /// Sets the url the client will send requests to, stripping any
+    /// trailing slash. Use [`Client::try_set_url`] to validate the url.
     ///
     /// # Arguments
     /// - `url`: The new base url to use.
@@ -104,168 +353,663 @@ impl Client {
     /// # Example
     /// ```
     /// # use unkey::Client;
-    /// let mut c = Client::new("unkey_ghj");
+    /// let c = Client::new("unkey_ghj");
     /// c.set_url("http://localhost:6969");
     /// ```
-    pub fn set_url(&mut self, url: &str) {
+    pub fn set_url(&self, url: &str) {
         self.http.set_url(url);
     }
 
-    /// Verifies an existing api key.
+    /// Sets the url the client will send requests to, failing if it is
+    /// invalid.
     ///
     /// # Arguments
-    /// - `req`: The verify key request to send.
+    /// - `url`: The new base url to use, including the scheme.
     ///
     /// # Returns
-    /// A [`Result`] containing the response, or an error.
+    /// A [`Result`] indicating whether the url was set.
     ///
     /// # Errors
-    /// The [`HttpError`], if one occurred.
+    /// An [`Error::Api`] with [`ErrorCode::BadRequest`] if the url can't be
+    /// parsed, or its scheme is not `http` or `https`.
+    ///
+    /// [`ErrorCode::BadRequest`]: crate::models::ErrorCode::BadRequest
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::Client;
+    /// let c = Client::new("unkey_ghj");
+    ///
+    /// assert!(c.try_set_url("http://localhost:6969/").is_ok());
+    /// assert!(c.try_set_url("localhost:6969").is_err());
+    /// ```
+    pub fn try_set_url(&self, url: &str) -> Result<(), Error> {
+        Ok(self.http.try_set_url(url)?)
+    }
+
+    /// Sets the user agent the client will send with requests, i.e. to tag
+    /// which of your services made the request.
+    ///
+    /// # Arguments
+    /// - `user_agent`: The new user agent to use, or an empty string to
+    ///   restore the default.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::Client;
+    /// let c = Client::new("unkey_ghj");
+    /// c.set_user_agent("billing-service");
+    /// ```
+    pub fn set_user_agent(&self, user_agent: &str) {
+        self.http.set_user_agent(user_agent);
+    }
+
+    /// Sets a custom header the client will send with every request, i.e.
+    /// one required by a proxy in front of unkey, or to override the default
+    /// `Accept` header.
+    ///
+    /// ## Note
+    /// The `Authorization` header is reserved for the root api key, use
+    /// [`Client::set_key`] to change it. Attempts to set it, or to set an
+    /// invalid header name or value, are logged and ignored.
+    ///
+    /// # Arguments
+    /// - `name`: The header name.
+    /// - `value`: The header value.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::Client;
+    /// let c = Client::new("unkey_ghj");
+    /// c.set_header("X-Request-Source", "billing-service");
+    /// ```
+    pub fn set_header(&self, name: &str, value: &str) {
+        self.http.set_header(name, value);
+    }
+
+    /// Sets a hook the client applies to every request just before it is
+    /// sent, i.e. to attach tracing headers or sign requests.
+    ///
+    /// ## Note
+    /// This is an escape hatch for advanced use cases, the hook runs after
+    /// all other headers and the payload have been set. When retrying, the
+    /// hook is applied once and the resulting request is resent.
+    ///
+    /// # Arguments
+    /// - `hook`: The hook to apply to each [`reqwest::RequestBuilder`].
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::Client;
+    /// let c = Client::new("unkey_ghj");
+    /// c.set_request_hook(|req| req.header("traceparent", "00-abc-01"));
+    /// ```
+    pub fn set_request_hook<F>(&self, hook: F)
+    where
+        F: Fn(reqwest::RequestBuilder) -> reqwest::RequestBuilder + Send + Sync + 'static,
+    {
+        self.http.set_request_hook(Some(Arc::new(hook)));
+    }
+
+    /// Sets whether the client runs in dry run mode, where requests are
+    /// logged instead of sent, i.e. to inspect payloads in ci.
+    ///
+    /// ## Note
+    /// Responses are not real in this mode. Every request logs its method,
+    /// full url and body at the info level, with any plaintext keys
+    /// redacted, then fails with an [`ErrorCode::DryRun`] error without
+    /// touching the network. Dry run mode is unavailable on
+    /// `wasm32-unknown-unknown`.
+    ///
+    /// [`ErrorCode::DryRun`]: crate::models::ErrorCode::DryRun
+    ///
+    /// # Arguments
+    /// - `enabled`: Whether dry run mode is enabled.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::Client;
+    /// # use unkey::LogLevel;
+    /// let c = Client::new("unkey_ghj");
+    /// Client::set_log_level(LogLevel::Info);
+    /// c.dry_run(true);
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn dry_run(&self, enabled: bool) {
+        self.http.set_dry_run(enabled);
+    }
+
+    /// The ratelimit headers unkey sent with the most recent response that
+    /// included them.
+    ///
+    /// ## Note
+    /// When the client is shared, this may come from a request made on
+    /// another task.
+    ///
+    /// # Returns
+    /// The ratelimit headers, if any response has included them.
     ///
     /// # Example
     /// ```no_run
     /// # async fn verify() {
     /// # use unkey::Client;
-    /// # use unkey::models::VerifyKeyRequest;
     /// let c = Client::new("abc123");
-    /// let req = VerifyKeyRequest::new("test_KEYABC", "api_123123");
+    /// let _ = c.verify_key_simple("test_123", "api_123").await;
     ///
-    /// match c.verify_key(req).await {
-    ///     Ok(res) => println!("{:?}", res),
-    ///     Err(err) => println!("{:?}", err),
+    /// if let Some(ratelimit) = c.last_ratelimit() {
+    ///     println!("{} requests remaining", ratelimit.remaining);
     /// }
     /// # }
     /// ```
-    pub async fn verify_key(&self, req: VerifyKeyRequest) -> Result<VerifyKeyResponse, HttpError> {
-        self.keys.verify_key(&self.http, req).await
+    #[must_use]
+    pub fn last_ratelimit(&self) -> Option<RatelimitHeaders> {
+        self.http.last_ratelimit()
     }
 
-    /// Creates a new api key.
+    /// Sets the log level for the whole crate at runtime.
+    ///
+    /// ## Note
+    /// By default the level is read from the `UNKEY_LOG` environment variable
+    /// (`debug`, `info`, or `error`). A level set here always takes
+    /// precedence over the environment variable, and applies to every client.
+    ///
+    /// Without the default `logging` feature, nothing is logged and this has
+    /// no effect.
     ///
     /// # Arguments
-    /// - `req`: The create key request to send.
+    /// - `level`: The new log level.
     ///
-    /// # Returns
-    /// A [`Result`] containing the response, or an error.
+    /// # Example
+    /// ```
+    /// # use unkey::Client;
+    /// # use unkey::LogLevel;
+    /// Client::set_log_level(LogLevel::Debug);
+    /// ```
+    pub fn set_log_level(level: LogLevel) {
+        logging::set_level(Some(level));
+    }
+
+    /// Sets the clock used for the whole crate, i.e. when computing key
+    /// expirations relative to now.
     ///
-    /// # Errors
-    /// The [`HttpError`], if one occurred.
+    /// ## Note
+    /// The system clock is used by default, but it is unavailable on
+    /// `wasm32-unknown-unknown`, where a clock should always be set, i.e.
+    /// one backed by `js_sys::Date::now`.
+    ///
+    /// # Arguments
+    /// - `clock`: Returns the current time, as the duration since the unix
+    ///   epoch.
     ///
     /// # Example
-    /// ```no_run
-    /// # async fn create() {
+    /// ```
+    /// # use std::time::{Duration, SystemTime, UNIX_EPOCH};
     /// # use unkey::Client;
-    /// # use unkey::models::CreateKeyRequest;
-    /// let c = Client::new("abc123");
-    /// let req = CreateKeyRequest::new("api_CCC").set_remaining(100);
+    /// Client::set_clock(|| {
+    ///     SystemTime::now()
+    ///         .duration_since(UNIX_EPOCH)
+    ///         .unwrap_or(Duration::ZERO)
+    /// });
+    /// ```
+    pub fn set_clock(clock: fn() -> Duration) {
+        clock::set(Some(clock));
+    }
+
+    /// Sets the maximum size of a keys serialized meta for this client,
+    /// i.e. to match a different unkey deployment.
     ///
-    /// match c.create_key(req).await {
-    ///     Ok(res) => println!("{:?}", res),
-    ///     Err(err) => println!("{:?}", err),
-    /// }
-    /// # }
+    /// ## Note
+    /// Create, update, and migrate key requests with larger meta are
+    /// rejected locally with [`ErrorCode::BadRequest`], without being sent.
+    /// The limit defaults to [`DEFAULT_META_SIZE_LIMIT`].
+    ///
+    /// [`DEFAULT_META_SIZE_LIMIT`]: crate::models::DEFAULT_META_SIZE_LIMIT
+    ///
+    /// # Arguments
+    /// - `limit`: The new limit, in bytes.
+    ///
+    /// # Example
     /// ```
-    pub async fn create_key(&self, req: CreateKeyRequest) -> Result<CreateKeyResponse, HttpError> {
-        self.keys.create_key(&self.http, req).await
+    /// # use unkey::Client;
+    /// let c = Client::new("unkey_ghj");
+    /// c.set_meta_size_limit(64 * 1024);
+    /// ```
+    pub fn set_meta_size_limit(&self, limit: usize) {
+        self.http.set_meta_size_limit(limit);
     }
 
-    /// Retrieves a paginated list of api keys.
+    /// Caches the information returned by [`Client::get_api`] in memory,
+    /// keyed by api id.
+    ///
+    /// ## Note
+    /// Requires the `cache` feature. Cached entries are dropped when the
+    /// api is updated or deleted through this client, but changes made
+    /// elsewhere are only seen once the entry expires.
     ///
     /// # Arguments
-    /// - `req`: The list keys request to send.
+    /// - `ttl`: How long each entry is served from the cache.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::time::Duration;
+    /// # use unkey::Client;
+    /// let c = Client::new("unkey_ghj").with_cache(Duration::from_secs(60));
+    /// ```
+    #[cfg(feature = "cache")]
+    #[must_use]
+    pub fn with_cache(mut self, ttl: Duration) -> Self {
+        self.cache = Some(ApiCache::new(ttl));
+        self
+    }
+
+    /// Verifies an existing api key.
+    ///
+    /// # Arguments
+    /// - `req`: The verify key request to send.
     ///
     /// # Returns
     /// A [`Result`] containing the response, or an error.
     ///
     /// # Errors
-    /// The [`HttpError`], if one occurred.
+    /// The [`Error`], if one occurred.
     ///
     /// # Example
     /// ```no_run
-    /// # async fn list() {
+    /// # async fn verify() {
     /// # use unkey::Client;
-    /// # use unkey::models::ListKeysRequest;
+    /// # use unkey::models::VerifyKeyRequest;
     /// let c = Client::new("abc123");
-    /// let req = ListKeysRequest::new("api_id").set_limit(25);
+    /// let req = VerifyKeyRequest::new("test_KEYABC", "api_123123");
     ///
-    /// match c.list_keys(req).await {
+    /// match c.verify_key(req).await {
     ///     Ok(res) => println!("{:?}", res),
     ///     Err(err) => println!("{:?}", err),
     /// }
     /// # }
     /// ```
-    pub async fn list_keys(&self, req: ListKeysRequest) -> Result<ListKeysResponse, HttpError> {
-        self.apis.list_keys(&self.http, req).await
+    pub async fn verify_key(&self, req: VerifyKeyRequest) -> Result<VerifyKeyResponse, Error> {
+        self.keys.verify_key(&self.http, req).await
     }
 
-    /// Revokes an existing api key.
+    /// Verifies an existing api key, authorizing this request only with a
+    /// different root api key.
+    ///
+    /// The client's own root api key is left unchanged, making this safe to
+    /// use concurrently across workspaces.
     ///
     /// # Arguments
-    /// - `req`: The revoke key request to send.
+    /// - `req`: The verify key request to send.
+    /// - `key`: The root api key to send with this request.
     ///
     /// # Returns
     /// A [`Result`] containing the response, or an error.
     ///
     /// # Errors
-    /// The [`HttpError`], if one occurred.
+    /// The [`Error`], if one occurred.
     ///
     /// # Example
     /// ```no_run
-    /// # async fn revoke() {
+    /// # async fn verify() {
     /// # use unkey::Client;
-    /// # use unkey::models::RevokeKeyRequest;
+    /// # use unkey::models::VerifyKeyRequest;
     /// let c = Client::new("abc123");
-    /// let req = RevokeKeyRequest::new("test_123");
+    /// let req = VerifyKeyRequest::new("test_KEYABC", "api_123123");
     ///
-    /// match c.revoke_key(req).await {
-    ///     Ok(_) => println!("Success!"), // Nothing on success
+    /// match c.verify_key_with_key(req, "def456").await {
+    ///     Ok(res) => println!("{:?}", res),
     ///     Err(err) => println!("{:?}", err),
     /// }
     /// # }
     /// ```
-    pub async fn revoke_key(&self, req: RevokeKeyRequest) -> Result<(), HttpError> {
-        self.keys.revoke_key(&self.http, req).await
+    pub async fn verify_key_with_key(
+        &self,
+        req: VerifyKeyRequest,
+        key: &str,
+    ) -> Result<VerifyKeyResponse, Error> {
+        self.keys.verify_key(&self.http.with_key(key), req).await
     }
 
-    /// Retrieves information for the given api id.
+    /// Verifies an existing api key without building a request first.
     ///
     /// # Arguments
-    /// - `req`: The get api request to send.
+    /// - `key`: The api key to verify.
+    /// - `api_id`: The id of the api this key belongs to.
     ///
     /// # Returns
     /// A [`Result`] containing the response, or an error.
     ///
     /// # Errors
-    /// The [`HttpError`], if one occurred.
+    /// The [`Error`], if one occurred.
     ///
     /// # Example
     /// ```no_run
-    /// # async fn get() {
+    /// # async fn verify() {
     /// # use unkey::Client;
-    /// # use unkey::models::GetApiRequest;
     /// let c = Client::new("abc123");
-    /// let req = GetApiRequest::new("api_id");
     ///
-    /// match c.get_api(req).await {
+    /// match c.verify_key_simple("test_KEYABC", "api_123123").await {
     ///     Ok(res) => println!("{:?}", res),
     ///     Err(err) => println!("{:?}", err),
     /// }
     /// # }
-    /// ````
-    pub async fn get_api(&self, req: GetApiRequest) -> Result<GetApiResponse, HttpError> {
-        self.apis.get_api(&self.http, req).await
+    /// ```
+    pub async fn verify_key_simple<T: Into<String>>(
+        &self,
+        key: T,
+        api_id: T,
+    ) -> Result<VerifyKeyResponse, Error> {
+        self.verify_key(VerifyKeyRequest::new(key, api_id)).await
     }
 
-    /// Permanently deletes an api and revokes all keys associated with it.
+    /// Verifies many api keys, sending up to `concurrency` requests at a
+    /// time, i.e. for bulk validation jobs.
+    ///
+    /// ## Note
+    /// Unkey has no bulk verify route, so each key is verified with an
+    /// individual request. A failure for one key does not stop the others.
     ///
     /// # Arguments
-    /// - `req`: The delete api request to send.
+    /// - `reqs`: The verify key requests to send.
+    /// - `concurrency`: The maximum number of requests in flight at once.
     ///
     /// # Returns
-    /// A [`Result`] containing the response, or an error.
+    /// The result of each request, in the same order as `reqs`, regardless
+    /// of the order they complete in.
     ///
-    /// # Errors
-    /// The [`HttpError`], if one occurred.
+    /// # Example
+    /// ```no_run
+    /// # async fn verify() {
+    /// # use unkey::Client;
+    /// # use unkey::models::VerifyKeyRequest;
+    /// let c = Client::new("abc123");
+    /// let reqs = vec![
+    ///     VerifyKeyRequest::new("test_KEY1", "api_123"),
+    ///     VerifyKeyRequest::new("test_KEY2", "api_123"),
+    /// ];
+    ///
+    /// for res in c.verify_keys(reqs, 5).await {
+    ///     match res {
+    ///         Ok(res) => println!("{:?}", res),
+    ///         Err(err) => println!("{:?}", err),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub async fn verify_keys(
+        &self,
+        reqs: Vec<VerifyKeyRequest>,
+        concurrency: usize,
+    ) -> Vec<Result<VerifyKeyResponse, Error>> {
+        self.keys.verify_keys(&self.http, reqs, concurrency).await
+    }
+
+    /// Creates a new api key.
+    ///
+    /// # Arguments
+    /// - `req`: The create key request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn create() {
+    /// # use unkey::Client;
+    /// # use unkey::models::CreateKeyRequest;
+    /// let c = Client::new("abc123");
+    /// let req = CreateKeyRequest::new("api_CCC").set_remaining(100);
+    ///
+    /// match c.create_key(req).await {
+    ///     Ok(res) => println!("{:?}", res),
+    ///     Err(err) => println!("{:?}", err),
+    /// }
+    /// # }
+    /// ```
+    pub async fn create_key(&self, req: CreateKeyRequest) -> Result<CreateKeyResponse, Error> {
+        self.keys.create_key(&self.http, req).await
+    }
+
+    /// Creates many new api keys, sending up to `concurrency` requests at
+    /// a time.
+    ///
+    /// ## Note
+    /// Unkey has no bulk create route, so each key is created with an
+    /// individual request. A failure for one key does not stop the others.
+    ///
+    /// # Arguments
+    /// - `reqs`: The create key requests to send.
+    /// - `concurrency`: The maximum number of requests in flight at once.
+    ///
+    /// # Returns
+    /// The result of each request, in the same order as `reqs`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn create() {
+    /// # use unkey::Client;
+    /// # use unkey::models::CreateKeyRequest;
+    /// let c = Client::new("abc123");
+    /// let reqs = vec![
+    ///     CreateKeyRequest::new("api_CCC").set_external_id("jonxslays"),
+    ///     CreateKeyRequest::new("api_CCC").set_external_id("unkey"),
+    /// ];
+    ///
+    /// for res in c.create_keys(reqs, 5).await {
+    ///     match res {
+    ///         Ok(res) => println!("{:?}", res),
+    ///         Err(err) => println!("{:?}", err),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub async fn create_keys(
+        &self,
+        reqs: Vec<CreateKeyRequest>,
+        concurrency: usize,
+    ) -> Vec<Result<CreateKeyResponse, Error>> {
+        self.keys.create_keys(&self.http, reqs, concurrency).await
+    }
+
+    /// Retrieves a paginated list of api keys.
+    ///
+    /// # Arguments
+    /// - `req`: The list keys request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn list() {
+    /// # use unkey::Client;
+    /// # use unkey::models::ListKeysRequest;
+    /// let c = Client::new("abc123");
+    /// let req = ListKeysRequest::new("api_id").set_limit(25);
+    ///
+    /// match c.list_keys(req).await {
+    ///     Ok(res) => println!("{:?}", res),
+    ///     Err(err) => println!("{:?}", err),
+    /// }
+    /// # }
+    /// ```
+    pub async fn list_keys(&self, req: ListKeysRequest) -> Result<ListKeysResponse, Error> {
+        self.apis.list_keys(&self.http, req).await
+    }
+
+    /// Retrieves every key for an api, following the pagination cursors as
+    /// the stream is consumed.
+    ///
+    /// ## Note
+    /// The stream ends after yielding the first error encountered.
+    ///
+    /// # Arguments
+    /// - `req`: The list keys request to send for the first page.
+    ///
+    /// # Returns
+    /// A stream of each key, or the error that ended it.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn list() {
+    /// # use futures_util::StreamExt;
+    /// # use unkey::Client;
+    /// # use unkey::models::ListKeysRequest;
+    /// let c = Client::new("abc123");
+    /// let keys = c.list_all_keys(ListKeysRequest::new("api_123"));
+    /// futures_util::pin_mut!(keys);
+    ///
+    /// while let Some(res) = keys.next().await {
+    ///     match res {
+    ///         Ok(key) => println!("{:?}", key),
+    ///         Err(err) => println!("{:?}", err),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn list_all_keys(
+        &self,
+        req: ListKeysRequest,
+    ) -> impl Stream<Item = Result<ApiKey, Error>> + '_ {
+        self.apis.list_all_keys(&self.http, req)
+    }
+
+    /// Deletes an existing api key, revoking it so it can no longer be
+    /// verified.
+    ///
+    /// # Arguments
+    /// - `req`: The delete key request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn delete() {
+    /// # use unkey::Client;
+    /// # use unkey::models::DeleteKeyRequest;
+    /// let c = Client::new("abc123");
+    /// let req = DeleteKeyRequest::new("test_123");
+    ///
+    /// match c.delete_key(req).await {
+    ///     Ok(_) => println!("Success!"), // Nothing on success
+    ///     Err(err) => println!("{:?}", err),
+    /// }
+    /// # }
+    /// ```
+    pub async fn delete_key(&self, req: DeleteKeyRequest) -> Result<(), Error> {
+        self.keys.delete_key(&self.http, req).await
+    }
+
+    /// Revokes an existing api key.
+    ///
+    /// # Arguments
+    /// - `req`: The revoke key request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    #[deprecated(since = "0.7.0", note = "use `Client::delete_key` instead")]
+    pub async fn revoke_key(&self, req: DeleteKeyRequest) -> Result<(), Error> {
+        self.delete_key(req).await
+    }
+
+    /// Revokes many existing api keys, sending up to 10 requests at a time.
+    ///
+    /// ## Note
+    /// Unkey has no bulk revoke route, so each key is revoked with an
+    /// individual request. A failure for one key does not stop the others.
+    ///
+    /// # Arguments
+    /// - `ids`: The ids of the keys to revoke.
+    ///
+    /// # Returns
+    /// Each key id paired with its result, in the same order as `ids`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn revoke() {
+    /// # use unkey::Client;
+    /// let c = Client::new("abc123");
+    /// let ids = vec![String::from("key_123"), String::from("key_456")];
+    ///
+    /// for (id, res) in c.revoke_keys(ids).await {
+    ///     match res {
+    ///         Ok(_) => println!("Revoked {id}"),
+    ///         Err(err) => println!("Failed to revoke {id}: {err}"),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub async fn revoke_keys(&self, ids: Vec<String>) -> Vec<(String, Result<(), Error>)> {
+        self.keys
+            .revoke_keys(&self.http, ids, REVOKE_CONCURRENCY)
+            .await
+    }
+
+    /// Retrieves information for the given api id.
+    ///
+    /// # Arguments
+    /// - `req`: The get api request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn get() {
+    /// # use unkey::Client;
+    /// # use unkey::models::GetApiRequest;
+    /// let c = Client::new("abc123");
+    /// let req = GetApiRequest::new("api_id");
+    ///
+    /// match c.get_api(req).await {
+    ///     Ok(res) => println!("{:?}", res),
+    ///     Err(err) => println!("{:?}", err),
+    /// }
+    /// # }
+    /// ````
+    pub async fn get_api(&self, req: GetApiRequest) -> Result<GetApiResponse, Error> {
+        #[cfg(feature = "cache")]
+        if let Some(cache) = &self.cache {
+            if let Some(res) = cache.get(&req.api_id) {
+                return Ok(res);
+            }
+
+            let res = self.apis.get_api(&self.http, req).await?;
+            cache.insert(res.clone());
+            return Ok(res);
+        }
+
+        self.apis.get_api(&self.http, req).await
+    }
+
+    /// Permanently deletes an api and revokes all keys associated with it.
+    ///
+    /// # Arguments
+    /// - `req`: The delete api request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
     ///
     /// # Example
     /// ```no_run
@@ -281,113 +1025,1366 @@ impl Client {
     /// }
     /// # }
     /// ````
-    pub async fn delete_api(&self, req: DeleteApiRequest) -> Result<(), HttpError> {
-        self.apis.delete_api(&self.http, req).await
+    pub async fn delete_api(&self, req: DeleteApiRequest) -> Result<(), Error> {
+        #[cfg(feature = "cache")]
+        let api_id = req.api_id.clone();
+        let res = self.apis.delete_api(&self.http, req).await;
+
+        #[cfg(feature = "cache")]
+        self.invalidate_api(&api_id);
+
+        res
     }
 
-    /// Retrieves information for the given api id.
+    /// Retrieves a paginated list of apis in the workspace.
     ///
     /// # Arguments
-    /// - `req`: The get api request to send.
+    /// - `req`: The list apis request to send.
     ///
     /// # Returns
     /// A [`Result`] containing the response, or an error.
     ///
     /// # Errors
-    /// The [`HttpError`], if one occurred.
+    /// The [`Error`], if one occurred.
     ///
     /// # Example
     /// ```no_run
-    /// # async fn get() {
+    /// # async fn list() {
     /// # use unkey::Client;
-    /// # use unkey::models::UpdateKeyRequest;
+    /// # use unkey::models::ListApisRequest;
     /// let c = Client::new("abc123");
-    /// let req = UpdateKeyRequest::new("api_id").set_remaining(Some(100));
+    /// let req = ListApisRequest::new().set_limit(25);
     ///
-    /// match c.update_key(req).await {
-    ///     Ok(_) => println!("Success"), // Nothing on success
+    /// match c.list_apis(req).await {
+    ///     Ok(res) => println!("{:?}", res),
     ///     Err(err) => println!("{:?}", err),
     /// }
     /// # }
-    /// ````
-    pub async fn update_key(&self, req: UpdateKeyRequest) -> Result<(), HttpError> {
-        self.keys.update_key(&self.http, req).await
+    /// ```
+    pub async fn list_apis(&self, req: ListApisRequest) -> Result<ListApisResponse, Error> {
+        self.apis.list_apis(&self.http, req).await
     }
 
-    /// Retrieves information for the given api id.
+    /// Retrieves every api in the workspace, following the pagination
+    /// cursors as the stream is consumed.
+    ///
+    /// ## Note
+    /// The stream ends after yielding the first error encountered.
     ///
     /// # Arguments
-    /// - `req`: The get key request to send.
+    /// - `req`: The list apis request to send for the first page.
+    ///
+    /// # Returns
+    /// A stream of each api, or the error that ended it.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn list() {
+    /// # use futures_util::StreamExt;
+    /// # use unkey::Client;
+    /// # use unkey::models::ListApisRequest;
+    /// let c = Client::new("abc123");
+    /// let apis = c.list_all_apis(ListApisRequest::new());
+    /// futures_util::pin_mut!(apis);
+    ///
+    /// while let Some(res) = apis.next().await {
+    ///     match res {
+    ///         Ok(api) => println!("{:?}", api),
+    ///         Err(err) => println!("{:?}", err),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn list_all_apis(
+        &self,
+        req: ListApisRequest,
+    ) -> impl Stream<Item = Result<GetApiResponse, Error>> + '_ {
+        self.apis.list_all_apis(&self.http, req)
+    }
+
+    /// Updates an existing api.
+    ///
+    /// # Arguments
+    /// - `req`: The update api request to send.
     ///
     /// # Returns
     /// A [`Result`] containing the response, or an error.
     ///
     /// # Errors
-    /// The [`HttpError`], if one occurred.
+    /// The [`Error`], if one occurred.
     ///
     /// # Example
     /// ```no_run
-    /// # async fn get() {
+    /// # async fn update() {
     /// # use unkey::Client;
-    /// # use unkey::models::GetKeyRequest;
+    /// # use unkey::models::UpdateApiRequest;
     /// let c = Client::new("abc123");
-    /// let req = GetKeyRequest::new("key_id");
+    /// let req = UpdateApiRequest::new("api_id").set_name(Some("new_name"));
     ///
-    /// match c.get_key(req).await {
-    ///     Ok(res) => println!("{:?}", res),
+    /// match c.update_api(req).await {
+    ///     Ok(_) => println!("Success"), // Nothing on success
     ///     Err(err) => println!("{:?}", err),
     /// }
     /// # }
     /// ````
-    pub async fn get_key(&self, req: GetKeyRequest) -> Result<ApiKey, HttpError> {
-        self.keys.get_key(&self.http, req).await
+    pub async fn update_api(&self, req: UpdateApiRequest) -> Result<(), Error> {
+        #[cfg(feature = "cache")]
+        let api_id = req.api_id.clone();
+        let res = self.apis.update_api(&self.http, req).await;
+
+        #[cfg(feature = "cache")]
+        self.invalidate_api(&api_id);
+
+        res
     }
 
-    /// Update the remaining verifications for a key.
+    /// Removes the cached information for the api, if any.
     ///
     /// # Arguments
-    /// - `req`: The update remaining request to send.
+    /// - `api_id`: The id of the api.
+    #[cfg(feature = "cache")]
+    fn invalidate_api(&self, api_id: &str) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate(api_id);
+        }
+    }
+
+    /// Retrieves information for the given api id.
+    ///
+    /// # Arguments
+    /// - `req`: The get api request to send.
     ///
     /// # Returns
     /// A [`Result`] containing the response, or an error.
     ///
     /// # Errors
-    /// The [`HttpError`], if one occurred.
+    /// The [`Error`], if one occurred. Requests without any changes are
+    /// rejected locally with [`ErrorCode::BadRequest`].
+    ///
+    /// [`ErrorCode::BadRequest`]: crate::models::ErrorCode::BadRequest
     ///
     /// # Example
     /// ```no_run
     /// # async fn get() {
     /// # use unkey::Client;
-    /// # use unkey::models::UpdateRemainingRequest;
-    /// # use unkey::models::UpdateOp;
+    /// # use unkey::models::UpdateKeyRequest;
     /// let c = Client::new("abc123");
-    /// let req = UpdateRemainingRequest::new("key_id", Some(100), UpdateOp::Set);
+    /// let req = UpdateKeyRequest::new("api_id").set_remaining(Some(100));
     ///
-    /// match c.update_remaining(req).await {
-    ///     Ok(res) => println!("{:?}", res),
+    /// match c.update_key(req).await {
+    ///     Ok(_) => println!("Success"), // Nothing on success
     ///     Err(err) => println!("{:?}", err),
     /// }
     /// # }
     /// ````
-    pub async fn update_remaining(
-        &self,
-        req: UpdateRemainingRequest,
-    ) -> Result<UpdateRemainingResponse, HttpError> {
+    pub async fn update_key(&self, req: UpdateKeyRequest) -> Result<(), Error> {
+        self.keys.update_key(&self.http, req).await
+    }
+
+    /// Migrates an existing hashed key into unkey, i.e. when moving from
+    /// another key management system without rotating keys.
+    ///
+    /// # Arguments
+    /// - `req`: The migrate key request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn migrate() {
+    /// # use unkey::Client;
+    /// # use unkey::models::KeyHash;
+    /// # use unkey::models::MigrateKeyRequest;
+    /// let c = Client::new("abc123");
+    /// let hash = KeyHash::sha256_base64("n4bQgYhMfWWaL+qgxVrQFaO/TxsrC4Is0V1sFbDwCgg=");
+    /// let req = MigrateKeyRequest::new("api_123", hash).set_start("sk_te");
+    ///
+    /// match c.migrate_key(req).await {
+    ///     Ok(res) => println!("{}", res.key_id),
+    ///     Err(err) => println!("{:?}", err),
+    /// }
+    /// # }
+    /// ````
+    pub async fn migrate_key(&self, req: MigrateKeyRequest) -> Result<MigrateKeyResponse, Error> {
+        self.keys.migrate_key(&self.http, req).await
+    }
+
+    /// Retrieves information for the given api id.
+    ///
+    /// # Arguments
+    /// - `req`: The get key request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn get() {
+    /// # use unkey::Client;
+    /// # use unkey::models::GetKeyRequest;
+    /// let c = Client::new("abc123");
+    /// let req = GetKeyRequest::new("key_id");
+    ///
+    /// match c.get_key(req).await {
+    ///     Ok(res) => println!("{:?}", res),
+    ///     Err(err) => println!("{:?}", err),
+    /// }
+    /// # }
+    /// ````
+    pub async fn get_key(&self, req: GetKeyRequest) -> Result<ApiKey, Error> {
+        self.keys.get_key(&self.http, req).await
+    }
+
+    /// Update the remaining verifications for a key.
+    ///
+    /// # Arguments
+    /// - `req`: The update remaining request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn get() {
+    /// # use unkey::Client;
+    /// # use unkey::models::UpdateRemainingRequest;
+    /// # use unkey::models::UpdateOp;
+    /// let c = Client::new("abc123");
+    /// let req = UpdateRemainingRequest::new("key_id", Some(100), UpdateOp::Set);
+    ///
+    /// match c.update_remaining(req).await {
+    ///     Ok(res) => println!("{:?}", res),
+    ///     Err(err) => println!("{:?}", err),
+    /// }
+    /// # }
+    /// ````
+    pub async fn update_remaining(
+        &self,
+        req: UpdateRemainingRequest,
+    ) -> Result<UpdateRemainingResponse, Error> {
         self.keys.update_remaining(&self.http, req).await
     }
+
+    /// Retrieves the verification usage numbers for a key or owner.
+    ///
+    /// # Arguments
+    /// - `req`: The get usage numbers request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred. Requests without a key id or
+    /// owner id fail locally with [`crate::models::ErrorCode::BadRequest`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn get() {
+    /// # use unkey::Client;
+    /// # use unkey::models::GetUsageNumbersRequest;
+    /// let c = Client::new("abc123");
+    /// let req = GetUsageNumbersRequest::new().set_key_id("key_id");
+    ///
+    /// match c.get_verifications(req).await {
+    ///     Ok(res) => println!("{:?}", res),
+    ///     Err(err) => println!("{:?}", err),
+    /// }
+    /// # }
+    /// ```
+    pub async fn get_verifications(
+        &self,
+        req: GetUsageNumbersRequest,
+    ) -> Result<GetUsageNumbersResponse, Error> {
+        self.keys.get_verifications(&self.http, req).await
+    }
+
+    /// Adds permissions to an existing key.
+    ///
+    /// # Arguments
+    /// - `req`: The add permissions request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn add() {
+    /// # use unkey::Client;
+    /// # use unkey::models::AddPermissionsRequest;
+    /// let c = Client::new("abc123");
+    /// let req = AddPermissionsRequest::new("key_123", &["keys.read"]);
+    ///
+    /// match c.add_permissions(req).await {
+    ///     Ok(_) => println!("Success!"), // Nothing on success
+    ///     Err(err) => println!("{:?}", err),
+    /// }
+    /// # }
+    /// ```
+    pub async fn add_permissions(&self, req: AddPermissionsRequest) -> Result<(), Error> {
+        self.keys.add_permissions(&self.http, req).await
+    }
+
+    /// Removes permissions from an existing key.
+    ///
+    /// # Arguments
+    /// - `req`: The remove permissions request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn remove() {
+    /// # use unkey::Client;
+    /// # use unkey::models::RemovePermissionsRequest;
+    /// let c = Client::new("abc123");
+    /// let req = RemovePermissionsRequest::new("key_123", &["keys.write"]);
+    ///
+    /// match c.remove_permissions(req).await {
+    ///     Ok(_) => println!("Success!"), // Nothing on success
+    ///     Err(err) => println!("{:?}", err),
+    /// }
+    /// # }
+    /// ```
+    pub async fn remove_permissions(&self, req: RemovePermissionsRequest) -> Result<(), Error> {
+        self.keys.remove_permissions(&self.http, req).await
+    }
+
+    /// Applies a standalone ratelimit to an identifier.
+    ///
+    /// # Arguments
+    /// - `req`: The ratelimit request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn limit() {
+    /// # use unkey::Client;
+    /// # use unkey::models::RatelimitRequest;
+    /// let c = Client::new("abc123");
+    /// let req = RatelimitRequest::new("email.outbound", "user_123", 10, 60000);
+    ///
+    /// match c.limit(req).await {
+    ///     Ok(res) => println!("{:?}", res),
+    ///     Err(err) => println!("{:?}", err),
+    /// }
+    /// # }
+    /// ```
+    pub async fn limit(&self, req: RatelimitRequest) -> Result<RatelimitResponse, Error> {
+        self.ratelimits.limit(&self.http, req).await
+    }
+
+    /// Creates a new identity.
+    ///
+    /// # Arguments
+    /// - `req`: The create identity request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn create() {
+    /// # use unkey::Client;
+    /// # use unkey::models::CreateIdentityRequest;
+    /// let c = Client::new("abc123");
+    /// let req = CreateIdentityRequest::new("user_123");
+    ///
+    /// match c.create_identity(req).await {
+    ///     Ok(res) => println!("{:?}", res),
+    ///     Err(err) => println!("{:?}", err),
+    /// }
+    /// # }
+    /// ```
+    pub async fn create_identity(
+        &self,
+        req: CreateIdentityRequest,
+    ) -> Result<CreateIdentityResponse, Error> {
+        self.identities.create_identity(&self.http, req).await
+    }
+
+    /// Retrieves an identity by its id or external id.
+    ///
+    /// # Arguments
+    /// - `req`: The get identity request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the identity, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn get() {
+    /// # use unkey::Client;
+    /// # use unkey::models::GetIdentityRequest;
+    /// let c = Client::new("abc123");
+    /// let req = GetIdentityRequest::with_external_id("user_123");
+    ///
+    /// match c.get_identity(req).await {
+    ///     Ok(res) => println!("{:?}", res),
+    ///     Err(err) => println!("{:?}", err),
+    /// }
+    /// # }
+    /// ```
+    pub async fn get_identity(&self, req: GetIdentityRequest) -> Result<Identity, Error> {
+        self.identities.get_identity(&self.http, req).await
+    }
+
+    /// Retrieves a paginated list of identities in the workspace.
+    ///
+    /// # Arguments
+    /// - `req`: The list identities request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn list() {
+    /// # use unkey::Client;
+    /// # use unkey::models::ListIdentitiesRequest;
+    /// let c = Client::new("abc123");
+    /// let req = ListIdentitiesRequest::new().set_limit(25);
+    ///
+    /// match c.list_identities(req).await {
+    ///     Ok(res) => println!("{:?}", res),
+    ///     Err(err) => println!("{:?}", err),
+    /// }
+    /// # }
+    /// ```
+    pub async fn list_identities(
+        &self,
+        req: ListIdentitiesRequest,
+    ) -> Result<ListIdentitiesResponse, Error> {
+        self.identities.list_identities(&self.http, req).await
+    }
+
+    /// Permanently deletes an identity.
+    ///
+    /// # Arguments
+    /// - `req`: The delete identity request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] indicating whether the identity was deleted.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn delete() {
+    /// # use unkey::Client;
+    /// # use unkey::models::DeleteIdentityRequest;
+    /// let c = Client::new("abc123");
+    /// let req = DeleteIdentityRequest::new("id_123");
+    ///
+    /// match c.delete_identity(req).await {
+    ///     Ok(_) => println!("Success!"), // Nothing on success
+    ///     Err(err) => println!("{:?}", err),
+    /// }
+    /// # }
+    /// ```
+    pub async fn delete_identity(&self, req: DeleteIdentityRequest) -> Result<(), Error> {
+        self.identities.delete_identity(&self.http, req).await
+    }
+
+    /// Checks connectivity to the unkey api and the validity of the root
+    /// key, e.g. for readiness probes.
+    ///
+    /// ## Note
+    /// Unkey has no dedicated health route, so this lists a single api,
+    /// which unkey rejects with [`ErrorCode::Unauthorized`] for an invalid
+    /// root key. The root key therefore needs permission to read apis.
+    ///
+    /// # Returns
+    /// A [`Result`] indicating whether the api is reachable and the root key
+    /// was accepted.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn health() {
+    /// # use unkey::Client;
+    /// let c = Client::new("abc123");
+    ///
+    /// match c.health().await {
+    ///     Ok(_) => println!("Ready!"),
+    ///     Err(err) => println!("{:?}", err),
+    /// }
+    /// # }
+    /// ```
+    pub async fn health(&self) -> Result<(), Error> {
+        let mut route = routes::LIST_APIS.compile();
+        route.query_insert("limit", "1");
+
+        parse_empty_response(fetch!(self.http, route).await).await
+    }
+
+    /// Sends a request to an arbitrary endpoint, i.e. one unkey has added
+    /// that this sdk doesn't support yet.
+    ///
+    /// ## Note
+    /// This is an escape hatch, prefer the dedicated methods where they
+    /// exist. The request is sent with the same headers, hooks and retry
+    /// policy as any other, but only idempotent methods are retried.
+    ///
+    /// # Arguments
+    /// - `method`: The http method to use.
+    /// - `path`: The path of the endpoint, relative to the base url.
+    /// - `body`: The optional json body to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn raw() {
+    /// # use unkey::Client;
+    /// # use reqwest::Method;
+    /// # use serde_json::{json, Value};
+    /// let c = Client::new("abc123");
+    /// let body = json!({"identityId": "id_123"});
+    ///
+    /// match c.raw::<Value, _>(Method::POST, "/identities.getIdentity", Some(body)).await {
+    ///     Ok(res) => println!("{res}"),
+    ///     Err(err) => println!("{:?}", err),
+    /// }
+    /// # }
+    /// ```
+    pub async fn raw<T, B>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<B>,
+    ) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+        B: std::fmt::Debug + Serialize,
+    {
+        let route = CompiledRoute::custom(method, path);
+
+        parse_response(self.http.fetch(route, body).await).await
+    }
+}
+
+/// A builder used to configure and create a [`Client`].
+///
+/// ## Note
+/// The root api key is required, and should always be set using
+/// [`ClientBuilder::key`]. If it is not set, the client is built with an
+/// empty key and every request will fail authorization.
+#[derive(Debug, Clone, Default)]
+pub struct ClientBuilder {
+    /// The root api key the client should send with requests.
+    key: Option<String>,
+
+    /// The base url to use, excluding trailing slash.
+    url: Option<String>,
+
+    /// The api version to use, if no base url is set.
+    version: Option<ApiVersion>,
+
+    /// The timeout for each request.
+    timeout: Option<Duration>,
+
+    /// The policy for retrying failed requests.
+    retry_policy: Option<RetryPolicy>,
+
+    /// The user agent to send with requests.
+    user_agent: Option<String>,
+
+    /// The proxy url to route all requests through.
+    proxy: Option<String>,
+
+    /// The maximum number of idle connections kept per host.
+    pool_max_idle_per_host: Option<usize>,
+
+    /// How long idle connections are kept in the pool.
+    pool_idle_timeout: Option<Duration>,
+}
+
+impl ClientBuilder {
+    /// Sets the root api key the client should send with requests.
+    ///
+    /// # Arguments
+    /// - `key`: The root api key to use.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    #[must_use]
+    pub fn key<T: Into<String>>(mut self, key: T) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Sets the base url the client will send requests to.
+    ///
+    /// # Arguments
+    /// - `url`: The base url to use, excluding trailing slash.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    #[must_use]
+    pub fn url<T: Into<String>>(mut self, url: T) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// Sets the api version the client will use.
+    ///
+    /// A base url set with [`ClientBuilder::url`] takes precedence.
+    ///
+    /// # Arguments
+    /// - `version`: The [`ApiVersion`] to use.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    #[must_use]
+    pub fn version(mut self, version: ApiVersion) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Sets the timeout for each request.
+    ///
+    /// ## Note
+    /// This is ignored on wasm, where the browser controls timeouts.
+    ///
+    /// # Arguments
+    /// - `timeout`: The timeout to use.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the policy for retrying failed idempotent requests.
+    ///
+    /// # Arguments
+    /// - `policy`: The [`RetryPolicy`] to use.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    #[must_use]
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Sets the user agent to send with requests.
+    ///
+    /// # Arguments
+    /// - `user_agent`: The user agent to use.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    #[must_use]
+    pub fn user_agent<T: Into<String>>(mut self, user_agent: T) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sets the proxy url to route all requests through.
+    ///
+    /// ## Note
+    /// This is ignored on wasm, where the browser controls proxies.
+    ///
+    /// # Arguments
+    /// - `url`: The proxy url to use, i.e. `http://proxy.internal:8080`.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    #[must_use]
+    pub fn proxy<T: Into<String>>(mut self, url: T) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    /// Sets the maximum number of idle connections kept open per host.
+    ///
+    /// Defaults to reqwest's default, which keeps every idle connection.
+    /// Lowering it bounds the sockets held open after bursts of concurrent
+    /// requests.
+    ///
+    /// ## Note
+    /// This is ignored on wasm, where the browser manages connections.
+    ///
+    /// # Arguments
+    /// - `max`: The maximum number of idle connections per host.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    #[must_use]
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Sets how long idle connections are kept open in the pool.
+    ///
+    /// Defaults to reqwest's default of 90 seconds.
+    ///
+    /// ## Note
+    /// This is ignored on wasm, where the browser manages connections.
+    ///
+    /// # Arguments
+    /// - `timeout`: How long to keep idle connections.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    #[must_use]
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Builds the configured client.
+    ///
+    /// # Returns
+    /// The new client.
+    ///
+    /// # Panics
+    /// If the key is not a valid header value, or the underlying http
+    /// client fails to build, i.e. due to an invalid proxy url. Use
+    /// [`ClientBuilder::try_build`] to handle the error instead.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::time::Duration;
+    /// # use unkey::Client;
+    /// # use unkey::models::RetryPolicy;
+    /// let c = Client::builder()
+    ///     .key("unkey_ghj")
+    ///     .url("http://localhost:3000")
+    ///     .timeout(Duration::from_secs(10))
+    ///     .retry_policy(RetryPolicy::default())
+    ///     .user_agent("my-service")
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn build(self) -> Client {
+        self.try_build()
+            .expect("the client configuration must be valid")
+    }
+
+    /// Builds the configured client, failing if the http client can't be
+    /// built.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the new client, or an error.
+    ///
+    /// # Errors
+    /// An [`Error::Api`] with [`ErrorCode::BadRequest`] if the proxy url or
+    /// the key is invalid, or [`Error::Network`] if the http client fails to build.
+    ///
+    /// [`ErrorCode::BadRequest`]: crate::models::ErrorCode::BadRequest
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::Client;
+    /// # use unkey::models::ErrorCode;
+    /// let c = Client::builder()
+    ///     .key("unkey_ghj")
+    ///     .proxy("http://localhost:8080")
+    ///     .try_build();
+    ///
+    /// assert!(c.is_ok());
+    ///
+    /// let c = Client::builder().proxy("not a url").try_build();
+    ///
+    /// assert_eq!(c.unwrap_err().code(), ErrorCode::BadRequest);
+    /// ```
+    pub fn try_build(self) -> Result<Client, Error> {
+        let client = self.build_http_client()?;
+
+        self.finish(client)
+    }
+
+    /// Builds the underlying http client from the configured options.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn build_http_client(&self) -> Result<reqwest::Client, Error> {
+        let mut builder = reqwest::Client::builder();
+
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+
+        if let Some(proxy) = &self.proxy {
+            let proxy = reqwest::Proxy::all(proxy).map_err(|e| {
+                HttpError::new(ErrorCode::BadRequest, format!("Invalid proxy url: {e}"))
+            })?;
+
+            builder = builder.proxy(proxy);
+        }
+
+        builder.build().map_err(Error::from)
+    }
+
+    /// Builds the underlying http client from the configured options.
+    ///
+    /// The browser controls timeouts, proxies and connection pooling on
+    /// wasm, so they are ignored.
+    #[cfg(target_arch = "wasm32")]
+    fn build_http_client(&self) -> Result<reqwest::Client, Error> {
+        if self.timeout.is_some() || self.proxy.is_some() {
+            logging::error!(String::from("Timeouts and proxies are unsupported on wasm"));
+        }
+
+        if self.pool_max_idle_per_host.is_some() || self.pool_idle_timeout.is_some() {
+            logging::error!(String::from(
+                "Connection pool settings are unsupported on wasm"
+            ));
+        }
+
+        reqwest::Client::builder().build().map_err(Error::from)
+    }
+
+    /// Creates the client using the given http client.
+    fn finish(self, client: reqwest::Client) -> Result<Client, Error> {
+        let key = self.key.unwrap_or_default();
+        let url = self.url.unwrap_or_else(|| match self.version {
+            Some(version) => version.base_url(),
+            None => HttpService::default_url(),
+        });
+
+        let mut http = HttpService::try_with_client(&key, &url, client)?;
+        http.set_retry_policy(self.retry_policy);
+
+        if let Some(user_agent) = &self.user_agent {
+            http.set_user_agent(user_agent);
+        }
+
+        Ok(Client::from_http(http))
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::mock::block_on;
+    use crate::mock::body;
+    use crate::mock::env_lock;
+    use crate::mock::header;
+    use crate::mock::MockServer;
+    use crate::models::ApiVersion;
+    use crate::models::DeleteKeyRequest;
+    use crate::models::Error;
+    use crate::models::ErrorCode;
+    use crate::models::GetApiRequest;
+    use crate::models::RetryPolicy;
+    use crate::models::VerifyKeyRequest;
     use crate::services::ApiService;
+    use crate::services::IdentityService;
     use crate::services::KeyService;
+    use crate::services::RatelimitService;
     use crate::Client;
 
+    #[test]
+    fn try_new_with_invalid_key() {
+        let err = Client::try_new("unkey_abc\ndef").unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::BadRequest);
+        assert!(Client::try_new("unkey_abc").is_ok());
+    }
+
+    #[test]
+    fn mock_client_is_not_routable() {
+        let c = Client::mock();
+
+        assert_eq!(c.http.url(), "http://0.0.0.0:0");
+
+        let err = block_on(c.health()).unwrap_err();
+
+        assert!(matches!(err, Error::Network(_)), "{err:?}");
+    }
+
+    #[test]
+    fn try_build_with_invalid_key() {
+        let err = Client::builder().key("unkey_\n").try_build().unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::BadRequest);
+    }
+
+    #[test]
+    #[cfg(feature = "cache")]
+    fn get_api_uses_cache() {
+        let server = MockServer::new(vec![(
+            200,
+            r#"{"id":"api_123","name":"test","workspaceId":"ws_123"}"#,
+        )]);
+        let c = Client::with_url("unkey_abc", &server.url).with_cache(Duration::from_secs(60));
+
+        let first = block_on(c.get_api(GetApiRequest::new("api_123"))).unwrap();
+        let second = block_on(c.get_api(GetApiRequest::new("api_123"))).unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(server.attempts(), 1);
+
+        block_on(c.get_api(GetApiRequest::new("api_456"))).unwrap();
+        assert_eq!(server.attempts(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "cache")]
+    fn delete_api_invalidates_cache() {
+        let server = MockServer::new(vec![
+            (
+                200,
+                r#"{"id":"api_123","name":"test","workspaceId":"ws_123"}"#,
+            ),
+            (200, "{}"),
+            (
+                200,
+                r#"{"id":"api_123","name":"test","workspaceId":"ws_123"}"#,
+            ),
+        ]);
+        let c = Client::with_url("unkey_abc", &server.url).with_cache(Duration::from_secs(60));
+
+        block_on(c.get_api(GetApiRequest::new("api_123"))).unwrap();
+        let req = crate::models::DeleteApiRequest::new("api_123");
+        block_on(c.delete_api(req)).unwrap();
+        block_on(c.get_api(GetApiRequest::new("api_123"))).unwrap();
+
+        assert_eq!(server.attempts(), 3);
+    }
+
+    #[test]
+    fn builder_with_all_options() {
+        let server = MockServer::new(vec![
+            (500, "{}"),
+            (
+                200,
+                r#"{"id":"api_123","name":"test","workspaceId":"ws_123"}"#,
+            ),
+        ]);
+        let policy = RetryPolicy::new(3, Duration::from_millis(1), Duration::from_millis(5), false);
+        let c = Client::builder()
+            .key("unkey_abc")
+            .url(&server.url)
+            .timeout(Duration::from_secs(5))
+            .retry_policy(policy)
+            .user_agent("my-service")
+            .build();
+
+        let res = block_on(c.get_api(GetApiRequest::new("api_123"))).unwrap();
+        let request = &server.requests()[0];
+
+        assert_eq!(res.api_id, String::from("api_123"));
+        assert_eq!(server.attempts(), 2);
+        assert_eq!(
+            header(request, "authorization").unwrap(),
+            "Bearer unkey_abc"
+        );
+        assert_eq!(header(request, "x-user-agent").unwrap(), "my-service");
+    }
+
+    #[test]
+    fn builder_with_proxy() {
+        let _env = env_lock();
+        let c = Client::builder()
+            .key("unkey_abc")
+            .proxy("http://localhost:8080")
+            .build();
+
+        assert_eq!(c.http.url(), "https://api.unkey.dev/v1");
+    }
+
+    #[test]
+    fn builder_with_pool_settings() {
+        let c = Client::builder()
+            .key("unkey_abc")
+            .pool_max_idle_per_host(4)
+            .pool_idle_timeout(Duration::from_secs(30))
+            .try_build();
+
+        assert!(c.is_ok());
+    }
+
+    #[test]
+    fn builder_with_invalid_proxy() {
+        let _env = env_lock();
+        let err = Client::builder()
+            .proxy("not a url")
+            .try_build()
+            .unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::BadRequest);
+    }
+
+    #[test]
+    #[should_panic(expected = "the client configuration must be valid")]
+    fn builder_build_panics_with_invalid_proxy() {
+        let _env = env_lock();
+        let _ = Client::builder().proxy("not a url").build();
+    }
+
+    #[test]
+    fn set_header_is_sent() {
+        let server = MockServer::new(vec![(200, "{}")]);
+        let c = Client::with_url("unkey_abc", &server.url);
+        c.set_header("X-Request-Source", "billing");
+        c.set_header("Authorization", "Bearer nope");
+
+        block_on(c.delete_key(DeleteKeyRequest::new("key_123"))).unwrap();
+        let request = &server.requests()[0];
+
+        assert_eq!(header(request, "x-request-source").unwrap(), "billing");
+        assert_eq!(
+            header(request, "authorization").unwrap(),
+            "Bearer unkey_abc"
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn delete_and_revoke_key_share_route() {
+        use crate::models::RevokeKeyRequest;
+
+        let server = MockServer::new(vec![(200, "{}")]);
+        let c = Client::with_url("unkey_abc", &server.url);
+
+        block_on(c.delete_key(DeleteKeyRequest::new("key_1"))).unwrap();
+        block_on(c.revoke_key(RevokeKeyRequest::new("key_2"))).unwrap();
+        let requests = server.requests();
+
+        assert!(requests[0].starts_with("POST /keys.deleteKey "));
+        assert!(requests[1].starts_with("POST /keys.deleteKey "));
+        assert_eq!(body(&requests[0]), r#"{"keyId":"key_1"}"#);
+        assert_eq!(body(&requests[1]), r#"{"keyId":"key_2"}"#);
+    }
+
+    #[test]
+    fn set_meta_size_limit_is_per_client() {
+        use crate::models::CreateKeyRequest;
+
+        let server = MockServer::new(vec![(200, r#"{"key":"test_abc","keyId":"key_123"}"#)]);
+        let small = Client::with_url("unkey_abc", &server.url);
+        let c = Client::with_url("unkey_abc", &server.url);
+        small.set_meta_size_limit(8);
+        let req = CreateKeyRequest::new("api_123").set_meta(serde_json::json!({"plan": "pro"}));
+
+        let err = block_on(small.create_key(req.clone())).unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::BadRequest);
+        assert_eq!(server.attempts(), 0);
+
+        block_on(c.create_key(req)).unwrap();
+
+        assert_eq!(server.attempts(), 1);
+    }
+
+    #[test]
+    fn set_request_hook() {
+        let server = MockServer::new(vec![(200, "{}")]);
+        let c = Client::with_url("unkey_abc", &server.url);
+        c.set_request_hook(|req| req.header("X-Signature", "signed"));
+
+        block_on(c.delete_key(DeleteKeyRequest::new("key_123"))).unwrap();
+        let request = &server.requests()[0];
+
+        assert_eq!(header(request, "x-signature").unwrap(), "signed");
+    }
+
+    #[test]
+    #[cfg(feature = "logging")]
+    fn dry_run_logs_without_sending() {
+        use crate::logging;
+        use crate::LogLevel;
+
+        let _env = env_lock();
+        let server = MockServer::new(vec![(200, r#"{"valid":true}"#)]);
+        let c = Client::with_url("unkey_abc", &server.url);
+        c.dry_run(true);
+
+        logging::set_level(Some(LogLevel::Info));
+        let mut res = None;
+        let lines = logging::capture(|| {
+            res = Some(block_on(c.verify_key_simple("test_abc", "api_123")));
+        });
+        logging::set_level(None);
+
+        let err = res.unwrap().unwrap_err();
+        let expected = format!(
+            r#"DRY RUN : POST {}/keys.verifyKey {{"key":"***","apiId":"api_123"}}"#,
+            server.url
+        );
+
+        assert_eq!(err.code(), ErrorCode::DryRun);
+        assert_eq!(server.attempts(), 0);
+        assert!(lines.iter().any(|l| l.ends_with(&expected)), "{lines:?}");
+    }
+
+    #[test]
+    fn raw_custom_endpoint() {
+        let server = MockServer::new(vec![(200, r#"{"id":"id_123","externalId":"user_123"}"#)]);
+        let c = Client::with_url("unkey_abc", &server.url);
+        let payload = serde_json::json!({"identityId": "id_123"});
+
+        let res: serde_json::Value = block_on(c.raw(
+            reqwest::Method::POST,
+            "identities.getIdentity",
+            Some(payload),
+        ))
+        .unwrap();
+        let request = &server.requests()[0];
+
+        assert_eq!(res["externalId"], "user_123");
+        assert!(request.starts_with("POST /identities.getIdentity "));
+        assert_eq!(body(request), r#"{"identityId":"id_123"}"#);
+    }
+
+    #[test]
+    fn last_ratelimit() {
+        let server = MockServer::with_headers(
+            vec![(200, r#"{"valid":true}"#)],
+            &[
+                ("X-RateLimit-Limit", "10"),
+                ("X-RateLimit-Remaining", "4"),
+                ("X-RateLimit-Reset", "1700000000000"),
+            ],
+        );
+        let c = Client::with_url("unkey_abc", &server.url);
+
+        block_on(c.verify_key_simple("test_abc", "api_123")).unwrap();
+        let ratelimit = c.last_ratelimit().unwrap();
+
+        assert_eq!(ratelimit.limit, 10);
+        assert_eq!(ratelimit.remaining, 4);
+    }
+
+    #[test]
+    fn builder_without_key() {
+        let server = MockServer::new(vec![(200, "{}")]);
+        let c = Client::builder().url(&server.url).build();
+
+        block_on(c.delete_key(DeleteKeyRequest::new("key_123"))).unwrap();
+        let request = &server.requests()[0];
+
+        assert_eq!(header(request, "authorization").unwrap(), "Bearer");
+    }
+
+    #[test]
+    fn verify_key_simple() {
+        let server = MockServer::new(vec![(200, r#"{"valid":true,"keyId":"key_123"}"#)]);
+        let c = Client::with_url("unkey_abc", &server.url);
+
+        let res = block_on(c.verify_key_simple("test_abc", "api_123")).unwrap();
+        let request = &server.requests()[0];
+
+        assert!(res.valid);
+        assert_eq!(res.key_id.as_deref(), Some("key_123"));
+        assert!(request.starts_with("POST /keys.verifyKey "));
+        assert_eq!(body(request), r#"{"key":"test_abc","apiId":"api_123"}"#);
+    }
+
+    #[test]
+    fn verify_key_with_key() {
+        let server = MockServer::new(vec![(200, r#"{"valid":true}"#)]);
+        let c = Client::with_url("unkey_abc", &server.url);
+
+        let req = VerifyKeyRequest::new("test_abc", "api_123");
+        block_on(c.verify_key_with_key(req, "unkey_def")).unwrap();
+        block_on(c.verify_key_simple("test_abc", "api_123")).unwrap();
+
+        let requests = server.requests();
+
+        assert_eq!(
+            header(&requests[0], "authorization").unwrap(),
+            "Bearer unkey_def"
+        );
+        assert_eq!(
+            header(&requests[1], "authorization").unwrap(),
+            "Bearer unkey_abc"
+        );
+    }
+
+    #[test]
+    fn set_key() {
+        let server = MockServer::new(vec![(200, r#"{"valid":true}"#)]);
+        let c = Client::with_url("unkey_abc", &server.url);
+        c.set_key("unkey_def");
+
+        block_on(c.verify_key_simple("test_abc", "api_123")).unwrap();
+        let request = &server.requests()[0];
+
+        assert_eq!(
+            header(request, "authorization").unwrap(),
+            "Bearer unkey_def"
+        );
+    }
+
+    #[test]
+    fn set_user_agent_while_shared() {
+        let server = MockServer::new(vec![(200, r#"{"valid":true}"#)]);
+        let c = Arc::new(Client::with_url("unkey_abc", &server.url));
+
+        c.set_user_agent("billing-service");
+        block_on(c.verify_key_simple("test_abc", "api_123")).unwrap();
+        let request = &server.requests()[0];
+
+        assert_eq!(header(request, "user-agent").unwrap(), "billing-service");
+    }
+
+    #[test]
+    fn set_key_while_shared() {
+        let server = MockServer::new(vec![(200, r#"{"valid":true}"#)]);
+        let c = Arc::new(Client::with_url("unkey_abc", &server.url));
+
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let c = Arc::clone(&c);
+                thread::spawn(move || {
+                    for _ in 0..5 {
+                        block_on(c.verify_key_simple("test_abc", "api_123")).unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        for i in 0..20 {
+            c.set_key(if i % 2 == 0 { "unkey_def" } else { "unkey_ghi" });
+        }
+
+        for reader in readers {
+            reader.join().unwrap();
+        }
+
+        let allowed = ["Bearer unkey_abc", "Bearer unkey_def", "Bearer unkey_ghi"];
+        let requests = server.requests();
+
+        assert_eq!(requests.len(), 20);
+        for request in &requests {
+            assert!(allowed.contains(&header(request, "authorization").unwrap().as_str()));
+        }
+    }
+
+    #[test]
+    fn with_version() {
+        let c = Client::with_version("", ApiVersion::V1);
+
+        assert_eq!(c.http.url(), "https://api.unkey.dev/v1");
+    }
+
+    #[test]
+    fn builder_with_version() {
+        let c = Client::builder().version(ApiVersion::V1).build();
+
+        assert_eq!(c.http.url(), "https://api.unkey.dev/v1");
+    }
+
+    #[test]
+    fn base_url_from_env() {
+        let _env = env_lock();
+        std::env::set_var("UNKEY_BASE_URL", "http://localhost:3000/");
+
+        let default = Client::new("unkey_abc");
+        let built = Client::builder().key("unkey_abc").build();
+        let explicit = Client::with_url("unkey_abc", "http://localhost:4000");
+        let versioned = Client::with_version("unkey_abc", ApiVersion::V1);
+
+        std::env::remove_var("UNKEY_BASE_URL");
+
+        assert_eq!(default.http.url(), "http://localhost:3000");
+        assert_eq!(built.http.url(), "http://localhost:3000");
+        assert_eq!(explicit.http.url(), "http://localhost:4000");
+        assert_eq!(versioned.http.url(), "https://api.unkey.dev/v1");
+        assert_eq!(
+            Client::new("unkey_abc").http.url(),
+            "https://api.unkey.dev/v1"
+        );
+    }
+
+    #[test]
+    fn health() {
+        let server = MockServer::new(vec![(200, r#"{"apis":[],"cursor":null}"#)]);
+        let c = Client::with_url("unkey_abc", &server.url);
+
+        block_on(c.health()).unwrap();
+        let request = &server.requests()[0];
+
+        assert!(request.starts_with("GET /apis.listApis?limit=1 "));
+        assert_eq!(
+            header(request, "authorization").unwrap(),
+            "Bearer unkey_abc"
+        );
+    }
+
+    #[test]
+    fn health_unauthorized() {
+        let server = MockServer::new(vec![(
+            401,
+            r#"{"error":{"code":"UNAUTHORIZED","message":"key required"}}"#,
+        )]);
+        let c = Client::with_url("unkey_invalid", &server.url);
+
+        let err = block_on(c.health()).unwrap_err();
+        let request = &server.requests()[0];
+
+        assert!(request.starts_with("GET /apis.listApis?limit=1 "));
+        assert_eq!(
+            header(request, "authorization").unwrap(),
+            "Bearer unkey_invalid"
+        );
+
+        assert_eq!(err.code(), ErrorCode::Unauthorized);
+        assert_eq!(err.status(), Some(401));
+    }
+
     #[test]
     fn new() {
         let c = Client::new("");
 
         assert_eq!(c.apis, ApiService);
         assert_eq!(c.keys, KeyService);
+        assert_eq!(c.ratelimits, RatelimitService);
+        assert_eq!(c.identities, IdentityService);
     }
 }
@@ -0,0 +1,76 @@
+use std::sync::PoisonError;
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// The clock overriding the system clock, if one was set.
+static CLOCK: RwLock<Option<fn() -> Duration>> = RwLock::new(None);
+
+/// Gets the current time.
+///
+/// # Returns
+/// The duration since the unix epoch, from the clock set with [`set`] or
+/// otherwise the system clock.
+pub(crate) fn now() -> Duration {
+    read(*CLOCK.read().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// Reads the given clock, falling back to the system clock.
+///
+/// # Arguments
+/// - `clock`: The clock to read, if any.
+///
+/// # Returns
+/// The duration since the unix epoch.
+fn read(clock: Option<fn() -> Duration>) -> Duration {
+    clock.map_or_else(system_now, |c| c())
+}
+
+/// Overrides the clock for the whole crate.
+///
+/// # Arguments
+/// - `clock`: The new clock, or `None` to fall back to the system clock.
+pub(crate) fn set(clock: Option<fn() -> Duration>) {
+    *CLOCK.write().unwrap_or_else(PoisonError::into_inner) = clock;
+}
+
+/// Reads the system clock.
+///
+/// # Returns
+/// The duration since the unix epoch, or zero if the clock is before it.
+#[cfg(not(target_arch = "wasm32"))]
+fn system_now() -> Duration {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// Reads the system clock.
+///
+/// # Returns
+/// Always zero, as `SystemTime` panics on `wasm32-unknown-unknown`. A clock
+/// must be set with [`set`] to get the real time.
+#[cfg(target_arch = "wasm32")]
+fn system_now() -> Duration {
+    Duration::ZERO
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::clock;
+
+    #[test]
+    fn now_uses_system_clock() {
+        assert!(clock::now() > Duration::ZERO);
+        assert!(clock::read(None) > Duration::ZERO);
+    }
+
+    #[test]
+    fn read_uses_given_clock() {
+        // Setting the global clock would race with other tests
+        let fixed = clock::read(Some(|| Duration::from_secs(42)));
+
+        assert_eq!(fixed, Duration::from_secs(42));
+    }
+}
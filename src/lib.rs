@@ -1,31 +1,93 @@
 #![doc = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/README.md"))]
 
+#[cfg(feature = "cache")]
+mod cache;
 mod client;
+mod clock;
 mod logging;
+#[cfg(test)]
+mod mock;
 pub mod models;
+pub mod prelude;
 mod routes;
 mod services;
 
-use models::HttpError;
 use serde::Deserialize;
+use serde_json::Value;
 
 pub use client::Client;
-use models::ErrorCode;
+pub use client::ClientBuilder;
+pub use logging::LogLevel;
+use models::Error;
 use models::HttpResult;
 use models::Wrapped;
 
-/// Creates a new Err variant of [`Wrapped`].
+/// The maximum number of characters of a response body included in
+/// deserialization errors.
+const SNIPPET_LEN: usize = 256;
+
+/// The json fields whose string values may contain plaintext keys.
+const SECRET_FIELDS: [&str; 2] = ["\"key\"", "\"plaintext\""];
+
+/// Creates a truncated snippet of the response body for error messages,
+/// with the values of any fields that may contain plaintext keys redacted.
 ///
 /// # Arguments
-/// - `$code`: The [`ErrorCode`] for the error.
-/// - `$err`: The error (must have `to_string()` impl).
+/// - `text`: The raw response body.
 ///
 /// # Returns
-/// The wrapped error.
-macro_rules! response_error {
-    ($code:expr, $err:expr) => {
-        ::std::result::Result::Err($crate::models::HttpError::new($code, $err.to_string()))
-    };
+/// The redacted snippet.
+fn body_snippet(text: &str) -> String {
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some((start, field)) = SECRET_FIELDS
+        .iter()
+        .filter_map(|f| rest.find(f).map(|i| (i, f)))
+        .min()
+    {
+        let (head, tail) = rest.split_at(start + field.len());
+        redacted.push_str(head);
+
+        // Only string values are redacted, i.e. `"key": "..."` but not `"key": null`
+        let value = tail.trim_start_matches(|c: char| c == ':' || c.is_whitespace());
+        let separator = &tail[..tail.len() - value.len()];
+
+        match value
+            .strip_prefix('"')
+            .and_then(|v| v.find('"').map(|i| &v[i..]))
+        {
+            Some(after) if separator.contains(':') => {
+                redacted.push_str(separator);
+                redacted.push_str("\"***");
+                rest = after;
+            }
+            _ => rest = tail,
+        }
+    }
+
+    redacted.push_str(rest);
+
+    match redacted.char_indices().nth(SNIPPET_LEN) {
+        Some((end, _)) => format!("{}...", &redacted[..end]),
+        None => redacted,
+    }
+}
+
+/// Creates the error for a response body that failed to deserialize.
+///
+/// # Arguments
+/// - `err`: The serde error.
+/// - `text`: The raw response body.
+///
+/// # Returns
+/// The decode error, including a snippet of the body.
+fn deserialize_error<T>(err: &serde_json::Error, text: &str) -> Result<T, Error> {
+    let snippet = body_snippet(text);
+    Err(Error::Decode {
+        message: format!("{err} in response: {snippet}"),
+        status: None,
+    })
 }
 
 /// Parses the http result.
@@ -37,30 +99,32 @@ macro_rules! response_error {
 /// A [`Result`] containing the response, or an error.
 ///
 /// # Errors
-/// The [`HttpError`], if one occurred.
-pub(crate) async fn parse_response<T>(result: HttpResult) -> Result<T, HttpError>
+/// The [`Error`], if one occurred.
+pub(crate) async fn parse_response<T>(result: HttpResult) -> Result<T, Error>
 where
     T: for<'a> Deserialize<'a>,
 {
-    let data = match result {
-        Ok(r) => r.text().await,
+    let (status, data) = match result {
+        Ok(r) => (Some(r.status().as_u16()), r.text().await),
         Err(e) => {
             logging::error!(format!("HTTP request failed: {}", e.to_string()));
-            Err(e)
+            (None, Err(e))
         }
     };
 
-    match data {
-        Err(e) => response_error!(ErrorCode::Unknown, e),
+    let res = match data {
+        Err(e) => Err(e.into()),
         Ok(text) => {
             logging::debug!(format!("INCOMING: {text}"));
 
             match serde_json::from_str::<Wrapped<T>>(&text) {
-                Err(e) => response_error!(ErrorCode::Unknown, e),
+                Err(e) => deserialize_error(&e, &text),
                 Ok(r) => r.into(),
             }
         }
-    }
+    };
+
+    res.map_err(|e| e.with_status(status))
 }
 
 /// Wraps the http result for an empty return value.
@@ -72,37 +136,40 @@ where
 /// A [`Result`] containing the response, or an error.
 ///
 /// # Errors
-/// The [`HttpError`], if one occurred.
-pub(crate) async fn parse_empty_response(result: HttpResult) -> Result<(), HttpError> {
-    let data = match result {
-        Ok(r) => r.text().await,
+/// The [`Error`], if one occurred.
+pub(crate) async fn parse_empty_response(result: HttpResult) -> Result<(), Error> {
+    let (status, data) = match result {
+        Ok(r) => (Some(r.status().as_u16()), r.text().await),
         Err(e) => {
             logging::error!(format!("HTTP request failed: {}", e.to_string()));
-            Err(e)
+            (None, Err(e))
         }
     };
 
-    match data {
-        Err(e) => response_error!(ErrorCode::Unknown, e),
+    let res = match data {
+        Err(e) => Err(e.into()),
         Ok(text) => {
             logging::debug!(format!("INCOMING: {text}"));
 
-            match serde_json::from_str::<Wrapped<()>>(&text) {
-                Ok(r) => r.into(),
+            match serde_json::from_str::<Wrapped<Value>>(&text) {
+                Ok(Wrapped::Err(e)) => Err(Error::Api(e)),
+                Ok(Wrapped::Ok(_) | Wrapped::Unknown { .. }) => Ok(()),
                 Err(e) => {
-                    if text.contains("error") {
-                        // If the text contains error and we failed to deserialize
-                        // it means the error struct is misaligned with the api
-                        response_error!(ErrorCode::Unknown, e)
-                    } else {
-                        // Otherwise it was successful even though we are in Err
-                        // due to serde failing to deserialize a unit type
+                    let success = status.map_or(true, |s| (200..300).contains(&s));
+
+                    if success && !text.trim_start().starts_with('{') {
+                        // Not json at all, i.e. an empty body, which is fine
+                        // for a successful request
                         Ok(())
+                    } else {
+                        deserialize_error(&e, &text)
                     }
                 }
             }
         }
-    }
+    };
+
+    res.map_err(|e| e.with_status(status))
 }
 
 /// Fetches the given route with the provided http service.
@@ -119,8 +186,12 @@ pub(crate) use fetch;
 
 #[cfg(test)]
 mod test {
+    use crate::mock::block_on;
+    use crate::models::CreateKeyResponse;
+    use crate::models::Error;
     use crate::models::ErrorCode;
-    use crate::models::HttpError;
+    #[cfg(feature = "strict")]
+    use crate::models::VerifyKeyResponse;
 
     struct FakeHttp;
 
@@ -136,18 +207,187 @@ mod test {
     }
 
     #[test]
-    fn response_error() {
-        let res: Result<(), _> = response_error!(ErrorCode::NotFound, "not found!");
+    fn parse_response_non_json_status() {
+        let response = http::Response::builder()
+            .status(502)
+            .body("<html>Bad Gateway</html>")
+            .unwrap();
+
+        let res: Result<u8, _> = block_on(super::parse_response(Ok(response.into())));
+        let err = res.unwrap_err();
+
+        assert!(matches!(err, Error::Decode { .. }));
+        assert_eq!(err.status(), Some(502));
+    }
+
+    #[test]
+    fn parse_response_valid_error_code() {
+        let response = http::Response::builder()
+            .status(500)
+            .body(r#"{"error":{"code":"VALID","message":"confused api"}}"#)
+            .unwrap();
+
+        let res: Result<u8, _> = block_on(super::parse_response(Ok(response.into())));
+        let err = res.unwrap_err();
+
+        assert_eq!(err.as_api().unwrap().code, ErrorCode::Unknown);
+        assert_eq!(err.status(), Some(500));
+        assert_eq!(
+            empty_response(
+                500,
+                r#"{"error":{"code":"VALID","message":"confused api"}}"#
+            )
+            .unwrap_err()
+            .code(),
+            ErrorCode::Unknown
+        );
+    }
+
+    #[test]
+    fn parse_response_error_details() {
+        let response = http::Response::builder()
+            .status(400)
+            .body(r#"{"error":{"code":"BAD_REQUEST","message":"invalid meta","details":[{"field":"meta"}]}}"#)
+            .unwrap();
+
+        let res: Result<u8, _> = block_on(super::parse_response(Ok(response.into())));
+        let err = res.unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::BadRequest);
+        assert_eq!(
+            err.as_api().unwrap().details.as_ref().unwrap()[0]["field"],
+            "meta"
+        );
+    }
+
+    #[test]
+    fn parse_response_malformed_includes_snippet() {
+        let response = http::Response::builder()
+            .status(200)
+            .body(r#"{"keyId":"key_123","key": "unkey_secret","extra":"#)
+            .unwrap();
+
+        let res: Result<u8, _> = block_on(super::parse_response(Ok(response.into())));
+        let (message, status) = match res {
+            Err(Error::Decode { message, status }) => (message, status),
+            other => panic!("expected a decode error, got {other:?}"),
+        };
+
+        assert!(message.contains(r#"{"keyId":"key_123","key": "***","extra":"#));
+        assert!(!message.contains("unkey_secret"));
+        assert_eq!(status, Some(200));
+    }
+
+    #[test]
+    fn parse_response_missing_field_names_field() {
+        let response = http::Response::builder()
+            .status(200)
+            .body(r#"{"key":"unkey_secret"}"#)
+            .unwrap();
+
+        let res: Result<CreateKeyResponse, _> =
+            block_on(super::parse_response(Ok(response.into())));
+        let (message, status) = match res {
+            Err(Error::Decode { message, status }) => (message, status),
+            other => panic!("expected a decode error, got {other:?}"),
+        };
+
+        assert!(message.starts_with("missing field `keyId` in response: "));
+        assert!(!message.contains("unkey_secret"));
+        assert_eq!(status, Some(200));
+    }
+
+    #[test]
+    #[cfg(feature = "strict")]
+    fn parse_response_strict_names_unknown_field() {
+        let response = http::Response::builder()
+            .status(200)
+            .body(r#"{"valid":true,"keyId":"key_123","newField":1}"#)
+            .unwrap();
+
+        let res: Result<VerifyKeyResponse, _> =
+            block_on(super::parse_response(Ok(response.into())));
+        let message = match res {
+            Err(Error::Decode { message, .. }) => message,
+            other => panic!("expected a decode error, got {other:?}"),
+        };
+
+        assert!(message.contains("unknown field `newField`"));
+    }
+
+    #[test]
+    fn body_snippet_truncates() {
+        let text = "a".repeat(300);
+        let snippet = super::body_snippet(&text);
+
+        assert_eq!(snippet, format!("{}...", "a".repeat(256)));
+        assert_eq!(super::body_snippet("short"), "short");
+    }
+
+    #[test]
+    fn body_snippet_redacts_keys() {
+        let text = r#"{"plaintext":"t_1","keyId":"key_1","key":null,"keys":[{"key":"t_2"}]}"#;
 
         assert_eq!(
-            res,
-            Err(HttpError::new(
-                ErrorCode::NotFound,
-                String::from("not found!")
-            ))
+            super::body_snippet(text),
+            r#"{"plaintext":"***","keyId":"key_1","key":null,"keys":[{"key":"***"}]}"#
         );
     }
 
+    fn empty_response(status: u16, body: &'static str) -> Result<(), Error> {
+        let response = http::Response::builder().status(status).body(body).unwrap();
+
+        block_on(super::parse_empty_response(Ok(response.into())))
+    }
+
+    #[test]
+    fn parse_empty_response_success_bodies() {
+        assert!(empty_response(200, "").is_ok());
+        assert!(empty_response(200, "{}").is_ok());
+        assert!(empty_response(200, r#"{"result":"error_free"}"#).is_ok());
+    }
+
+    #[test]
+    fn parse_empty_response_error_body() {
+        let err = empty_response(
+            403,
+            r#"{"error":{"code":"FORBIDDEN","message":"insufficient permissions"}}"#,
+        )
+        .unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::Forbidden);
+        assert_eq!(err.as_api().unwrap().message, "insufficient permissions");
+    }
+
+    #[test]
+    fn parse_empty_response_malformed_error() {
+        let err = empty_response(400, r#"{"error":"bad request"}"#).unwrap_err();
+
+        assert!(matches!(err, Error::Decode { .. }));
+        assert_eq!(err.status(), Some(400));
+    }
+
+    #[test]
+    fn parse_empty_response_non_json_failure() {
+        let err = empty_response(502, "<html>Bad Gateway</html>").unwrap_err();
+
+        assert!(matches!(err, Error::Decode { .. }));
+        assert_eq!(err.status(), Some(502));
+    }
+
+    #[test]
+    fn parse_empty_response_error_status() {
+        let response = http::Response::builder()
+            .status(404)
+            .body(r#"{"error":{"code":"NOT_FOUND","message":"key not found"}}"#)
+            .unwrap();
+
+        let err = block_on(super::parse_empty_response(Ok(response.into()))).unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::NotFound);
+        assert_eq!(err.status(), Some(404));
+    }
+
     #[test]
     fn fetch_no_payload() {
         let route = 69;
@@ -1,87 +1,199 @@
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::Ordering;
+
+#[cfg(feature = "logging")]
 lazy_static::lazy_static! {
-    /// An environment variable containing the log level env var.
-    pub(crate) static ref UNKEY_LOG: Log = match option_env!("UNKEY_LOG") {
-        None => Log::None,
-        Some(level) => match level {
-           "debug" | "DEBUG" => Log::Debug,
-           "info" | "INFO" => Log::Info,
-           "error" | "ERROR" => Log::Error,
+    /// The log level read from the `UNKEY_LOG` environment variable.
+    pub(crate) static ref UNKEY_LOG: LogLevel = match std::env::var("UNKEY_LOG") {
+        Err(_) => LogLevel::None,
+        Ok(level) => match level.as_str() {
+           "debug" | "DEBUG" => LogLevel::Debug,
+           "info" | "INFO" => LogLevel::Info,
+           "error" | "ERROR" => LogLevel::Error,
            _ => {
                eprintln!("Invalid UNKEY_LOG level detected: {level}");
-               Log::None
+               LogLevel::None
            }
         }
     };
 }
 
+/// Sentinel indicating no programmatic log level override is set.
+const NO_OVERRIDE: u8 = u8::MAX;
+
+/// The programmatic log level override, if one was set.
+static LOG_OVERRIDE: AtomicU8 = AtomicU8::new(NO_OVERRIDE);
+
 /// The different logging levels supported by the crate.
-#[derive(Eq, PartialEq, PartialOrd)]
-pub(crate) enum Log {
+#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd)]
+pub enum LogLevel {
+    /// Nothing is logged.
     None,
+
+    /// Only errors are logged.
     Error,
+
+    /// Errors and outgoing requests are logged.
     Info,
+
+    /// Everything is logged, including payloads and responses.
     Debug,
 }
 
-impl From<&Log> for String {
-    fn from(val: &Log) -> String {
+impl LogLevel {
+    /// Converts a stored override back into a log level.
+    #[cfg(feature = "logging")]
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::None),
+            1 => Some(Self::Error),
+            2 => Some(Self::Info),
+            3 => Some(Self::Debug),
+            _ => None,
+        }
+    }
+}
+
+impl From<&LogLevel> for String {
+    fn from(val: &LogLevel) -> String {
         let message = match val {
-            Log::Debug => "[DEBUG]",
-            Log::Info => "[INFO] ",
-            Log::Error => "[ERROR]",
-            Log::None => "",
+            LogLevel::Debug => "[DEBUG]",
+            LogLevel::Info => "[INFO] ",
+            LogLevel::Error => "[ERROR]",
+            LogLevel::None => "",
         };
 
         message.to_string()
     }
 }
 
-impl From<Log> for String {
-    fn from(val: Log) -> String {
+impl From<LogLevel> for String {
+    fn from(val: LogLevel) -> String {
         (&val).into()
     }
 }
 
-impl std::fmt::Display for Log {
+impl std::fmt::Display for LogLevel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s: String = self.into();
         write!(f, "{s}")
     }
 }
 
+/// Gets the active log level.
+///
+/// # Returns
+/// The programmatic override if one was set, otherwise the level from the
+/// `UNKEY_LOG` environment variable.
+#[cfg(feature = "logging")]
+pub(crate) fn active_level() -> LogLevel {
+    LogLevel::from_u8(LOG_OVERRIDE.load(Ordering::Relaxed)).unwrap_or(*UNKEY_LOG)
+}
+
+/// Overrides the log level for the whole crate.
+///
+/// ## Note
+/// Without the `logging` feature nothing is ever logged, so the level is
+/// stored but has no effect.
+///
+/// # Arguments
+/// - `level`: The new log level, or `None` to fall back to `UNKEY_LOG`.
+pub(crate) fn set_level(level: Option<LogLevel>) {
+    let value = level.map_or(NO_OVERRIDE, |l| l as u8);
+    LOG_OVERRIDE.store(value, Ordering::Relaxed);
+}
+
+#[cfg(all(test, feature = "logging"))]
+thread_local! {
+    /// Captures log output on the current thread, when enabled.
+    static CAPTURED: std::cell::RefCell<Option<Vec<String>>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Runs the closure, returning the log lines it emitted on this thread
+/// instead of printing them.
+///
+/// # Arguments
+/// - `f`: The closure to run.
+///
+/// # Returns
+/// The captured log lines.
+#[cfg(all(test, feature = "logging"))]
+pub(crate) fn capture<F: FnOnce()>(f: F) -> Vec<String> {
+    CAPTURED.with(|c| *c.borrow_mut() = Some(Vec::new()));
+    f();
+    CAPTURED.with(|c| c.borrow_mut().take().unwrap_or_default())
+}
+
+/// Writes the message if the given level is active.
+///
+/// # Arguments
+/// - `level`: The level to log at.
+/// - `message`: The message to log.
+#[cfg(feature = "logging")]
+pub(crate) fn emit(level: LogLevel, message: &str) {
+    if level == LogLevel::None || active_level() < level {
+        return;
+    }
+
+    #[cfg(test)]
+    {
+        let captured = CAPTURED.with(|c| {
+            c.borrow_mut()
+                .as_mut()
+                .map(|lines| lines.push(format!("{level} {message}")))
+        });
+
+        if captured.is_some() {
+            return;
+        }
+    }
+
+    match level {
+        LogLevel::None => (),
+        LogLevel::Error => eprintln!("{level} {message}"),
+        LogLevel::Info | LogLevel::Debug => println!("{level} {message}"),
+    }
+}
+
 /// Logs the given message at the given level.
+#[cfg(feature = "logging")]
 macro_rules! log {
     ($level:expr, $message:expr) => {
-        if *$crate::logging::UNKEY_LOG >= $level {
-            match $level {
-                $crate::logging::Log::None => (),
-                $crate::logging::Log::Error => eprintln!("{} {}", $level, $message),
-                $crate::logging::Log::Info | $crate::logging::Log::Debug => {
-                    println!("{} {}", $level, $message)
-                }
-            }
-        }
+        $crate::logging::emit($level, &$message)
     };
 }
 
+/// Discards the message without evaluating it, as the `logging` feature is
+/// disabled.
+///
+/// The message is wrapped in a closure that is never called, so variables
+/// only used for logging don't trigger unused warnings.
+#[cfg(not(feature = "logging"))]
+macro_rules! log {
+    ($level:expr, $message:expr) => {{
+        let _ = $level;
+        let _ = || $message;
+    }};
+}
+
 /// Logs the given message at the debug level.
 macro_rules! debug {
     ($message:expr) => {
-        $crate::logging::log!($crate::logging::Log::Debug, $message)
+        $crate::logging::log!($crate::logging::LogLevel::Debug, $message)
     };
 }
 
 /// Logs the given message at the infomation level.
 macro_rules! info {
     ($message:expr) => {
-        $crate::logging::log!($crate::logging::Log::Info, $message)
+        $crate::logging::log!($crate::logging::LogLevel::Info, $message)
     };
 }
 
 /// Logs the given message at the error level.
 macro_rules! error {
     ($message:expr) => {
-        $crate::logging::log!($crate::logging::Log::Error, $message)
+        $crate::logging::log!($crate::logging::LogLevel::Error, $message)
     };
 }
 
@@ -89,3 +201,45 @@ pub(crate) use debug;
 pub(crate) use error;
 pub(crate) use info;
 pub(crate) use log;
+
+#[cfg(all(test, feature = "logging"))]
+mod test {
+    use crate::logging::capture;
+    use crate::logging::set_level;
+    use crate::logging::LogLevel;
+    use crate::mock::env_lock;
+
+    #[test]
+    fn override_toggles_output() {
+        let _env = env_lock();
+        set_level(Some(LogLevel::Info));
+        let lines = capture(|| {
+            info!("shown");
+            debug!("hidden");
+        });
+
+        assert_eq!(lines, vec![String::from("[INFO]  shown")]);
+
+        set_level(Some(LogLevel::None));
+        let lines = capture(|| error!("hidden"));
+
+        assert!(lines.is_empty());
+
+        set_level(Some(LogLevel::Debug));
+        let lines = capture(|| debug!("shown"));
+
+        assert_eq!(lines, vec![String::from("[DEBUG] shown")]);
+
+        set_level(None);
+    }
+}
+
+#[cfg(all(test, not(feature = "logging")))]
+mod test {
+    #[test]
+    fn disabled_macros_are_noops() {
+        debug!(panic!("debug was evaluated"));
+        info!(panic!("info was evaluated"));
+        error!(panic!("error was evaluated"));
+    }
+}
@@ -0,0 +1,253 @@
+use std::future::Future;
+use std::io::Read;
+use std::io::Write;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::thread;
+
+use serde::Serialize;
+
+use crate::routes::CompiledRoute;
+use crate::services::FetchFuture;
+use crate::services::HttpTransport;
+
+/// Serializes the tests that read or modify environment variables, or the
+/// global log level.
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+/// A minimal http server used to test the crate against canned responses.
+pub(crate) struct MockServer {
+    /// The base url the server is listening on.
+    pub url: String,
+
+    /// The raw requests the server has received.
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockServer {
+    /// Spawns a new mock server.
+    ///
+    /// # Arguments
+    /// - `responses`: The status and body to respond with, in order. The
+    ///   last response is repeated once the others are exhausted.
+    ///
+    /// # Returns
+    /// The running mock server.
+    pub fn new(responses: Vec<(u16, &str)>) -> Self {
+        Self::with_headers(responses, &[])
+    }
+
+    /// Spawns a new mock server that sends extra headers with every
+    /// response.
+    ///
+    /// # Arguments
+    /// - `responses`: The status and body to respond with, in order.
+    /// - `headers`: The extra headers to send with each response.
+    ///
+    /// # Returns
+    /// The running mock server.
+    pub fn with_headers(responses: Vec<(u16, &str)>, headers: &[(&str, &str)]) -> Self {
+        let responses = responses
+            .into_iter()
+            .map(|(status, body)| (status, body.as_bytes().to_vec()))
+            .collect();
+
+        Self::spawn(responses, headers)
+    }
+
+    /// Spawns a new mock server responding with gzip encoded bodies.
+    ///
+    /// # Arguments
+    /// - `responses`: The status and body to respond with, in order. The
+    ///   last response is repeated once the others are exhausted.
+    ///
+    /// # Returns
+    /// The running mock server.
+    #[cfg(feature = "compression")]
+    pub fn gzip(responses: Vec<(u16, &str)>) -> Self {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+
+        let responses = responses
+            .into_iter()
+            .map(|(status, body)| {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(body.as_bytes()).unwrap();
+                (status, encoder.finish().unwrap())
+            })
+            .collect();
+
+        Self::spawn(responses, &[("Content-Encoding", "gzip")])
+    }
+
+    /// Spawns the server thread.
+    fn spawn(responses: Vec<(u16, Vec<u8>)>, headers: &[(&str, &str)]) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let received = requests.clone();
+
+        let extra: String = headers
+            .iter()
+            .map(|(name, value)| format!("{name}: {value}\r\n"))
+            .collect();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let request = Self::read_request(&mut stream);
+
+                let index = {
+                    let mut received = received.lock().unwrap();
+                    received.push(request);
+                    received.len() - 1
+                };
+
+                let (status, body) = &responses[index.min(responses.len() - 1)];
+                let head = format!(
+                    "HTTP/1.1 {status} MOCK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n{extra}\r\n",
+                    body.len()
+                );
+
+                stream.write_all(head.as_bytes()).unwrap();
+                stream.write_all(body).unwrap();
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    /// Reads an entire http request from the stream.
+    fn read_request(stream: &mut TcpStream) -> String {
+        let mut data = Vec::new();
+        let mut buffer = [0; 4096];
+
+        loop {
+            let read = stream.read(&mut buffer).unwrap();
+            data.extend_from_slice(&buffer[..read]);
+            let text = String::from_utf8_lossy(&data).to_string();
+
+            if let Some(end) = text.find("\r\n\r\n") {
+                let length = text[..end]
+                    .lines()
+                    .filter_map(|l| l.split_once(':'))
+                    .find(|(k, _)| k.eq_ignore_ascii_case("content-length"))
+                    .map_or(0, |(_, v)| v.trim().parse().unwrap());
+
+                if read == 0 || data.len() >= end + 4 + length {
+                    return text;
+                }
+            } else if read == 0 {
+                return text;
+            }
+        }
+    }
+
+    /// The raw requests received so far.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// The number of requests received so far.
+    pub fn attempts(&self) -> usize {
+        self.requests.lock().unwrap().len()
+    }
+}
+
+/// A fake transport returning canned responses without touching the network.
+pub(crate) struct MockTransport {
+    /// The status and body to respond with.
+    response: (u16, String),
+
+    /// The uri and serialized payload of each request sent.
+    sent: Mutex<Vec<(String, Option<String>)>>,
+}
+
+impl MockTransport {
+    /// Creates a new mock transport.
+    ///
+    /// # Arguments
+    /// - `status`: The status to respond with.
+    /// - `body`: The body to respond with.
+    ///
+    /// # Returns
+    /// The new mock transport.
+    pub fn new(status: u16, body: &str) -> Self {
+        let response = (status, body.to_string());
+        let sent = Mutex::new(Vec::new());
+
+        Self { response, sent }
+    }
+
+    /// The uri and serialized payload of each request sent so far.
+    pub fn sent(&self) -> Vec<(String, Option<String>)> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+impl HttpTransport for MockTransport {
+    fn fetch<'a, T>(&'a self, route: CompiledRoute, payload: Option<T>) -> FetchFuture<'a>
+    where
+        T: std::fmt::Debug + Serialize + Send + 'a,
+    {
+        let uri = format!("{}{}", route.uri, route.build_query());
+        let payload = payload.map(|p| serde_json::to_string(&p).unwrap());
+        self.sent.lock().unwrap().push((uri, payload));
+
+        let (status, body) = self.response.clone();
+        let response = http::Response::builder().status(status).body(body).unwrap();
+
+        Box::pin(async move { Ok(response.into()) })
+    }
+}
+
+/// Finds the value of the given header in a raw request.
+///
+/// # Arguments
+/// - `request`: The raw http request.
+/// - `name`: The case insensitive header name.
+///
+/// # Returns
+/// The header value, if present.
+pub(crate) fn header(request: &str, name: &str) -> Option<String> {
+    request
+        .split("\r\n\r\n")
+        .next()?
+        .lines()
+        .filter_map(|l| l.split_once(':'))
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.trim().to_string())
+}
+
+/// Gets the body of a raw request.
+///
+/// # Arguments
+/// - `request`: The raw http request.
+///
+/// # Returns
+/// The request body.
+pub(crate) fn body(request: &str) -> &str {
+    request.split_once("\r\n\r\n").map_or("", |(_, b)| b)
+}
+
+/// Locks the environment, so tests relying on environment variables don't
+/// race with each other.
+///
+/// # Returns
+/// The guard, releasing the lock when dropped.
+pub(crate) fn env_lock() -> MutexGuard<'static, ()> {
+    ENV_LOCK.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Runs the future to completion on a new runtime.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
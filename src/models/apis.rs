@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 
 use super::ApiKey;
+use super::UndefinedOr;
+
+/// The maximum number of keys the api returns per page.
+pub const LIST_KEYS_MAX_LIMIT: usize = 100;
 
 /// An outgoing paginated list keys request.
 #[derive(Debug, Clone, Serialize)]
@@ -13,6 +17,8 @@ pub struct ListKeysRequest {
     pub owner_id: Option<String>,
 
     /// The optional number of keys to return, up to 100.
+    ///
+    /// *Note*: Prefer [`ListKeysRequest::set_limit`], which clamps the value.
     pub limit: Option<usize>,
 
     /// The pagination cursor indicating the last key that was returned.
@@ -43,6 +49,7 @@ impl ListKeysRequest {
     /// assert_eq!(r.revalidate_cache, None);
     /// ```
     #[must_use]
+    #[inline]
     pub fn new<T: Into<String>>(api_id: T) -> Self {
         Self {
             api_id: api_id.into(),
@@ -55,6 +62,9 @@ impl ListKeysRequest {
 
     /// Sets the limit for the request.
     ///
+    /// The api accepts at most 100 keys per page, so the limit is clamped
+    /// to `1..=100`.
+    ///
     /// # Arguments
     /// - `limit`: The limit to set.
     ///
@@ -67,10 +77,14 @@ impl ListKeysRequest {
     /// let r = ListKeysRequest::new("test").set_limit(50);
     ///
     /// assert_eq!(r.limit.unwrap(), 50);
+    ///
+    /// let r = r.set_limit(500);
+    ///
+    /// assert_eq!(r.limit.unwrap(), 100);
     /// ```
     #[must_use]
     pub fn set_limit(mut self, limit: usize) -> Self {
-        self.limit = Some(limit);
+        self.limit = Some(limit.clamp(1, LIST_KEYS_MAX_LIMIT));
         self
     }
 
@@ -139,7 +153,8 @@ impl ListKeysRequest {
 }
 
 /// An incoming paginated list keys response.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct ListKeysResponse {
     /// The api keys included in this page.
     pub keys: Vec<ApiKey>,
@@ -148,7 +163,113 @@ pub struct ListKeysResponse {
     pub total: usize,
 
     /// The cursor indicating the last key that was returned.
-    pub cursor: Option<String>,
+    pub cursor: Option<Cursor>,
+}
+
+/// An opaque pagination cursor, which can be saved to resume listing later.
+///
+/// # Example
+/// ```
+/// # use unkey::models::Cursor;
+/// # use unkey::models::ListKeysRequest;
+/// let cursor = Cursor::new("key_123");
+/// let saved = serde_json::to_string(&cursor).unwrap();
+///
+/// // Later, i.e. after restarting
+/// let cursor: Cursor = serde_json::from_str(&saved).unwrap();
+/// let r = ListKeysRequest::new("api_123").set_cursor(cursor);
+///
+/// assert_eq!(r.cursor.unwrap(), String::from("key_123"));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Cursor(String);
+
+impl Cursor {
+    /// Creates a new cursor.
+    ///
+    /// # Arguments
+    /// - `cursor`: The raw cursor value.
+    ///
+    /// # Returns
+    /// The new cursor.
+    #[must_use]
+    pub fn new<T: Into<String>>(cursor: T) -> Self {
+        Self(cursor.into())
+    }
+
+    /// The raw cursor value.
+    ///
+    /// # Returns
+    /// The cursor as a string slice.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Cursor {
+    fn from(cursor: String) -> Self {
+        Self(cursor)
+    }
+}
+
+impl From<Cursor> for String {
+    fn from(cursor: Cursor) -> Self {
+        cursor.0
+    }
+}
+
+impl std::fmt::Display for Cursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl ListKeysResponse {
+    /// The number of keys in this page.
+    ///
+    /// # Returns
+    /// The number of keys.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Whether this page contains no keys.
+    ///
+    /// # Returns
+    /// True if the page is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// An iterator over the keys in this page.
+    ///
+    /// # Returns
+    /// The iterator over the keys.
+    pub fn iter(&self) -> std::slice::Iter<'_, ApiKey> {
+        self.keys.iter()
+    }
+}
+
+impl IntoIterator for ListKeysResponse {
+    type Item = ApiKey;
+    type IntoIter = std::vec::IntoIter<ApiKey>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.keys.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ListKeysResponse {
+    type Item = &'a ApiKey;
+    type IntoIter = std::slice::Iter<'a, ApiKey>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.keys.iter()
+    }
 }
 
 /// An outgoing get api request.
@@ -176,6 +297,7 @@ impl GetApiRequest {
     /// assert_eq!(r.api_id, String::from("test"));
     /// ```
     #[must_use]
+    #[inline]
     pub fn new<T: Into<String>>(api_id: T) -> Self {
         Self {
             api_id: api_id.into(),
@@ -183,8 +305,22 @@ impl GetApiRequest {
     }
 }
 
+impl From<&str> for GetApiRequest {
+    fn from(api_id: &str) -> Self {
+        Self::new(api_id)
+    }
+}
+
+impl From<String> for GetApiRequest {
+    fn from(api_id: String) -> Self {
+        Self::new(api_id)
+    }
+}
+
 /// An incoming get api response.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct GetApiResponse {
     /// The id of the api.
     #[serde(rename = "id")]
@@ -194,7 +330,6 @@ pub struct GetApiResponse {
     pub name: String,
 
     /// The workspace id of the api.
-    #[serde(rename = "workspaceId")]
     pub workspace_id: String,
 }
 
@@ -223,9 +358,320 @@ impl DeleteApiRequest {
     /// assert_eq!(r.api_id, String::from("test"));
     /// ```
     #[must_use]
+    #[inline]
+    pub fn new<T: Into<String>>(api_id: T) -> Self {
+        Self {
+            api_id: api_id.into(),
+        }
+    }
+}
+
+impl From<&str> for DeleteApiRequest {
+    fn from(api_id: &str) -> Self {
+        Self::new(api_id)
+    }
+}
+
+impl From<String> for DeleteApiRequest {
+    fn from(api_id: String) -> Self {
+        Self::new(api_id)
+    }
+}
+
+/// An outgoing update api request.
+///
+/// ## Note
+/// The name is initialized to the [`UndefinedOr::Undefined`] state, and is
+/// omitted from the request. Calling `set_name(None)` sends an explicit
+/// `null`, clearing the name currently set on the api.
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateApiRequest {
+    /// The id of the api to update.
+    pub api_id: String,
+
+    /// The optional new name for the api.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub name: UndefinedOr<String>,
+}
+
+impl UpdateApiRequest {
+    /// Creates a new update api request.
+    ///
+    /// # Arguments
+    /// - `api_id`: The id of the api to update.
+    ///
+    /// # Returns
+    /// The new update api request.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateApiRequest;
+    /// # use unkey::models::UndefinedOr;
+    /// let r = UpdateApiRequest::new("test");
+    ///
+    /// assert_eq!(r.api_id, String::from("test"));
+    /// assert_eq!(r.name, UndefinedOr::Undefined);
+    /// ```
+    #[must_use]
+    #[inline]
     pub fn new<T: Into<String>>(api_id: T) -> Self {
         Self {
             api_id: api_id.into(),
+            ..Default::default()
         }
     }
+
+    /// Sets or unsets the name for the api.
+    ///
+    /// # Arguments
+    /// - `name`: The name to set or unset.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateApiRequest;
+    /// # use unkey::models::UndefinedOr;
+    /// let r = UpdateApiRequest::new("test").set_name(Some("new_name"));
+    ///
+    /// assert_eq!(r.name, UndefinedOr::Value(String::from("new_name")));
+    ///
+    /// let r = r.set_name(None);
+    ///
+    /// assert_eq!(r.name, UndefinedOr::Null);
+    /// ```
+    #[must_use]
+    pub fn set_name(mut self, name: Option<&str>) -> Self {
+        self.name = name.map(String::from).into();
+        self
+    }
+}
+
+/// An outgoing paginated list apis request.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListApisRequest {
+    /// The optional number of apis to return, up to 100.
+    pub limit: Option<usize>,
+
+    /// The pagination cursor indicating the last api that was returned.
+    pub cursor: Option<String>,
+}
+
+impl ListApisRequest {
+    /// Creates a new list apis request.
+    ///
+    /// # Returns
+    /// The new list apis request.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::ListApisRequest;
+    /// let r = ListApisRequest::new();
+    ///
+    /// assert_eq!(r.limit, None);
+    /// assert_eq!(r.cursor, None);
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the limit for the request.
+    ///
+    /// # Arguments
+    /// - `limit`: The limit to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::ListApisRequest;
+    /// let r = ListApisRequest::new().set_limit(50);
+    ///
+    /// assert_eq!(r.limit.unwrap(), 50);
+    /// ```
+    #[must_use]
+    pub fn set_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Sets the pagination cursor for the request.
+    ///
+    /// # Arguments
+    /// - `cursor`: The pagination cursor to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::ListApisRequest;
+    /// let r = ListApisRequest::new().set_cursor("abcabc");
+    ///
+    /// assert_eq!(r.cursor.unwrap(), String::from("abcabc"));
+    /// ```
+    #[must_use]
+    pub fn set_cursor<T: Into<String>>(mut self, cursor: T) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+}
+
+/// An incoming paginated list apis response.
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct ListApisResponse {
+    /// The apis included in this page.
+    pub apis: Vec<GetApiResponse>,
+
+    /// The cursor indicating the last api that was returned.
+    pub cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::models::Cursor;
+    use crate::models::DeleteApiRequest;
+    use crate::models::GetApiRequest;
+    use crate::models::GetApiResponse;
+    use crate::models::ListKeysRequest;
+    use crate::models::ListKeysResponse;
+    use crate::models::UpdateApiRequest;
+
+    fn list_keys_response() -> ListKeysResponse {
+        let body = r#"{"keys":[
+            {"id":"key_1","apiId":"api_123","workspaceId":"ws_123","start":"t_","createdAt":0},
+            {"id":"key_2","apiId":"api_123","workspaceId":"ws_123","start":"t_","createdAt":0}
+        ],"total":2,"cursor":null}"#;
+
+        serde_json::from_str(body).unwrap()
+    }
+
+    #[test]
+    fn list_keys_response_iter() {
+        let res = list_keys_response();
+        let mut ids = Vec::new();
+
+        for key in &res {
+            ids.push(key.id.as_str());
+        }
+
+        assert_eq!(ids, vec!["key_1", "key_2"]);
+        assert_eq!(res.iter().map(|k| k.id.as_str()).collect::<Vec<_>>(), ids);
+        assert_eq!(res.len(), 2);
+        assert!(!res.is_empty());
+    }
+
+    #[test]
+    fn list_keys_response_into_iter() {
+        let mut ids = Vec::new();
+
+        for key in list_keys_response() {
+            ids.push(key.id);
+        }
+
+        assert_eq!(ids, vec![String::from("key_1"), String::from("key_2")]);
+    }
+
+    #[test]
+    fn list_keys_request_limit_clamps_high() {
+        let r = ListKeysRequest::new("api_123").set_limit(500);
+
+        assert_eq!(r.limit, Some(100));
+    }
+
+    #[test]
+    fn list_keys_request_limit_clamps_low() {
+        let r = ListKeysRequest::new("api_123").set_limit(0);
+
+        assert_eq!(r.limit, Some(1));
+    }
+
+    #[test]
+    fn update_api_serialize_undefined() {
+        let r = UpdateApiRequest::new("api_123");
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(res.as_str(), r#"{"apiId":"api_123"}"#);
+    }
+
+    #[test]
+    fn update_api_serialize_set() {
+        let r = UpdateApiRequest::new("api_123").set_name(Some("test"));
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(res.as_str(), r#"{"apiId":"api_123","name":"test"}"#);
+    }
+
+    #[test]
+    fn update_api_serialize_clear() {
+        let r = UpdateApiRequest::new("api_123").set_name(None);
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(res.as_str(), r#"{"apiId":"api_123","name":null}"#);
+    }
+
+    #[test]
+    fn get_api_response_deserialize() {
+        let body = r#"{
+            "id": "api_7oKUUscTZy22jmVf9THxDA",
+            "name": "production",
+            "workspaceId": "ws_3qQ6tLsH2GZnNSX1zkwm9F"
+        }"#;
+        let res: GetApiResponse = serde_json::from_str(body).unwrap();
+
+        assert_eq!(res.api_id, "api_7oKUUscTZy22jmVf9THxDA");
+        assert_eq!(res.name, "production");
+        assert_eq!(res.workspace_id, "ws_3qQ6tLsH2GZnNSX1zkwm9F");
+    }
+
+    #[test]
+    fn get_api_response_eq() {
+        let body = r#"{"id":"api_123","name":"test","workspaceId":"ws_123"}"#;
+        let a: GetApiResponse = serde_json::from_str(body).unwrap();
+        let b = GetApiResponse {
+            api_id: String::from("api_123"),
+            name: String::from("test"),
+            workspace_id: String::from("ws_123"),
+        };
+
+        assert_eq!(a, b);
+        assert_ne!(
+            a,
+            GetApiResponse {
+                name: String::from("other"),
+                ..b
+            }
+        );
+    }
+
+    #[test]
+    fn api_requests_from_id() {
+        let get: GetApiRequest = "api_123".into();
+        let delete = DeleteApiRequest::from(String::from("api_123"));
+
+        assert_eq!(get.api_id, "api_123");
+        assert_eq!(delete.api_id, "api_123");
+    }
+
+    #[test]
+    fn cursor_round_trip() {
+        let body = r#"{"keys":[],"total":0,"cursor":"key_123"}"#;
+        let res: ListKeysResponse = serde_json::from_str(body).unwrap();
+        let cursor = res.cursor.unwrap();
+
+        let saved = serde_json::to_string(&cursor).unwrap();
+        let restored: Cursor = serde_json::from_str(&saved).unwrap();
+        let req = ListKeysRequest::new("api_123").set_cursor(restored.clone());
+
+        assert_eq!(saved, r#""key_123""#);
+        assert_eq!(restored, cursor);
+        assert_eq!(restored.as_str(), "key_123");
+        assert_eq!(req.cursor.as_deref(), Some("key_123"));
+    }
 }
@@ -1,9 +1,80 @@
+use serde::de::value::StrDeserializer;
+use serde::de::DeserializeOwned;
+use serde::de::Error as _;
+use serde::de::IntoDeserializer;
 use serde::Deserialize;
+use serde::Deserializer;
+use serde_json::Value;
+
+use crate::logging;
 
 /// A low level http result representation.
 pub(crate) type HttpResult = Result<reqwest::Response, reqwest::Error>;
 
+/// The unkey api production base url, excluding the version.
+pub(crate) static BASE_API_URL: &str = "https://api.unkey.dev";
+
+/// A version of the unkey api.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum ApiVersion {
+    /// Version 1 of the api.
+    #[default]
+    V1,
+}
+
+impl ApiVersion {
+    /// The production base url for this api version.
+    ///
+    /// # Returns
+    /// The base url, excluding trailing slash.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::ApiVersion;
+    /// assert_eq!(ApiVersion::V1.base_url(), "https://api.unkey.dev/v1");
+    /// ```
+    #[must_use]
+    pub fn base_url(&self) -> String {
+        format!("{BASE_API_URL}/{self}")
+    }
+}
+
+impl std::fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::V1 => write!(f, "v1"),
+        }
+    }
+}
+
 /// An error code returned by the unkey api.
+///
+/// ## Note
+/// New variants are added as the unkey api evolves, so this enum is
+/// `#[non_exhaustive]` and matching on it requires a wildcard arm. For
+/// coarse grained matching that won't change between releases, match on
+/// [`ErrorCode::category`] instead.
+///
+/// # Example
+/// ```
+/// use unkey::models::ErrorCategory;
+/// use unkey::models::ErrorCode;
+/// use unkey::models::HttpError;
+///
+/// fn is_missing(err: &HttpError) -> bool {
+///     err.code == ErrorCode::NotFound
+/// }
+///
+/// fn describe(err: &HttpError) -> &'static str {
+///     match err.code.category() {
+///         ErrorCategory::Client => "check the request",
+///         ErrorCategory::Server => "try again later",
+///         ErrorCategory::RateLimit => "slow down",
+///         ErrorCategory::Unknown => "something unexpected happened",
+///     }
+/// }
+/// ```
+#[non_exhaustive]
 #[derive(Debug, Clone, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ErrorCode {
@@ -52,20 +123,206 @@ pub enum ErrorCode {
     /// You have made too many requests.
     TooManyRequests,
 
+    /// The request timed out before a response was received.
+    Timeout,
+
+    /// The request failed to connect to the api.
+    Network,
+
+    /// The request was not sent, as the client is in dry run mode.
+    DryRun,
+
     /// Reserved for unknown interactions.
     #[serde(other)]
     Unknown,
 }
 
+impl ErrorCode {
+    /// Whether a request failing with this error code is worth retrying.
+    ///
+    /// # Returns
+    /// True if the error is transient.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::ErrorCode;
+    /// assert!(ErrorCode::RateLimited.is_retryable());
+    /// assert!(!ErrorCode::NotFound.is_retryable());
+    /// ```
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RateLimited
+            | Self::TooManyRequests
+            | Self::InternalServerError
+            | Self::Timeout
+            | Self::Network => true,
+            Self::Valid
+            | Self::NotFound
+            | Self::Forbidden
+            | Self::BadRequest
+            | Self::Unauthorized
+            | Self::UsageExceeded
+            | Self::InvalidKeyType
+            | Self::NotUnique
+            | Self::Conflict
+            | Self::DeleteProtected
+            | Self::Expired
+            | Self::Disabled
+            | Self::DryRun
+            | Self::Unknown => false,
+        }
+    }
+
+    /// The canonical http status code for this error code.
+    ///
+    /// # Returns
+    /// The http status code.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::ErrorCode;
+    /// assert_eq!(ErrorCode::NotFound.http_status(), 404);
+    /// assert_eq!(ErrorCode::Unknown.http_status(), 500);
+    /// ```
+    #[must_use]
+    pub fn http_status(&self) -> u16 {
+        match self {
+            Self::Valid | Self::DryRun => 200,
+            Self::BadRequest | Self::InvalidKeyType => 400,
+            Self::Unauthorized => 401,
+            Self::Forbidden | Self::DeleteProtected | Self::Expired | Self::Disabled => 403,
+            Self::NotFound => 404,
+            Self::Timeout => 408,
+            Self::NotUnique | Self::Conflict => 409,
+            Self::RateLimited | Self::TooManyRequests | Self::UsageExceeded => 429,
+            Self::InternalServerError | Self::Unknown => 500,
+            Self::Network => 503,
+        }
+    }
+
+    /// The coarse grained category this error code belongs to.
+    ///
+    /// # Returns
+    /// The [`ErrorCategory`] for this error code.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::ErrorCategory;
+    /// # use unkey::models::ErrorCode;
+    /// assert_eq!(ErrorCode::NotFound.category(), ErrorCategory::Client);
+    /// assert_eq!(ErrorCode::Timeout.category(), ErrorCategory::Server);
+    /// assert_eq!(ErrorCode::RateLimited.category(), ErrorCategory::RateLimit);
+    /// ```
+    #[must_use]
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::NotFound
+            | Self::Forbidden
+            | Self::BadRequest
+            | Self::Unauthorized
+            | Self::InvalidKeyType
+            | Self::NotUnique
+            | Self::Conflict
+            | Self::DeleteProtected
+            | Self::Expired
+            | Self::Disabled => ErrorCategory::Client,
+            Self::InternalServerError | Self::Timeout | Self::Network => ErrorCategory::Server,
+            Self::RateLimited | Self::TooManyRequests | Self::UsageExceeded => {
+                ErrorCategory::RateLimit
+            }
+            Self::Valid | Self::DryRun | Self::Unknown => ErrorCategory::Unknown,
+        }
+    }
+}
+
+impl From<&str> for ErrorCode {
+    /// Parses the `SCREAMING_SNAKE_CASE` code sent by the api, falling back
+    /// to [`ErrorCode::Unknown`] for unrecognized codes, matching
+    /// deserialization.
+    ///
+    /// As this never fails, `TryFrom<&str>` is provided with an
+    /// [`Infallible`] error.
+    ///
+    /// [`Infallible`]: std::convert::Infallible
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::ErrorCode;
+    /// assert_eq!(ErrorCode::from("NOT_FOUND"), ErrorCode::NotFound);
+    /// assert_eq!(ErrorCode::try_from("RATE_LIMITED"), Ok(ErrorCode::RateLimited));
+    /// assert_eq!(ErrorCode::from("SOMETHING_NEW"), ErrorCode::Unknown);
+    /// ```
+    fn from(code: &str) -> Self {
+        let de: StrDeserializer<'_, serde::de::value::Error> = code.into_deserializer();
+        Self::deserialize(de).unwrap_or(Self::Unknown)
+    }
+}
+
+impl std::str::FromStr for ErrorCode {
+    type Err = std::convert::Infallible;
+
+    /// Parses the `SCREAMING_SNAKE_CASE` code sent by the api, falling back
+    /// to [`ErrorCode::Unknown`] for unrecognized codes.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::ErrorCode;
+    /// let code: ErrorCode = "DELETE_PROTECTED".parse().unwrap();
+    ///
+    /// assert_eq!(code, ErrorCode::DeleteProtected);
+    /// ```
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(code))
+    }
+}
+
+/// A stable, coarse grained grouping of [`ErrorCode`] variants.
+///
+/// Unlike [`ErrorCode`], new variants are not expected to be added here,
+/// so it can be matched exhaustively.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ErrorCategory {
+    /// The request was invalid or not permitted, retrying won't help.
+    Client,
+
+    /// The api or the connection to it failed.
+    Server,
+
+    /// The request was rejected due to ratelimiting or exceeded usage.
+    RateLimit,
+
+    /// The error could not be categorized.
+    Unknown,
+}
+
 /// An http error representation.
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Clone, Deserialize, Eq, PartialEq)]
 pub struct HttpError {
     /// The error code for the error.
+    ///
+    /// This is never [`ErrorCode::Valid`], which only appears as the
+    /// [`VerifyKeyResponse::code`] of a successful verification.
+    ///
+    /// [`VerifyKeyResponse::code`]: crate::models::VerifyKeyResponse::code
     pub code: ErrorCode,
 
     /// The error message.
     pub message: String,
+
+    /// The id of the failed request, useful when contacting unkey support.
+    #[serde(default, rename = "requestId")]
+    pub request_id: Option<String>,
+
+    /// The raw http status code of the response, if one was received.
+    #[serde(skip)]
+    pub status: Option<u16>,
+
+    /// The structured error details, if any, i.e. which field failed
+    /// validation.
+    #[serde(default)]
+    pub details: Option<Value>,
 }
 
 impl HttpError {
@@ -84,50 +341,496 @@ impl HttpError {
     /// # use unkey::models::ErrorCode;
     /// let e = HttpError {
     ///     code: ErrorCode::Unknown,
-    ///     message: String::from("err")
+    ///     message: String::from("err"),
+    ///     request_id: None,
+    ///     status: None,
+    ///     details: None,
     /// };
     ///
     /// assert_eq!(e.code, ErrorCode::Unknown);
     /// assert_eq!(e.message, String::from("err"));
+    /// assert_eq!(e.request_id, None);
+    /// assert_eq!(e.status, None);
+    /// assert_eq!(e.details, None);
     /// ```
     #[must_use]
+    #[rustfmt::skip]
     pub(crate) fn new(code: ErrorCode, message: String) -> Self {
-        Self { code, message }
+        debug_assert_ne!(code, ErrorCode::Valid, "an error can't be valid");
+        Self { code, message, request_id: None, status: None, details: None }
+    }
+
+    /// Replaces an [`ErrorCode::Valid`] code received from the api with
+    /// [`ErrorCode::Unknown`], as an error is never valid.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    #[must_use]
+    pub(crate) fn without_valid_code(mut self) -> Self {
+        if self.code == ErrorCode::Valid {
+            logging::error!(format!(
+                "Received an error with a VALID code: {}",
+                self.message
+            ));
+            self.code = ErrorCode::Unknown;
+        }
+
+        self
+    }
+
+    /// Sets the http status code, unless one is already present.
+    ///
+    /// # Arguments
+    /// - `status`: The http status code of the response.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    #[must_use]
+    pub(crate) fn with_status(mut self, status: Option<u16>) -> Self {
+        self.status = self.status.or(status);
+        self
+    }
+}
+
+impl From<reqwest::Error> for HttpError {
+    fn from(err: reqwest::Error) -> Self {
+        let code = if err.is_timeout() {
+            ErrorCode::Timeout
+        } else if is_connect(&err) {
+            ErrorCode::Network
+        } else {
+            ErrorCode::Unknown
+        };
+
+        let status = err.status().map(|s| s.as_u16());
+        Self::new(code, err.to_string()).with_status(status)
+    }
+}
+
+/// Whether the error occurred while connecting.
+#[cfg(not(target_arch = "wasm32"))]
+fn is_connect(err: &reqwest::Error) -> bool {
+    err.is_connect()
+}
+
+/// Whether the error occurred while connecting, which reqwest can't tell
+/// on wasm.
+#[cfg(target_arch = "wasm32")]
+fn is_connect(_err: &reqwest::Error) -> bool {
+    false
+}
+
+impl std::fmt::Display for HttpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: {}", self.code, self.message)?;
+
+        if let Some(status) = self.status {
+            write!(f, " (status: {status})")?;
+        }
+
+        if let Some(id) = &self.request_id {
+            write!(f, " (request id: {id})")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An error returned by the [`Client`].
+///
+/// [`HttpError`] remains the payload for errors reported by the api, while
+/// the other variants describe failures before a usable response arrived.
+///
+/// [`Client`]: crate::Client
+///
+/// # Example
+/// ```
+/// use unkey::models::Error;
+/// use unkey::models::ErrorCode;
+///
+/// fn describe(err: &Error) -> String {
+///     match err {
+///         Error::Timeout => String::from("timed out, try again"),
+///         Error::Network(e) => format!("connection failed: {e}"),
+///         Error::Decode { message, .. } => format!("unexpected response: {message}"),
+///         Error::Api(e) if e.code == ErrorCode::NotFound => String::from("missing"),
+///         Error::Api(e) => format!("api error: {}", e.message),
+///         _ => err.to_string(),
+///     }
+/// }
+///
+/// assert_eq!(describe(&Error::Timeout), "timed out, try again");
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The request timed out before a response was received.
+    Timeout,
+
+    /// The request failed to send, or its response failed to be read.
+    Network(reqwest::Error),
+
+    /// The response body could not be decoded, i.e. the api returned an
+    /// unexpected shape.
+    Decode {
+        /// The decoding error, including a redacted snippet of the body.
+        message: String,
+
+        /// The http status code of the response, if one was received.
+        status: Option<u16>,
+    },
+
+    /// The api returned an error, or the request failed local validation
+    /// before it was sent.
+    Api(HttpError),
+}
+
+impl Error {
+    /// The [`ErrorCode`] best describing this error.
+    ///
+    /// # Returns
+    /// The api error code, or the equivalent code for transport errors.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::Error;
+    /// # use unkey::models::ErrorCode;
+    /// assert_eq!(Error::Timeout.code(), ErrorCode::Timeout);
+    /// ```
+    #[must_use]
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Timeout => ErrorCode::Timeout,
+            Self::Network(_) => ErrorCode::Network,
+            Self::Decode { .. } => ErrorCode::Unknown,
+            Self::Api(e) => e.code.clone(),
+        }
+    }
+
+    /// The raw http status code of the response, if one was received.
+    ///
+    /// # Returns
+    /// The status code, if any.
+    #[must_use]
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::Timeout => None,
+            Self::Network(e) => e.status().map(|s| s.as_u16()),
+            Self::Decode { status, .. } => *status,
+            Self::Api(e) => e.status,
+        }
+    }
+
+    /// The api error, if this is an [`Error::Api`].
+    ///
+    /// # Returns
+    /// The [`HttpError`], if any.
+    #[must_use]
+    pub fn as_api(&self) -> Option<&HttpError> {
+        match self {
+            Self::Api(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Sets the http status code of the response, unless one is already
+    /// present, and replaces an [`ErrorCode::Valid`] api error code with
+    /// [`ErrorCode::Unknown`].
+    ///
+    /// # Arguments
+    /// - `status`: The http status code of the response.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    #[must_use]
+    pub(crate) fn with_status(self, status: Option<u16>) -> Self {
+        match self {
+            Self::Api(e) => Self::Api(e.without_valid_code().with_status(status)),
+            Self::Decode { message, status: s } => Self::Decode {
+                message,
+                status: s.or(status),
+            },
+            e => e,
+        }
+    }
+}
+
+impl From<HttpError> for Error {
+    fn from(err: HttpError) -> Self {
+        Self::Api(err)
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            Self::Timeout
+        } else {
+            Self::Network(err)
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Timeout => write!(f, "The request timed out"),
+            Self::Network(e) => write!(f, "Network error: {e}"),
+            Self::Decode { message, .. } => write!(f, "Failed to decode response: {message}"),
+            Self::Api(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Network(e) => Some(e),
+            _ => None,
+        }
     }
 }
 
 /// A wrapper around the response type or an error.
-#[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 #[must_use = "this `Wrapped` result may be an `Err` variant, which should be handled"]
 pub(crate) enum Wrapped<T> {
-    /// The error value.
-    #[serde(rename = "error")]
+    /// The error value, from a body with an `error` key.
     Err(HttpError),
 
     /// The ok value.
-    #[serde(untagged)]
     Ok(T),
+
+    /// A response matching neither the response type nor the error
+    /// envelope, i.e. if the api changed its shape. The raw value is kept
+    /// for diagnostics.
+    Unknown {
+        /// The raw response value.
+        value: Value,
+
+        /// The error from deserializing the value as the response type.
+        error: String,
+    },
 }
 
-impl<T> From<Wrapped<T>> for Result<T, HttpError> {
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Wrapped<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+
+        if let Some(error) = value.get("error").filter(|e| !e.is_null()) {
+            return HttpError::deserialize(error)
+                .map(Self::Err)
+                .map_err(D::Error::custom);
+        }
+
+        // Deserializing from the value keeps serde's error, i.e. the
+        // missing or unknown field, rather than discarding it
+        match T::deserialize(&value) {
+            Ok(res) => Ok(Self::Ok(res)),
+            Err(e) => Ok(Self::Unknown {
+                value,
+                error: e.to_string(),
+            }),
+        }
+    }
+}
+
+impl<T> From<Wrapped<T>> for Result<T, Error> {
     fn from(wrapped: Wrapped<T>) -> Self {
         match wrapped {
-            Wrapped::Err(err) => Err(err),
+            Wrapped::Err(err) => Err(Error::Api(err)),
             Wrapped::Ok(res) => Ok(res),
+            Wrapped::Unknown { value, error } => {
+                let snippet = crate::body_snippet(&value.to_string());
+                let message = format!("{error} in response: {snippet}");
+                Err(Error::Decode {
+                    message,
+                    status: None,
+                })
+            }
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    use crate::mock::block_on;
+
+    use super::Error;
+    use super::ErrorCategory;
     use super::ErrorCode;
     use super::HttpError;
     use super::Wrapped;
 
+    /// Every variant alongside its expected retryability and status.
+    const EXPECTED: [(ErrorCode, bool, u16); 19] = [
+        (ErrorCode::Valid, false, 200),
+        (ErrorCode::NotFound, false, 404),
+        (ErrorCode::Forbidden, false, 403),
+        (ErrorCode::BadRequest, false, 400),
+        (ErrorCode::RateLimited, true, 429),
+        (ErrorCode::Unauthorized, false, 401),
+        (ErrorCode::UsageExceeded, false, 429),
+        (ErrorCode::InternalServerError, true, 500),
+        (ErrorCode::InvalidKeyType, false, 400),
+        (ErrorCode::NotUnique, false, 409),
+        (ErrorCode::Conflict, false, 409),
+        (ErrorCode::DeleteProtected, false, 403),
+        (ErrorCode::Expired, false, 403),
+        (ErrorCode::Disabled, false, 403),
+        (ErrorCode::TooManyRequests, true, 429),
+        (ErrorCode::Timeout, true, 408),
+        (ErrorCode::Network, true, 503),
+        (ErrorCode::DryRun, false, 200),
+        (ErrorCode::Unknown, false, 500),
+    ];
+
+    #[test]
+    fn test_is_retryable() {
+        for (code, retryable, _) in EXPECTED {
+            assert_eq!(code.is_retryable(), retryable, "{code:?}");
+        }
+    }
+
+    #[test]
+    fn test_http_status() {
+        for (code, _, status) in EXPECTED {
+            assert_eq!(code.http_status(), status, "{code:?}");
+        }
+    }
+
+    #[test]
+    fn test_category() {
+        let expected = [
+            (ErrorCode::Valid, ErrorCategory::Unknown),
+            (ErrorCode::NotFound, ErrorCategory::Client),
+            (ErrorCode::Forbidden, ErrorCategory::Client),
+            (ErrorCode::BadRequest, ErrorCategory::Client),
+            (ErrorCode::RateLimited, ErrorCategory::RateLimit),
+            (ErrorCode::Unauthorized, ErrorCategory::Client),
+            (ErrorCode::UsageExceeded, ErrorCategory::RateLimit),
+            (ErrorCode::InternalServerError, ErrorCategory::Server),
+            (ErrorCode::InvalidKeyType, ErrorCategory::Client),
+            (ErrorCode::NotUnique, ErrorCategory::Client),
+            (ErrorCode::Conflict, ErrorCategory::Client),
+            (ErrorCode::DeleteProtected, ErrorCategory::Client),
+            (ErrorCode::Expired, ErrorCategory::Client),
+            (ErrorCode::Disabled, ErrorCategory::Client),
+            (ErrorCode::TooManyRequests, ErrorCategory::RateLimit),
+            (ErrorCode::Timeout, ErrorCategory::Server),
+            (ErrorCode::Network, ErrorCategory::Server),
+            (ErrorCode::DryRun, ErrorCategory::Unknown),
+            (ErrorCode::Unknown, ErrorCategory::Unknown),
+        ];
+
+        assert_eq!(expected.len(), EXPECTED.len());
+
+        for (code, category) in expected {
+            assert_eq!(code.category(), category, "{code:?}");
+        }
+    }
+
+    /// Deserializes the body, expecting an [`Error::Api`].
+    fn api_error(body: &str) -> HttpError {
+        let wrapped: Wrapped<u8> = serde_json::from_str(body).unwrap();
+
+        match Result::from(wrapped) {
+            Err(Error::Api(e)) => e,
+            res => panic!("expected an api error, got {res:?}"),
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_fallible_conversions)]
+    fn test_from_str() {
+        let expected = [
+            ("VALID", ErrorCode::Valid),
+            ("NOT_FOUND", ErrorCode::NotFound),
+            ("BAD_REQUEST", ErrorCode::BadRequest),
+            ("RATE_LIMITED", ErrorCode::RateLimited),
+            ("INTERNAL_SERVER_ERROR", ErrorCode::InternalServerError),
+            ("DELETE_PROTECTED", ErrorCode::DeleteProtected),
+            ("TOO_MANY_REQUESTS", ErrorCode::TooManyRequests),
+            ("DRY_RUN", ErrorCode::DryRun),
+        ];
+
+        for (code, variant) in expected {
+            assert_eq!(ErrorCode::from(code), variant, "{code}");
+            assert_eq!(ErrorCode::try_from(code), Ok(variant.clone()), "{code}");
+            assert_eq!(code.parse::<ErrorCode>(), Ok(variant), "{code}");
+        }
+    }
+
+    #[test]
+    fn test_from_str_unknown() {
+        assert_eq!(ErrorCode::from("SOMETHING_NEW"), ErrorCode::Unknown);
+        assert_eq!(ErrorCode::from("not_found"), ErrorCode::Unknown);
+        assert_eq!("".parse::<ErrorCode>(), Ok(ErrorCode::Unknown));
+    }
+
+    #[test]
+    fn test_deserialize_request_id() {
+        let body = r#"{"error":{"code":"NOT_FOUND","message":"key not found","docs":"https://unkey.dev/docs","requestId":"req_123"}}"#;
+        let err = api_error(body);
+
+        assert_eq!(err.code, ErrorCode::NotFound);
+        assert_eq!(err.message, "key not found");
+        assert_eq!(err.request_id.as_deref(), Some("req_123"));
+    }
+
+    #[test]
+    fn test_valid_code_maps_to_unknown() {
+        let body = r#"{"error":{"code":"VALID","message":"confused api"}}"#;
+        let err = api_error(body).without_valid_code();
+
+        assert_eq!(err.code, ErrorCode::Unknown);
+        assert_eq!(err.message, "confused api");
+    }
+
+    #[test]
+    fn test_deserialize_details() {
+        let body = r#"{"error":{"code":"BAD_REQUEST","message":"invalid meta","details":[{"field":"meta","message":"too large"}]}}"#;
+        let err = api_error(body);
+
+        assert_eq!(err.code, ErrorCode::BadRequest);
+        assert_eq!(
+            err.details,
+            Some(serde_json::json!([{"field": "meta", "message": "too large"}]))
+        );
+    }
+
+    #[test]
+    fn test_deserialize_without_request_id() {
+        let body = r#"{"error":{"code":"NOT_FOUND","message":"key not found"}}"#;
+
+        assert_eq!(api_error(body).request_id, None);
+    }
+
+    #[test]
+    fn test_display() {
+        let mut err = HttpError::new(ErrorCode::NotFound, "key not found".to_string());
+        assert_eq!(err.to_string(), "NotFound: key not found");
+
+        err.request_id = Some("req_123".to_string());
+        assert_eq!(
+            err.to_string(),
+            "NotFound: key not found (request id: req_123)"
+        );
+
+        err.status = Some(404);
+        assert_eq!(
+            err.to_string(),
+            "NotFound: key not found (status: 404) (request id: req_123)"
+        );
+    }
+
     #[test]
     fn test_from_wrapped_ok() {
         let wrapped = Wrapped::Ok(120);
-        let result: Result<_, HttpError> = wrapped.into();
+        let result: Result<_, Error> = wrapped.into();
 
         assert_eq!(result.unwrap(), 120);
     }
@@ -136,8 +839,173 @@ mod tests {
     fn test_from_wrapped_err() {
         let err = HttpError::new(ErrorCode::Conflict, "test".to_string());
         let wrapped = Wrapped::Err(err.clone());
-        let result: Result<u8, HttpError> = wrapped.into();
+        let result: Result<u8, Error> = wrapped.into();
+
+        assert_eq!(result.unwrap_err().as_api(), Some(&err));
+    }
+
+    #[test]
+    fn test_from_wrapped_unknown() {
+        let body = r#"{"result":{"keyId":"key_123","key":"unkey_secret"},"meta":{}}"#;
+        let wrapped: Wrapped<u8> = serde_json::from_str(body).unwrap();
+
+        assert!(matches!(wrapped, Wrapped::Unknown { .. }));
+
+        let err = Result::from(wrapped).unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::Unknown);
+
+        let (message, status) = match err {
+            Error::Decode { message, status } => (message, status),
+            other => panic!("expected a decode error, got {other:?}"),
+        };
+
+        assert!(message.starts_with("invalid type: map, expected u8 in response: "));
+        assert!(message.contains(r#""keyId":"key_123""#));
+        assert!(!message.contains("unkey_secret"));
+        assert_eq!(status, None);
+    }
+
+    #[test]
+    fn test_wrapped_keeps_missing_field() {
+        #[derive(Debug, serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        #[allow(dead_code)]
+        struct Res {
+            key_id: String,
+        }
+
+        let wrapped: Wrapped<Res> = serde_json::from_str(r#"{"name":"test"}"#).unwrap();
+        let error = match wrapped {
+            Wrapped::Unknown { error, .. } => error,
+            other => panic!("expected an unknown value, got {other:?}"),
+        };
+
+        assert_eq!(error, "missing field `keyId`");
+    }
+
+    #[test]
+    fn test_wrapped_malformed_error() {
+        let body = r#"{"error":{"message":"no code"}}"#;
+        let err = serde_json::from_str::<Wrapped<u8>>(body).unwrap_err();
+
+        assert!(err.to_string().contains("missing field `code`"));
+    }
+
+    #[test]
+    fn test_from_reqwest_connect_error() {
+        // Bind then drop the listener so nothing is listening on the port
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let url = format!("http://127.0.0.1:{port}");
+        let err = block_on(reqwest::Client::new().get(url).send()).unwrap_err();
+
+        assert_eq!(HttpError::from(err).code, ErrorCode::Network);
+
+        let url = format!("http://127.0.0.1:{port}");
+        let err = block_on(reqwest::Client::new().get(url).send()).unwrap_err();
+        let err = Error::from(err);
+
+        assert!(matches!(err, Error::Network(_)));
+        assert_eq!(err.code(), ErrorCode::Network);
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn test_from_reqwest_timeout_error() {
+        // Accepts connections but never responds
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+
+        let err = block_on(async { client.get(&url).send().await }).unwrap_err();
+
+        assert_eq!(HttpError::from(err).code, ErrorCode::Timeout);
+
+        let err = block_on(async { client.get(&url).send().await }).unwrap_err();
+
+        assert!(matches!(Error::from(err), Error::Timeout));
+    }
+
+    #[test]
+    fn test_from_reqwest_other_error() {
+        let err = block_on(reqwest::Client::new().get("not a url").send()).unwrap_err();
+
+        assert_eq!(HttpError::from(err).code, ErrorCode::Unknown);
+    }
+
+    #[test]
+    fn test_error_timeout() {
+        let err = Error::Timeout;
+
+        assert!(matches!(err, Error::Timeout));
+        assert_eq!(err.code(), ErrorCode::Timeout);
+        assert_eq!(err.status(), None);
+        assert_eq!(err.as_api(), None);
+        assert_eq!(err.to_string(), "The request timed out");
+    }
+
+    #[test]
+    fn test_error_network() {
+        let err = reqwest::Client::new().get("not a url").build().unwrap_err();
+        let err = Error::Network(err);
+
+        assert!(matches!(err, Error::Network(_)));
+        assert_eq!(err.code(), ErrorCode::Network);
+        assert_eq!(err.status(), None);
+        assert!(err.to_string().starts_with("Network error: "));
+    }
+
+    #[test]
+    fn test_error_decode() {
+        let err = Error::Decode {
+            message: String::from("expected value"),
+            status: None,
+        }
+        .with_status(Some(502));
+
+        assert!(matches!(err, Error::Decode { .. }));
+        assert_eq!(err.code(), ErrorCode::Unknown);
+        assert_eq!(err.status(), Some(502));
+        assert_eq!(err.to_string(), "Failed to decode response: expected value");
+    }
+
+    #[test]
+    fn test_error_api() {
+        let http = HttpError::new(ErrorCode::NotFound, String::from("key not found"));
+        let err = Error::from(http.clone()).with_status(Some(404));
+
+        match &err {
+            Error::Api(e) => assert_eq!(e.message, "key not found"),
+            _ => panic!("expected an api error, got {err:?}"),
+        }
+
+        assert_eq!(err.code(), ErrorCode::NotFound);
+        assert_eq!(err.status(), Some(404));
+        assert_eq!(err.as_api().unwrap().request_id, http.request_id);
+        assert_eq!(err.to_string(), "NotFound: key not found (status: 404)");
+    }
+
+    #[test]
+    fn test_error_api_valid_code() {
+        let http = HttpError {
+            code: ErrorCode::Valid,
+            message: String::from("confused api"),
+            request_id: None,
+            status: None,
+            details: None,
+        };
 
-        assert_eq!(result.unwrap_err(), err);
+        assert_eq!(
+            Error::Api(http).with_status(None).code(),
+            ErrorCode::Unknown
+        );
     }
 }
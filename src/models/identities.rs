@@ -0,0 +1,470 @@
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::UndefinedOr;
+
+/// The maximum number of identities the api returns per page.
+pub const LIST_IDENTITIES_MAX_LIMIT: usize = 100;
+
+/// An identity grouping keys that share ratelimits and meta, i.e. all the
+/// keys belonging to one of your users.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct Identity {
+    /// The unique id of the identity.
+    pub id: String,
+
+    /// The id of the user or organization in your system this identity
+    /// represents.
+    pub external_id: String,
+
+    /// The dynamic meta mapping shared by the identity's keys, if any.
+    pub meta: Option<Value>,
+
+    /// The named ratelimits shared by the identity's keys, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ratelimits: Option<Vec<IdentityRatelimit>>,
+}
+
+/// A named ratelimit shared by every key of an identity.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct IdentityRatelimit {
+    /// The name of the ratelimit, referenced when verifying a key.
+    pub name: String,
+
+    /// The number of requests allowed in each window.
+    pub limit: usize,
+
+    /// The duration of each window, in ms.
+    pub duration: usize,
+}
+
+impl IdentityRatelimit {
+    /// Creates a new identity ratelimit.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the ratelimit.
+    /// - `limit`: The number of requests allowed in each window.
+    /// - `duration`: The duration of each window, in ms.
+    ///
+    /// # Returns
+    /// The new identity ratelimit.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::IdentityRatelimit;
+    /// let r = IdentityRatelimit::new("requests", 10, 1000);
+    ///
+    /// assert_eq!(r.name, String::from("requests"));
+    /// assert_eq!(r.limit, 10);
+    /// assert_eq!(r.duration, 1000);
+    /// ```
+    #[must_use]
+    pub fn new<T: Into<String>>(name: T, limit: usize, duration: usize) -> Self {
+        Self {
+            name: name.into(),
+            limit,
+            duration,
+        }
+    }
+}
+
+/// An outgoing create identity request.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateIdentityRequest {
+    /// The id of the user or organization in your system the identity
+    /// represents.
+    pub external_id: String,
+
+    /// The optional dynamic meta mapping shared by the identity's keys.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub meta: UndefinedOr<Value>,
+
+    /// The optional named ratelimits shared by the identity's keys.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub ratelimits: UndefinedOr<Vec<IdentityRatelimit>>,
+}
+
+impl CreateIdentityRequest {
+    /// Creates a new create identity request.
+    ///
+    /// # Arguments
+    /// - `external_id`: The id of the user or organization in your system.
+    ///
+    /// # Returns
+    /// The new create identity request.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::CreateIdentityRequest;
+    /// # use unkey::models::UndefinedOr;
+    /// let r = CreateIdentityRequest::new("user_123");
+    ///
+    /// assert_eq!(r.external_id, String::from("user_123"));
+    /// assert_eq!(r.meta, UndefinedOr::Undefined);
+    /// assert_eq!(r.ratelimits, UndefinedOr::Undefined);
+    /// ```
+    #[must_use]
+    pub fn new<T: Into<String>>(external_id: T) -> Self {
+        Self {
+            external_id: external_id.into(),
+            meta: UndefinedOr::Undefined,
+            ratelimits: UndefinedOr::Undefined,
+        }
+    }
+
+    /// Sets the dynamic meta mapping for the new identity.
+    ///
+    /// # Arguments
+    /// - `meta`: The meta to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::CreateIdentityRequest;
+    /// # use serde_json::json;
+    /// let r = CreateIdentityRequest::new("user_123").set_meta(json!({"plan": "pro"}));
+    ///
+    /// assert_eq!(r.meta.inner().unwrap(), &json!({"plan": "pro"}));
+    /// ```
+    #[must_use]
+    pub fn set_meta(mut self, meta: Value) -> Self {
+        self.meta = UndefinedOr::Value(meta);
+        self
+    }
+
+    /// Sets the named ratelimits for the new identity.
+    ///
+    /// # Arguments
+    /// - `ratelimits`: The ratelimits to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::CreateIdentityRequest;
+    /// # use unkey::models::IdentityRatelimit;
+    /// let r = CreateIdentityRequest::new("user_123")
+    ///     .set_ratelimits(vec![IdentityRatelimit::new("requests", 10, 1000)]);
+    ///
+    /// assert_eq!(r.ratelimits.inner().unwrap().len(), 1);
+    /// ```
+    #[must_use]
+    pub fn set_ratelimits(mut self, ratelimits: Vec<IdentityRatelimit>) -> Self {
+        self.ratelimits = UndefinedOr::Value(ratelimits);
+        self
+    }
+}
+
+/// An incoming create identity response.
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct CreateIdentityResponse {
+    /// The unique id of the new identity.
+    pub identity_id: String,
+}
+
+/// An outgoing get identity request, by identity id or external id.
+#[derive(Debug, Clone)]
+pub struct GetIdentityRequest {
+    /// The id of the identity to get.
+    pub identity_id: Option<String>,
+
+    /// The external id of the identity to get.
+    pub external_id: Option<String>,
+}
+
+impl GetIdentityRequest {
+    /// Creates a new get identity request by identity id.
+    ///
+    /// # Arguments
+    /// - `identity_id`: The id of the identity to get.
+    ///
+    /// # Returns
+    /// The new get identity request.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::GetIdentityRequest;
+    /// let r = GetIdentityRequest::new("id_123");
+    ///
+    /// assert_eq!(r.identity_id.unwrap(), String::from("id_123"));
+    /// assert_eq!(r.external_id, None);
+    /// ```
+    #[must_use]
+    pub fn new<T: Into<String>>(identity_id: T) -> Self {
+        Self {
+            identity_id: Some(identity_id.into()),
+            external_id: None,
+        }
+    }
+
+    /// Creates a new get identity request by external id.
+    ///
+    /// # Arguments
+    /// - `external_id`: The external id of the identity to get.
+    ///
+    /// # Returns
+    /// The new get identity request.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::GetIdentityRequest;
+    /// let r = GetIdentityRequest::with_external_id("user_123");
+    ///
+    /// assert_eq!(r.identity_id, None);
+    /// assert_eq!(r.external_id.unwrap(), String::from("user_123"));
+    /// ```
+    #[must_use]
+    pub fn with_external_id<T: Into<String>>(external_id: T) -> Self {
+        Self {
+            identity_id: None,
+            external_id: Some(external_id.into()),
+        }
+    }
+}
+
+impl From<&str> for GetIdentityRequest {
+    fn from(identity_id: &str) -> Self {
+        Self::new(identity_id)
+    }
+}
+
+impl From<String> for GetIdentityRequest {
+    fn from(identity_id: String) -> Self {
+        Self::new(identity_id)
+    }
+}
+
+/// An outgoing paginated list identities request.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListIdentitiesRequest {
+    /// The optional number of identities to return, up to 100.
+    ///
+    /// *Note*: Prefer [`ListIdentitiesRequest::set_limit`], which clamps the value.
+    pub limit: Option<usize>,
+
+    /// The pagination cursor indicating the last identity that was returned.
+    pub cursor: Option<String>,
+}
+
+impl ListIdentitiesRequest {
+    /// Creates a new list identities request.
+    ///
+    /// # Returns
+    /// The new list identities request.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::ListIdentitiesRequest;
+    /// let r = ListIdentitiesRequest::new();
+    ///
+    /// assert_eq!(r.limit, None);
+    /// assert_eq!(r.cursor, None);
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the limit for the request.
+    ///
+    /// The api accepts at most 100 identities per page, so the limit is
+    /// clamped to `1..=100`.
+    ///
+    /// # Arguments
+    /// - `limit`: The limit to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::ListIdentitiesRequest;
+    /// let r = ListIdentitiesRequest::new().set_limit(50);
+    ///
+    /// assert_eq!(r.limit.unwrap(), 50);
+    ///
+    /// let r = r.set_limit(0);
+    ///
+    /// assert_eq!(r.limit.unwrap(), 1);
+    /// ```
+    #[must_use]
+    pub fn set_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit.clamp(1, LIST_IDENTITIES_MAX_LIMIT));
+        self
+    }
+
+    /// Sets the pagination cursor for the request.
+    ///
+    /// # Arguments
+    /// - `cursor`: The pagination cursor to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::ListIdentitiesRequest;
+    /// let r = ListIdentitiesRequest::new().set_cursor("abcabc");
+    ///
+    /// assert_eq!(r.cursor.unwrap(), String::from("abcabc"));
+    /// ```
+    #[must_use]
+    pub fn set_cursor<T: Into<String>>(mut self, cursor: T) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+}
+
+/// An incoming paginated list identities response.
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct ListIdentitiesResponse {
+    /// The identities included in this page.
+    pub identities: Vec<Identity>,
+
+    /// The cursor indicating the last identity that was returned.
+    pub cursor: Option<String>,
+
+    /// The total number of identities in the workspace.
+    pub total: usize,
+}
+
+/// An outgoing request to delete an identity.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteIdentityRequest {
+    /// The id of the identity to delete.
+    pub identity_id: String,
+}
+
+impl DeleteIdentityRequest {
+    /// Creates a new delete identity request.
+    ///
+    /// # Arguments
+    /// - `identity_id`: The id of the identity to delete.
+    ///
+    /// # Returns
+    /// The new delete identity request.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::DeleteIdentityRequest;
+    /// let r = DeleteIdentityRequest::new("id_123");
+    ///
+    /// assert_eq!(r.identity_id, String::from("id_123"));
+    /// ```
+    #[must_use]
+    pub fn new<T: Into<String>>(identity_id: T) -> Self {
+        Self {
+            identity_id: identity_id.into(),
+        }
+    }
+}
+
+impl From<&str> for DeleteIdentityRequest {
+    fn from(identity_id: &str) -> Self {
+        Self::new(identity_id)
+    }
+}
+
+impl From<String> for DeleteIdentityRequest {
+    fn from(identity_id: String) -> Self {
+        Self::new(identity_id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::CreateIdentityRequest;
+    use super::DeleteIdentityRequest;
+    use super::Identity;
+    use super::IdentityRatelimit;
+    use super::ListIdentitiesResponse;
+
+    #[test]
+    fn identity_deserialize() {
+        let body = r#"{"id":"id_123","externalId":"user_123","meta":{"plan":"pro"}}"#;
+        let res: Identity = serde_json::from_str(body).unwrap();
+
+        assert_eq!(res.id, "id_123");
+        assert_eq!(res.external_id, "user_123");
+        assert_eq!(res.meta, Some(json!({"plan": "pro"})));
+        assert_eq!(res.ratelimits, None);
+    }
+
+    #[test]
+    fn identity_deserialize_without_meta() {
+        let body = r#"{"id":"id_123","externalId":"user_123"}"#;
+        let res: Identity = serde_json::from_str(body).unwrap();
+
+        assert_eq!(res.meta, None);
+    }
+
+    #[test]
+    fn identity_deserialize_ratelimits() {
+        let body = r#"{"id":"id_123","externalId":"user_123","ratelimits":[{"name":"requests","limit":10,"duration":1000}]}"#;
+        let res: Identity = serde_json::from_str(body).unwrap();
+
+        assert_eq!(
+            res.ratelimits,
+            Some(vec![IdentityRatelimit::new("requests", 10, 1000)])
+        );
+    }
+
+    #[test]
+    fn create_identity_request_serialize() {
+        let req = CreateIdentityRequest::new("user_123");
+
+        assert_eq!(
+            serde_json::to_string(&req).unwrap(),
+            r#"{"externalId":"user_123"}"#
+        );
+    }
+
+    #[test]
+    fn create_identity_request_serialize_full() {
+        let req = CreateIdentityRequest::new("user_123")
+            .set_meta(json!({"plan": "pro"}))
+            .set_ratelimits(vec![IdentityRatelimit::new("requests", 10, 1000)]);
+
+        assert_eq!(
+            serde_json::to_string(&req).unwrap(),
+            r#"{"externalId":"user_123","meta":{"plan":"pro"},"ratelimits":[{"name":"requests","limit":10,"duration":1000}]}"#
+        );
+    }
+
+    #[test]
+    fn delete_identity_request_serialize() {
+        let req = DeleteIdentityRequest::from("id_123");
+
+        assert_eq!(
+            serde_json::to_string(&req).unwrap(),
+            r#"{"identityId":"id_123"}"#
+        );
+    }
+
+    #[test]
+    fn list_identities_response_deserialize() {
+        let body = r#"{"identities":[{"id":"id_1","externalId":"user_1","ratelimits":[]}],"cursor":"id_1","total":1}"#;
+        let res: ListIdentitiesResponse = serde_json::from_str(body).unwrap();
+
+        assert_eq!(res.identities.len(), 1);
+        assert_eq!(res.identities[0].external_id, "user_1");
+        assert_eq!(res.identities[0].ratelimits, Some(vec![]));
+        assert_eq!(res.cursor, Some(String::from("id_1")));
+        assert_eq!(res.total, 1);
+    }
+}
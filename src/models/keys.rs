@@ -1,13 +1,47 @@
-use std::time::SystemTime;
+use std::collections::HashMap;
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use super::ErrorCode;
+use super::HttpError;
+use super::Identity;
 use super::Ratelimit;
 use super::RatelimitState;
 use super::Refill;
 use super::UndefinedOr;
+use crate::clock;
+
+/// The default maximum size of a keys serialized meta, in bytes.
+pub const DEFAULT_META_SIZE_LIMIT: usize = 1024 * 1024;
+
+/// Checks the serialized size of the meta against the limit.
+///
+/// # Arguments
+/// - `meta`: The meta to check.
+/// - `limit`: The maximum size, in bytes.
+///
+/// # Returns
+/// A [`Result`] indicating whether the meta is within the limit.
+///
+/// # Errors
+/// An [`HttpError`] with [`ErrorCode::BadRequest`] if the meta is too large.
+pub(crate) fn validate_meta_size(meta: &UndefinedOr<Value>, limit: usize) -> Result<(), HttpError> {
+    let size = match meta.inner() {
+        Some(value) => value.to_string().len(),
+        None => return Ok(()),
+    };
+
+    if size > limit {
+        return Err(HttpError::new(
+            ErrorCode::BadRequest,
+            format!("The meta is {size} bytes, which exceeds the limit of {limit} bytes."),
+        ));
+    }
+
+    Ok(())
+}
 
 /// An update operation that can be performed.
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
@@ -17,6 +51,9 @@ pub enum UpdateOp {
     Increment,
 
     /// Decrement operation.
+    ///
+    /// The api clamps the result to zero, so decrementing by more than the
+    /// key has remaining leaves it exhausted rather than underflowing.
     Decrement,
 
     /// Set operation.
@@ -32,6 +69,32 @@ pub struct VerifyKeyRequest {
 
     /// The id of the api this key belongs to.
     pub api_id: String,
+
+    /// The optional ratelimit options for this verification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ratelimit: Option<VerifyKeyRatelimit>,
+}
+
+/// The ratelimit options for a single key verification.
+#[derive(Debug, Clone, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyKeyRatelimit {
+    /// The number of tokens this verification consumes from the ratelimit.
+    pub cost: usize,
+
+    /// The identifier to ratelimit on instead of the key, i.e. a tenant id.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<String>,
+}
+
+impl Default for VerifyKeyRatelimit {
+    fn default() -> Self {
+        // Unkey charges a single token per verification by default
+        Self {
+            cost: 1,
+            identifier: None,
+        }
+    }
 }
 
 impl VerifyKeyRequest {
@@ -51,19 +114,70 @@ impl VerifyKeyRequest {
     ///
     /// assert_eq!(r.key, String::from("test"));
     /// assert_eq!(r.api_id, String::from("api_123"));
+    /// assert_eq!(r.ratelimit, None);
     /// ```
     #[must_use]
+    #[inline]
     pub fn new<T: Into<String>>(key: T, api_id: T) -> Self {
         Self {
             key: key.into(),
             api_id: api_id.into(),
+            ratelimit: None,
         }
     }
+
+    /// Sets the number of ratelimit tokens this verification consumes.
+    ///
+    /// # Arguments
+    /// - `cost`: The cost to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::VerifyKeyRequest;
+    /// let r = VerifyKeyRequest::new("test", "api_123").set_ratelimit_cost(5);
+    ///
+    /// assert_eq!(r.ratelimit.unwrap().cost, 5);
+    /// ```
+    #[must_use]
+    pub fn set_ratelimit_cost(mut self, cost: usize) -> Self {
+        self.ratelimit.get_or_insert_with(Default::default).cost = cost;
+        self
+    }
+
+    /// Sets the identifier this verification is ratelimited on, scoping the
+    /// limit to i.e. a tenant id instead of the key itself.
+    ///
+    /// # Arguments
+    /// - `identifier`: The ratelimit identifier to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::VerifyKeyRequest;
+    /// let r = VerifyKeyRequest::new("test", "api_123").set_ratelimit_identifier("tenant_123");
+    /// let ratelimit = r.ratelimit.unwrap();
+    ///
+    /// assert_eq!(ratelimit.identifier, Some(String::from("tenant_123")));
+    /// assert_eq!(ratelimit.cost, 1);
+    /// ```
+    #[must_use]
+    pub fn set_ratelimit_identifier<T: Into<String>>(mut self, identifier: T) -> Self {
+        self.ratelimit
+            .get_or_insert_with(Default::default)
+            .identifier = Some(identifier.into());
+        self
+    }
 }
 
 /// An incoming verify key response.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct VerifyKeyResponse {
     /// Whether or not the key is valid for any reason.
     ///
@@ -77,6 +191,9 @@ pub struct VerifyKeyResponse {
     pub name: Option<String>,
 
     /// The owner id for this key, if any.
+    ///
+    /// *Note*: Also read from `externalId`, which unkey is migrating to.
+    #[serde(alias = "externalId")]
     pub owner_id: Option<String>,
 
     /// The dynamic mapping of values associated with this key, if any.
@@ -86,8 +203,11 @@ pub struct VerifyKeyResponse {
     /// any limit was set on the key.
     pub remaining: Option<usize>,
 
-    /// The code for the verification.
-    pub code: ErrorCode,
+    /// The reason for the verification result, if any.
+    ///
+    /// e.g. [`ErrorCode::RateLimited`] when `valid` is false due to a
+    /// ratelimit.
+    pub code: Option<ErrorCode>,
 
     /// Whether or not the key is enabled.
     pub enabled: Option<bool>,
@@ -100,6 +220,123 @@ pub struct VerifyKeyResponse {
 
     /// The refill state of this key, if any.
     pub refill: Option<Refill>,
+
+    /// The environment of this key, if any.
+    pub environment: Option<String>,
+
+    /// The identity this key belongs to, if any.
+    pub identity: Option<Identity>,
+}
+
+impl VerifyKeyResponse {
+    /// Deserializes the dynamic meta mapping into the given type.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the typed meta, if any meta was present.
+    ///
+    /// # Errors
+    /// The [`serde_json::Error`] if the meta does not match the given type.
+    ///
+    /// # Example
+    /// ```
+    /// # use serde::Deserialize;
+    /// # use unkey::models::VerifyKeyResponse;
+    /// #[derive(Deserialize)]
+    /// struct Meta {
+    ///     plan: String,
+    /// }
+    ///
+    /// let r: VerifyKeyResponse = serde_json::from_str(r#"{"valid":true,"meta":{"plan":"pro"}}"#).unwrap();
+    /// let meta: Meta = r.get_meta().unwrap().unwrap();
+    ///
+    /// assert_eq!(meta.plan, String::from("pro"));
+    /// ```
+    pub fn get_meta<T: DeserializeOwned>(&self) -> Result<Option<T>, serde_json::Error> {
+        self.meta.as_ref().map(T::deserialize).transpose()
+    }
+
+    /// Whether the verification was rejected due to a ratelimit.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::VerifyKeyResponse;
+    /// let r: VerifyKeyResponse = serde_json::from_str(r#"{"valid":false,"code":"RATE_LIMITED"}"#).unwrap();
+    ///
+    /// assert!(r.is_rate_limited());
+    /// ```
+    #[must_use]
+    pub fn is_rate_limited(&self) -> bool {
+        self.code == Some(ErrorCode::RateLimited)
+    }
+
+    /// Whether the verification was rejected because the key expired.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::VerifyKeyResponse;
+    /// let r: VerifyKeyResponse = serde_json::from_str(r#"{"valid":false,"code":"EXPIRED"}"#).unwrap();
+    ///
+    /// assert!(r.is_expired());
+    /// ```
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        self.code == Some(ErrorCode::Expired)
+    }
+
+    /// Whether the verification was rejected because the key is disabled.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::VerifyKeyResponse;
+    /// let r: VerifyKeyResponse = serde_json::from_str(r#"{"valid":false,"code":"DISABLED"}"#).unwrap();
+    ///
+    /// assert!(r.is_disabled());
+    /// ```
+    #[must_use]
+    pub fn is_disabled(&self) -> bool {
+        self.code == Some(ErrorCode::Disabled)
+    }
+
+    /// The number of verifications remaining for this key.
+    ///
+    /// # Returns
+    /// The remaining verifications, or [`usize::MAX`] if the key has no
+    /// usage limit.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::VerifyKeyResponse;
+    /// let r: VerifyKeyResponse = serde_json::from_str(r#"{"valid":true,"remaining":3}"#).unwrap();
+    ///
+    /// assert_eq!(r.remaining_or_zero(), 3);
+    ///
+    /// let r: VerifyKeyResponse = serde_json::from_str(r#"{"valid":true}"#).unwrap();
+    ///
+    /// assert_eq!(r.remaining_or_zero(), usize::MAX);
+    /// ```
+    #[must_use]
+    pub fn remaining_or_zero(&self) -> usize {
+        self.remaining.unwrap_or(usize::MAX)
+    }
+
+    /// Whether the request this key was verified for should be allowed, i.e.
+    /// the key is valid and has verifications remaining.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::VerifyKeyResponse;
+    /// let r: VerifyKeyResponse = serde_json::from_str(r#"{"valid":true,"remaining":1}"#).unwrap();
+    ///
+    /// assert!(r.should_allow());
+    ///
+    /// let r: VerifyKeyResponse = serde_json::from_str(r#"{"valid":true,"remaining":0}"#).unwrap();
+    ///
+    /// assert!(!r.should_allow());
+    /// ```
+    #[must_use]
+    pub fn should_allow(&self) -> bool {
+        self.valid && self.remaining_or_zero() > 0
+    }
 }
 
 /// An outgoing create key request.
@@ -144,6 +381,35 @@ pub struct CreateKeyRequest {
     /// The keys refill state, if any.
     #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
     pub refill: UndefinedOr<Refill>,
+
+    /// The optional environment for the key, i.e. `test` or `live`.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub environment: UndefinedOr<String>,
+
+    /// Whether the key should be enabled, defaults to true.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub enabled: UndefinedOr<bool>,
+
+    /// The optional roles to attach to the key.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub roles: UndefinedOr<Vec<String>>,
+
+    /// The optional permissions to attach to the key.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub permissions: UndefinedOr<Vec<String>>,
+
+    /// The optional id of an existing identity to attach the key to.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub identity_id: UndefinedOr<String>,
+
+    /// The optional idempotency key, sent as the `Idempotency-Key` header.
+    #[serde(skip)]
+    pub idempotency_key: Option<String>,
+
+    /// Additional fields sent at the top level of the body, i.e. for api
+    /// features the sdk doesn't model yet.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
 impl CreateKeyRequest {
@@ -171,8 +437,16 @@ impl CreateKeyRequest {
     /// assert_eq!(r.remaining, UndefinedOr::Undefined);
     /// assert_eq!(r.ratelimit, UndefinedOr::Undefined);
     /// assert_eq!(r.refill, UndefinedOr::Undefined);
+    /// assert_eq!(r.environment, UndefinedOr::Undefined);
+    /// assert_eq!(r.enabled, UndefinedOr::Undefined);
+    /// assert_eq!(r.roles, UndefinedOr::Undefined);
+    /// assert_eq!(r.permissions, UndefinedOr::Undefined);
+    /// assert_eq!(r.identity_id, UndefinedOr::Undefined);
+    /// assert_eq!(r.idempotency_key, None);
+    /// assert!(r.extra.is_empty());
     /// ```
     #[must_use]
+    #[inline]
     pub fn new<T: Into<String>>(api_id: T) -> Self {
         Self {
             api_id: api_id.into(),
@@ -185,13 +459,27 @@ impl CreateKeyRequest {
             remaining: UndefinedOr::Undefined,
             ratelimit: UndefinedOr::Undefined,
             refill: UndefinedOr::Undefined,
+            environment: UndefinedOr::Undefined,
+            enabled: UndefinedOr::Undefined,
+            roles: UndefinedOr::Undefined,
+            permissions: UndefinedOr::Undefined,
+            identity_id: UndefinedOr::Undefined,
+            idempotency_key: None,
+            extra: HashMap::new(),
         }
     }
 
-    /// Sets the owner id for the new key.
+    /// Sets the external id for the new key, i.e. the id of the user or
+    /// organization in your system that owns it.
+    ///
+    /// ## Note
+    /// Unkey is renaming `ownerId` to `externalId`. The value is stored in
+    /// `owner_id` and sent as `ownerId`, which [`ApiVersion::V1`] expects.
+    ///
+    /// [`ApiVersion::V1`]: crate::models::ApiVersion::V1
     ///
     /// # Arguments
-    /// - `owner_id`: The owner id to set.
+    /// - `external_id`: The external id to set.
     ///
     /// # Returns
     /// Self for chained calls.
@@ -199,16 +487,29 @@ impl CreateKeyRequest {
     /// # Example
     /// ```
     /// # use unkey::models::CreateKeyRequest;
-    /// let r = CreateKeyRequest::new("test").set_owner_id("jonxslays");
+    /// let r = CreateKeyRequest::new("test").set_external_id("jonxslays");
     ///
     /// assert_eq!(r.owner_id.inner().unwrap(), &String::from("jonxslays"));
     /// ```
     #[must_use]
-    pub fn set_owner_id<T: Into<String>>(mut self, owner_id: T) -> Self {
-        self.owner_id = UndefinedOr::Value(owner_id.into());
+    pub fn set_external_id<T: Into<String>>(mut self, external_id: T) -> Self {
+        self.owner_id = UndefinedOr::Value(external_id.into());
         self
     }
 
+    /// Sets the owner id for the new key.
+    ///
+    /// # Arguments
+    /// - `owner_id`: The owner id to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    #[deprecated(since = "0.7.0", note = "use `set_external_id` instead")]
+    #[must_use]
+    pub fn set_owner_id<T: Into<String>>(self, owner_id: T) -> Self {
+        self.set_external_id(owner_id)
+    }
+
     /// Sets the byte length for the new key.
     ///
     /// # Arguments
@@ -294,7 +595,13 @@ impl CreateKeyRequest {
         self
     }
 
-    /// Sets when this key expires.
+    /// Sets when this key expires, relative to now.
+    ///
+    /// ## Note
+    /// The argument is an offset added to the current time, unlike
+    /// [`UpdateKeyRequest::set_expires`]. To set an absolute unix epoch, use
+    /// [`CreateKeyRequest::set_expires_at`] instead. The resulting epoch
+    /// saturates at `usize::MAX` rather than overflowing.
     ///
     /// # Arguments
     /// - `expires`: The number of milliseconds in the future this key should
@@ -321,15 +628,33 @@ impl CreateKeyRequest {
     /// ```
     #[must_use]
     pub fn set_expires(mut self, expires: usize) -> Self {
-        let duration = SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_else(|e| {
-                eprintln!("Error fetching duration since unix epoch: {e}");
-                std::process::exit(1);
-            });
+        let now = usize::try_from(clock::now().as_millis()).unwrap_or(usize::MAX);
+        self.expires = UndefinedOr::Value(now.saturating_add(expires));
+        self
+    }
 
-        let expires = duration.as_millis() as usize + expires;
-        self.expires = UndefinedOr::Value(expires);
+    /// Sets the absolute unix epoch in ms when this key expires.
+    ///
+    /// ## Note
+    /// Unlike [`CreateKeyRequest::set_expires`], the argument is not added
+    /// to the current time.
+    ///
+    /// # Arguments
+    /// - `epoch_ms`: The unix epoch in ms this key should expire at.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::CreateKeyRequest;
+    /// let r = CreateKeyRequest::new("test").set_expires_at(1_900_000_000_000);
+    ///
+    /// assert_eq!(r.expires.inner().unwrap(), &1_900_000_000_000);
+    /// ```
+    #[must_use]
+    pub fn set_expires_at(mut self, epoch_ms: usize) -> Self {
+        self.expires = UndefinedOr::Value(epoch_ms);
         self
     }
 
@@ -386,6 +711,10 @@ impl CreateKeyRequest {
 
     /// Sets the refill for the new key.
     ///
+    /// ## Note
+    /// A refill only applies to keys with limited uses, so `remaining` must
+    /// also be set, see [`CreateKeyRequest::validate`].
+    ///
     /// # Arguments
     /// - `refill`: The refill to set.
     ///
@@ -399,7 +728,9 @@ impl CreateKeyRequest {
     /// # use unkey::models::RefillInterval;
     /// let refill = Refill::new(100, RefillInterval::Daily);
     ///
-    /// let r = CreateKeyRequest::new("test").set_refill(refill.clone());
+    /// let r = CreateKeyRequest::new("test")
+    ///     .set_remaining(100)
+    ///     .set_refill(refill.clone());
     ///
     /// assert_eq!(r.refill.inner().unwrap(), &refill);
     /// ```
@@ -408,505 +739,2400 @@ impl CreateKeyRequest {
         self.refill = UndefinedOr::Value(refill);
         self
     }
-}
-
-/// An incoming create key response.
-#[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct CreateKeyResponse {
-    /// The unique id of this key.
-    pub key_id: String,
-
-    /// The newly created api key.
-    pub key: String,
-}
-
-/// An individual api key, as the unkey api sees it.
-#[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ApiKey {
-    /// The unique id of this key.
-    pub id: String,
-
-    /// The optional name for the key.
-    pub name: Option<String>,
-
-    /// The id of the api this key belongs to.
-    pub api_id: String,
-
-    /// The id of the workspace this key belongs to.
-    pub workspace_id: String,
-
-    /// The keys prefix.
-    pub start: String,
-
-    /// The owner id of the key, if one was set.
-    pub owner_id: Option<String>,
-
-    /// The dynamic metadata associated with the key, if any.
-    pub meta: Option<Value>,
-
-    /// The keys creation time in ms since the unix epoch.
-    pub created_at: usize,
-
-    /// The unix epoch in ms when this key expires, if it does.
-    pub expires: Option<usize>,
 
-    /// The number of uses remaining for this key, if any.
+    /// Sets the environment for the new key.
     ///
-    /// *Note*: If `None`, the key has unlimited uses remaining.
-    pub remaining: Option<usize>,
-
-    /// The ratelimit imposed on this key, if any.
-    pub ratelimit: Option<Ratelimit>,
-
-    /// The refill state of this key, if any.
-    pub refill: Option<Refill>,
-}
+    /// # Arguments
+    /// - `environment`: The environment to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::CreateKeyRequest;
+    /// let r = CreateKeyRequest::new("test").set_environment("live");
+    ///
+    /// assert_eq!(r.environment.inner().unwrap(), &String::from("live"));
+    /// ```
+    #[must_use]
+    pub fn set_environment<T: Into<String>>(mut self, environment: T) -> Self {
+        self.environment = UndefinedOr::Value(environment.into());
+        self
+    }
 
-/// An outgoing revoke key request.
-#[derive(Debug, Clone, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct RevokeKeyRequest {
-    /// The unique id of the key to revoke.
-    pub key_id: String,
-}
+    /// Sets whether the new key is enabled.
+    ///
+    /// # Arguments
+    /// - `enabled`: Whether the key is enabled.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::CreateKeyRequest;
+    /// let r = CreateKeyRequest::new("test").set_enabled(false);
+    ///
+    /// assert_eq!(r.enabled.inner().unwrap(), &false);
+    /// ```
+    #[must_use]
+    pub fn set_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = UndefinedOr::Value(enabled);
+        self
+    }
 
-impl RevokeKeyRequest {
-    /// Creates a new revoke key request.
+    /// Sets the roles for the new key.
     ///
     /// # Arguments
-    /// - `key_id`: The id of the key to revoke.
+    /// - `roles`: The names of the roles to set.
     ///
     /// # Returns
-    /// The revoke key request.
+    /// Self for chained calls.
     ///
     /// # Example
     /// ```
-    /// # use unkey::models::RevokeKeyRequest;
-    /// let r = RevokeKeyRequest::new("test_ABC123");
+    /// # use unkey::models::CreateKeyRequest;
+    /// let r = CreateKeyRequest::new("test").set_roles(&["admin"]);
     ///
-    /// assert_eq!(r.key_id, String::from("test_ABC123"));
+    /// assert_eq!(r.roles.inner().unwrap(), &vec![String::from("admin")]);
     /// ```
     #[must_use]
-    #[rustfmt::skip]
-    pub fn new<T: Into<String>>(key_id: T) -> Self {
-        Self { key_id: key_id.into() }
+    pub fn set_roles(mut self, roles: &[&str]) -> Self {
+        self.roles = UndefinedOr::Value(roles.iter().map(|r| (*r).to_string()).collect());
+        self
     }
-}
 
-/// An outgoing update key request.
-///
-/// ## Note
-/// All optional values are initialized to the [`UndefinedOr::Undefined`] state.
-/// Upon calling the `set_x` method, you may set the value to `Some(_)` or
-/// `None`. Setting the value to `None` indicates you would like to remove any
-/// value that is currently set for that field on the key.
-///
-/// e.g. The key you are updating currently has a ratelimit and you call
-/// `set_ratelimit(None)` on the update key request. The key will no longer
-/// have a ratelimit.
-#[derive(Debug, Default, Clone, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct UpdateKeyRequest {
-    /// The id of the key to update.
-    pub key_id: String,
-
-    /// The optional new owner id for the key.
-    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
-    pub owner_id: UndefinedOr<String>,
-
-    /// The optional new name for the key.
-    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
-    pub name: UndefinedOr<String>,
-
-    /// The optional new dynamic meta mapping for the key.
-    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
-    pub meta: UndefinedOr<Value>,
-
-    /// The optional new unix epoch in ms when the key should expire.
-    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
-    pub expires: UndefinedOr<usize>,
-
-    /// The optional new number of uses remaining to set for the key.
-    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
-    pub remaining: UndefinedOr<usize>,
-
-    /// The optional new ratelimit to set for the key.
-    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
-    pub ratelimit: UndefinedOr<Ratelimit>,
-
-    /// The optional new refill to set for the key.
-    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
-    pub refill: UndefinedOr<Refill>,
-}
-
-impl UpdateKeyRequest {
-    /// Creates a new update key request.
+    /// Sets the permissions for the new key.
     ///
     /// # Arguments
-    /// - `key_id`: The id of the key to update.
+    /// - `permissions`: The names of the permissions to set.
     ///
     /// # Returns
-    /// The new update key request.
+    /// Self for chained calls.
     ///
     /// # Example
     /// ```
-    /// # use unkey::models::UpdateKeyRequest;
-    /// # use unkey::models::UndefinedOr;
-    /// let r = UpdateKeyRequest::new("test_123");
+    /// # use unkey::models::CreateKeyRequest;
+    /// let r = CreateKeyRequest::new("test").set_permissions(&["keys.read"]);
     ///
-    /// assert_eq!(r.key_id, String::from("test_123"));
-    /// assert_eq!(r.owner_id, UndefinedOr::Undefined);
-    /// assert_eq!(r.name, UndefinedOr::Undefined);
-    /// assert_eq!(r.meta, UndefinedOr::Undefined);
-    /// assert_eq!(r.expires, UndefinedOr::Undefined);
-    /// assert_eq!(r.remaining, UndefinedOr::Undefined);
-    /// assert_eq!(r.ratelimit, UndefinedOr::Undefined);
-    /// assert_eq!(r.refill, UndefinedOr::Undefined);
+    /// assert_eq!(r.permissions.inner().unwrap(), &vec![String::from("keys.read")]);
     /// ```
     #[must_use]
-    pub fn new<T: Into<String>>(key_id: T) -> Self {
-        Self {
-            key_id: key_id.into(),
-            ..Default::default()
-        }
+    pub fn set_permissions(mut self, permissions: &[&str]) -> Self {
+        self.permissions =
+            UndefinedOr::Value(permissions.iter().map(|p| (*p).to_string()).collect());
+        self
     }
 
-    /// Sets or unsets the owner id for the key.
+    /// Sets the id of an existing identity to attach the new key to, so it
+    /// shares the identity's ratelimits and meta.
     ///
     /// # Arguments
-    /// - `owner_id`: The owner id to set or unset.
+    /// - `identity_id`: The identity id to set.
     ///
     /// # Returns
     /// Self for chained calls.
     ///
     /// # Example
     /// ```
-    /// # use unkey::models::UpdateKeyRequest;
-    /// # use unkey::models::UndefinedOr;
-    /// let r = UpdateKeyRequest::new("test");
-    ///
-    /// assert_eq!(r.owner_id, UndefinedOr::Undefined);
-    /// assert_eq!(r.owner_id.inner(), None);
-    ///
-    /// let r = r.set_owner_id(Some("jonxslays"));
-    ///
-    /// assert_eq!(r.owner_id, UndefinedOr::Value(String::from("jonxslays")));
-    /// assert_eq!(r.owner_id.inner(), Some(&String::from("jonxslays")));
-    ///
-    /// let r = r.set_owner_id(None);
+    /// # use unkey::models::CreateKeyRequest;
+    /// let r = CreateKeyRequest::new("test").set_identity_id("id_123");
     ///
-    /// assert_eq!(r.owner_id, UndefinedOr::Null);
-    /// assert_eq!(r.owner_id.inner(), None);
+    /// assert_eq!(r.identity_id.inner().unwrap(), &String::from("id_123"));
     /// ```
     #[must_use]
-    pub fn set_owner_id(mut self, owner_id: Option<&str>) -> Self {
-        self.owner_id = match owner_id {
-            Some(id) => Some(id.into()).into(),
-            None => None.into(),
-        };
-
+    pub fn set_identity_id<T: Into<String>>(mut self, identity_id: T) -> Self {
+        self.identity_id = UndefinedOr::Value(identity_id.into());
         self
     }
 
-    /// Sets or unsets the name for the key.
+    /// Sets the idempotency key for the request.
+    ///
+    /// ## Note
+    /// The key is sent as the `Idempotency-Key` header. Sending the same
+    /// idempotency key again will not create a duplicate key, so requests
+    /// with one set are also retried by the client's [`RetryPolicy`].
+    ///
+    /// [`RetryPolicy`]: crate::models::RetryPolicy
     ///
     /// # Arguments
-    /// - `name`: The name to set or unset.
+    /// - `idempotency_key`: The unique idempotency key to set.
     ///
     /// # Returns
     /// Self for chained calls.
     ///
     /// # Example
     /// ```
-    /// # use unkey::models::UpdateKeyRequest;
-    /// # use unkey::models::UndefinedOr;
-    /// let r = UpdateKeyRequest::new("test");
-    ///
-    /// assert_eq!(r.name, UndefinedOr::Undefined);
-    /// assert_eq!(r.name.inner(), None);
-    ///
-    /// let r = r.set_name(Some("test_key"));
-    ///
-    /// assert_eq!(r.name, UndefinedOr::Value(String::from("test_key")));
-    /// assert_eq!(r.name.inner(), Some(&String::from("test_key")));
-    ///
-    /// let r = r.set_name(None);
+    /// # use unkey::models::CreateKeyRequest;
+    /// let r = CreateKeyRequest::new("test").set_idempotency_key("req_123");
     ///
-    /// assert_eq!(r.name, UndefinedOr::Null);
-    /// assert_eq!(r.name.inner(), None);
+    /// assert_eq!(r.idempotency_key.unwrap(), String::from("req_123"));
     /// ```
     #[must_use]
-    pub fn set_name(mut self, name: Option<&str>) -> Self {
-        self.name = match name {
-            Some(n) => Some(n.into()).into(),
-            None => None.into(),
-        };
-
+    pub fn set_idempotency_key<T: Into<String>>(mut self, idempotency_key: T) -> Self {
+        self.idempotency_key = Some(idempotency_key.into());
         self
     }
 
-    /// Sets or unsets the dynamic meta mapping for the key.
+    /// Sets an additional field to send at the top level of the body, i.e.
+    /// for api features the sdk doesn't model yet.
+    ///
+    /// ## Note
+    /// An extra field sharing its name with a modeled field is sent twice,
+    /// so prefer the dedicated setter when one exists.
     ///
     /// # Arguments
-    /// - `meta`: The meta to set or unset.
+    /// - `key`: The name of the field, as the api expects it.
+    /// - `value`: The value of the field.
     ///
     /// # Returns
     /// Self for chained calls.
     ///
     /// # Example
     /// ```
-    /// # use unkey::models::UpdateKeyRequest;
-    /// # use unkey::models::UndefinedOr;
+    /// # use unkey::models::CreateKeyRequest;
     /// # use serde_json::json;
-    /// let r = UpdateKeyRequest::new("test");
-    ///
-    /// assert_eq!(r.meta, UndefinedOr::Undefined);
-    /// assert_eq!(r.meta.inner(), None);
-    ///
-    /// let r = r.set_meta(Some(json!({"test": 69})));
-    ///
-    /// assert_eq!(r.meta, UndefinedOr::Value(json!({"test": 69})));
-    /// assert_eq!(r.meta.inner(), Some(&json!({"test": 69})));
-    ///
-    /// let r = r.set_meta(None);
+    /// let r = CreateKeyRequest::new("test").set_extra("newField", json!(true));
     ///
-    /// assert_eq!(r.meta, UndefinedOr::Null);
-    /// assert_eq!(r.meta.inner(), None);
+    /// assert_eq!(r.extra["newField"], json!(true));
     /// ```
     #[must_use]
-    pub fn set_meta(mut self, meta: Option<Value>) -> Self {
-        self.meta = match meta {
-            Some(m) => Some(m).into(),
-            None => None.into(),
-        };
-
+    pub fn set_extra<T: Into<String>>(mut self, key: T, value: Value) -> Self {
+        self.extra.insert(key.into(), value);
         self
     }
 
-    /// Sets or unsets the unix epoch in ms indicating when this key expires.
-    ///
-    /// # Arguments
-    /// - `expires`: The expiration epoch to set or unset.
+    /// Validates the request before it is sent.
     ///
     /// # Returns
-    /// Self for chained calls.
+    /// A [`Result`] indicating whether the request is valid.
+    ///
+    /// # Errors
+    /// An [`HttpError`] with [`ErrorCode::BadRequest`] if a refill is set
+    /// without `remaining`, or if the serialized meta exceeds the limit, see
+    /// [`DEFAULT_META_SIZE_LIMIT`].
     ///
     /// # Example
     /// ```
-    /// # use unkey::models::UpdateKeyRequest;
-    /// # use unkey::models::UndefinedOr;
-    /// let r = UpdateKeyRequest::new("test");
+    /// # use unkey::models::CreateKeyRequest;
+    /// # use unkey::models::ErrorCode;
+    /// # use unkey::models::Refill;
+    /// # use unkey::models::RefillInterval;
+    /// let r = CreateKeyRequest::new("test").set_meta(serde_json::json!({"plan": "pro"}));
     ///
-    /// assert_eq!(r.expires, UndefinedOr::Undefined);
-    /// assert_eq!(r.expires.inner(), None);
+    /// assert!(r.validate().is_ok());
     ///
-    /// let r = r.set_expires(Some(42));
+    /// let r = r.set_meta(serde_json::json!("a".repeat(2 * 1024 * 1024)));
     ///
-    /// assert_eq!(r.expires, UndefinedOr::Value(42));
-    /// assert_eq!(r.expires.inner(), Some(&42));
+    /// assert_eq!(r.validate().unwrap_err().code, ErrorCode::BadRequest);
     ///
-    /// let r = r.set_expires(None);
+    /// let r = CreateKeyRequest::new("test").set_refill(Refill::new(10, RefillInterval::Daily));
     ///
-    /// assert_eq!(r.expires, UndefinedOr::Null);
-    /// assert_eq!(r.expires.inner(), None);
+    /// assert_eq!(r.validate().unwrap_err().code, ErrorCode::BadRequest);
     /// ```
-    #[must_use]
-    pub fn set_expires(mut self, expires: Option<usize>) -> Self {
-        self.expires = expires.into();
-        self
+    pub fn validate(&self) -> Result<(), HttpError> {
+        self.validate_with_meta_limit(DEFAULT_META_SIZE_LIMIT)
     }
 
-    /// Sets or unsets the remaining uses for the key.
+    /// Validates the request before it is sent, using the given meta limit.
     ///
     /// # Arguments
-    /// - `remaining`: The number of remaining uses to set or unset.
+    /// - `meta_size_limit`: The maximum size of the serialized meta, in bytes.
     ///
     /// # Returns
-    /// Self for chained calls.
-    ///
-    /// # Example
-    /// ```
-    /// # use unkey::models::UpdateKeyRequest;
-    /// # use unkey::models::UndefinedOr;
-    /// let r = UpdateKeyRequest::new("test");
-    ///
-    /// assert_eq!(r.remaining, UndefinedOr::Undefined);
-    /// assert_eq!(r.remaining.inner(), None);
-    ///
-    /// let r = r.set_remaining(Some(420));
-    ///
-    /// assert_eq!(r.remaining, UndefinedOr::Value(420));
-    /// assert_eq!(r.remaining.inner(), Some(&420));
-    ///
-    /// let r = r.set_remaining(None);
-    ///
-    /// assert_eq!(r.remaining, UndefinedOr::Null);
-    /// assert_eq!(r.remaining.inner(), None);
-    /// ```
-    #[must_use]
-    pub fn set_remaining(mut self, remaining: Option<usize>) -> Self {
-        self.remaining = remaining.into();
-        self
+    /// A [`Result`] indicating whether the request is valid.
+    ///
+    /// # Errors
+    /// An [`HttpError`] with [`ErrorCode::BadRequest`] if the request is invalid.
+    pub(crate) fn validate_with_meta_limit(&self, meta_size_limit: usize) -> Result<(), HttpError> {
+        if !self.refill.is_undefined() && self.remaining.is_undefined() {
+            return Err(HttpError::new(
+                ErrorCode::BadRequest,
+                String::from("A refill requires remaining to be set."),
+            ));
+        }
+
+        validate_meta_size(&self.meta, meta_size_limit)
     }
+}
 
-    /// Sets or unsets the ratelimit for the key.
-    ///
-    /// # Arguments
-    /// - `ratelimit`: The ratelimit to set or unset.
+/// The placeholder shown in place of secrets in debug output.
+const REDACTED: &str = "***";
+
+/// An incoming create key response.
+///
+/// ## Note
+/// The `key` is redacted from the [`Debug`] output, so the response can be
+/// logged safely. Use [`CreateKeyResponse::reveal`] to access it.
+#[derive(Clone, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct CreateKeyResponse {
+    /// The unique id of this key.
+    pub key_id: String,
+
+    /// The newly created api key.
+    pub key: String,
+}
+
+impl CreateKeyResponse {
+    /// Reveals the newly created api key.
     ///
     /// # Returns
-    /// Self for chained calls.
+    /// The plaintext api key.
     ///
     /// # Example
     /// ```
-    /// # use unkey::models::UpdateKeyRequest;
-    /// # use unkey::models::Ratelimit;
-    /// # use unkey::models::RatelimitType;
-    /// # use unkey::models::UndefinedOr;
-    /// let r = UpdateKeyRequest::new("test");
-    ///
-    /// assert_eq!(r.ratelimit, UndefinedOr::Undefined);
-    /// assert_eq!(r.ratelimit.inner(), None);
-    ///
-    /// let ratelimit = Ratelimit::new(
-    ///     RatelimitType::Fast,
-    ///     10,
-    ///     10000,
-    ///     100
-    /// );
-    ///
-    /// let r = r.set_ratelimit(Some(ratelimit.clone()));
-    ///
-    /// assert_eq!(r.ratelimit, UndefinedOr::Value(ratelimit.clone()));
-    /// assert_eq!(r.ratelimit.inner(), Some(&ratelimit));
-    ///
-    /// let r = r.set_ratelimit(None);
+    /// # use unkey::models::CreateKeyResponse;
+    /// let r: CreateKeyResponse = serde_json::from_str(r#"{"keyId":"key_123","key":"test_abc"}"#).unwrap();
     ///
-    /// assert_eq!(r.ratelimit, UndefinedOr::Null);
-    /// assert_eq!(r.ratelimit.inner(), None);
+    /// assert_eq!(r.reveal(), "test_abc");
+    /// assert!(!format!("{r:?}").contains("test_abc"));
     /// ```
     #[must_use]
-    pub fn set_ratelimit(mut self, ratelimit: Option<Ratelimit>) -> Self {
-        self.ratelimit = ratelimit.into();
-        self
+    pub fn reveal(&self) -> &str {
+        &self.key
     }
+}
 
-    /// Sets or unsets the refill for the key.
-    ///
-    /// # Arguments
-    /// - `refill`: The refill to set or unset.
-    ///
-    /// # Returns
-    /// Self for chained calls.
-    ///
-    /// # Example
-    /// ```
-    /// # use unkey::models::UpdateKeyRequest;
-    /// # use unkey::models::Refill;
-    /// # use unkey::models::RefillInterval;
-    /// # use unkey::models::UndefinedOr;
-    /// let r = UpdateKeyRequest::new("test");
-    ///
-    /// assert_eq!(r.ratelimit, UndefinedOr::Undefined);
-    /// assert_eq!(r.ratelimit.inner(), None);
-    ///
-    /// let refill = Refill::new(100, RefillInterval::Daily);
-    ///
-    /// let r = r.set_refill(Some(refill.clone()));
-    ///
-    /// assert_eq!(r.refill, UndefinedOr::Value(refill.clone()));
-    /// assert_eq!(r.refill.inner(), Some(&refill));
+impl std::fmt::Debug for CreateKeyResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CreateKeyResponse")
+            .field("key_id", &self.key_id)
+            .field("key", &REDACTED)
+            .finish()
+    }
+}
+
+/// An individual api key, as the unkey api sees it.
+///
+/// ## Note
+/// The `plaintext` key is redacted from the [`Debug`] output, leaving only
+/// the `start` prefix visible.
+///
+/// Equality, ordering and hashing are based solely on the key `id`, not on
+/// the other fields. Two snapshots of the same key taken at different times
+/// compare equal, which lets keys be stored in a `HashSet` or `BTreeMap`
+/// directly.
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct ApiKey {
+    /// The unique id of this key.
+    pub id: String,
+
+    /// The optional name for the key.
+    pub name: Option<String>,
+
+    /// The id of the api this key belongs to.
+    pub api_id: String,
+
+    /// The id of the workspace this key belongs to.
+    pub workspace_id: String,
+
+    /// The keys prefix.
+    pub start: String,
+
+    /// The owner id of the key, if one was set.
     ///
-    /// let r = r.set_refill(None);
+    /// *Note*: Also read from `externalId`, which unkey is migrating to.
+    #[serde(alias = "externalId")]
+    pub owner_id: Option<String>,
+
+    /// The dynamic metadata associated with the key, if any.
+    pub meta: Option<Value>,
+
+    /// The keys creation time in ms since the unix epoch.
+    pub created_at: usize,
+
+    /// The keys last update time in ms since the unix epoch, if known.
+    pub updated_at: Option<usize>,
+
+    /// The unix epoch in ms when this key expires, if it does.
+    pub expires: Option<usize>,
+
+    /// The number of uses remaining for this key, if any.
     ///
-    /// assert_eq!(r.refill, UndefinedOr::Null);
-    /// assert_eq!(r.refill.inner(), None);
+    /// *Note*: If `None`, the key has unlimited uses remaining.
+    pub remaining: Option<usize>,
+
+    /// The ratelimit imposed on this key, if any.
+    pub ratelimit: Option<Ratelimit>,
+
+    /// The refill state of this key, if any.
+    pub refill: Option<Refill>,
+
+    /// The decrypted plaintext key, if it was requested and is recoverable.
+    pub plaintext: Option<String>,
+
+    /// Whether this key is enabled, if known.
+    pub enabled: Option<bool>,
+
+    /// The roles attached to this key, if any.
+    pub roles: Option<Vec<String>>,
+
+    /// The permissions attached to this key, if any.
+    pub permissions: Option<Vec<String>>,
+
+    /// The identity this key belongs to, if any.
+    pub identity: Option<Identity>,
+}
+
+impl ApiKey {
+    /// Deserializes the dynamic meta mapping into the given type.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the typed meta, if any meta was present.
+    ///
+    /// # Errors
+    /// The [`serde_json::Error`] if the meta does not match the given type.
+    ///
+    /// # Example
+    /// ```
+    /// # use serde::Deserialize;
+    /// # use unkey::models::ApiKey;
+    /// #[derive(Deserialize)]
+    /// struct Meta {
+    ///     plan: String,
+    /// }
+    ///
+    /// let r: ApiKey = serde_json::from_str(r#"{
+    ///     "id": "key_123",
+    ///     "apiId": "api_123",
+    ///     "workspaceId": "ws_123",
+    ///     "start": "test_",
+    ///     "createdAt": 0,
+    ///     "meta": {"plan": "pro"}
+    /// }"#).unwrap();
+    /// let meta: Meta = r.get_meta().unwrap().unwrap();
+    ///
+    /// assert_eq!(meta.plan, String::from("pro"));
+    /// ```
+    pub fn get_meta<T: DeserializeOwned>(&self) -> Result<Option<T>, serde_json::Error> {
+        self.meta.as_ref().map(T::deserialize).transpose()
+    }
+
+    /// The time the key was created, as a date time.
+    ///
+    /// ## Note
+    /// Requires the `chrono` feature. A timestamp too large to represent
+    /// saturates to the maximum date time.
+    ///
+    /// # Returns
+    /// The date time the key was created.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::ApiKey;
+    /// let r: ApiKey = serde_json::from_str(r#"{
+    ///     "id": "key_123",
+    ///     "apiId": "api_123",
+    ///     "workspaceId": "ws_123",
+    ///     "start": "test_",
+    ///     "createdAt": 1700000000000
+    /// }"#).unwrap();
+    ///
+    /// assert_eq!(r.created_at_datetime().timestamp_millis(), 1_700_000_000_000);
     /// ```
+    #[cfg(feature = "chrono")]
     #[must_use]
-    pub fn set_refill(mut self, refill: Option<Refill>) -> Self {
-        self.refill = refill.into();
-        self
+    pub fn created_at_datetime(&self) -> chrono::DateTime<chrono::Utc> {
+        millis_to_datetime(self.created_at).unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC)
+    }
+
+    /// The time the key expires, as a date time.
+    ///
+    /// ## Note
+    /// Requires the `chrono` feature.
+    ///
+    /// # Returns
+    /// The date time the key expires, if it does and the timestamp is in
+    /// range.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::ApiKey;
+    /// let r: ApiKey = serde_json::from_str(r#"{
+    ///     "id": "key_123",
+    ///     "apiId": "api_123",
+    ///     "workspaceId": "ws_123",
+    ///     "start": "test_",
+    ///     "createdAt": 0,
+    ///     "expires": 1700000000000
+    /// }"#).unwrap();
+    /// let at = r.expires_datetime().unwrap();
+    ///
+    /// assert_eq!(at.timestamp_millis(), 1_700_000_000_000);
+    /// ```
+    #[cfg(feature = "chrono")]
+    #[must_use]
+    pub fn expires_datetime(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        millis_to_datetime(self.expires?)
     }
 }
 
-/// An outgoing get key request.
+/// Converts a unix epoch in ms to a date time.
+///
+/// # Arguments
+/// - `millis`: The unix epoch in ms.
+///
+/// # Returns
+/// The date time, if the timestamp is in range.
+#[cfg(feature = "chrono")]
+pub(crate) fn millis_to_datetime(millis: usize) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::from_timestamp_millis(i64::try_from(millis).ok()?)
+}
+
+impl std::fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiKey")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("api_id", &self.api_id)
+            .field("workspace_id", &self.workspace_id)
+            .field("start", &self.start)
+            .field("owner_id", &self.owner_id)
+            .field("meta", &self.meta)
+            .field("created_at", &self.created_at)
+            .field("updated_at", &self.updated_at)
+            .field("expires", &self.expires)
+            .field("remaining", &self.remaining)
+            .field("ratelimit", &self.ratelimit)
+            .field("refill", &self.refill)
+            .field("plaintext", &self.plaintext.as_ref().map(|_| REDACTED))
+            .field("enabled", &self.enabled)
+            .field("roles", &self.roles)
+            .field("permissions", &self.permissions)
+            .field("identity", &self.identity)
+            .finish()
+    }
+}
+
+impl PartialEq for ApiKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for ApiKey {}
+
+impl std::hash::Hash for ApiKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl PartialOrd for ApiKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ApiKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+/// An outgoing delete key request.
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct GetKeyRequest {
-    /// The unique id of the key to get.
+pub struct DeleteKeyRequest {
+    /// The unique id of the key to delete.
     pub key_id: String,
 }
 
-impl GetKeyRequest {
-    /// Creates a new get key request.
+/// An outgoing revoke key request.
+#[deprecated(since = "0.7.0", note = "use `DeleteKeyRequest` instead")]
+pub type RevokeKeyRequest = DeleteKeyRequest;
+
+impl DeleteKeyRequest {
+    /// Creates a new delete key request.
     ///
     /// # Arguments
-    /// - `key_id`: The id of the key to get.
+    /// - `key_id`: The id of the key to delete.
     ///
     /// # Returns
-    /// The get key request.
+    /// The delete key request.
     ///
     /// # Example
     /// ```
-    /// # use unkey::models::GetKeyRequest;
-    /// let r = GetKeyRequest::new("test_ABC123");
+    /// # use unkey::models::DeleteKeyRequest;
+    /// let r = DeleteKeyRequest::new("test_ABC123");
     ///
     /// assert_eq!(r.key_id, String::from("test_ABC123"));
     /// ```
     #[must_use]
     #[rustfmt::skip]
+    #[inline]
     pub fn new<T: Into<String>>(key_id: T) -> Self {
         Self { key_id: key_id.into() }
     }
 }
 
-/// An outgoing update remaining request.
-#[derive(Debug, Clone, Serialize)]
+impl From<&str> for DeleteKeyRequest {
+    fn from(key_id: &str) -> Self {
+        Self::new(key_id)
+    }
+}
+
+impl From<String> for DeleteKeyRequest {
+    fn from(key_id: String) -> Self {
+        Self::new(key_id)
+    }
+}
+
+/// An outgoing update key request.
+///
+/// ## Note
+/// All optional values are initialized to the [`UndefinedOr::Undefined`] state.
+/// Upon calling the `set_x` method, you may set the value to `Some(_)` or
+/// `None`. Setting the value to `None` indicates you would like to remove any
+/// value that is currently set for that field on the key.
+///
+/// e.g. The key you are updating currently has a ratelimit and you call
+/// `set_ratelimit(None)` on the update key request. The key will no longer
+/// have a ratelimit.
+#[derive(Debug, Default, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct UpdateRemainingRequest {
-    /// The unique id of the key to updating remaining for.
+pub struct UpdateKeyRequest {
+    /// The id of the key to update.
     pub key_id: String,
 
-    /// The value to perform the operation on.
-    pub value: Option<usize>,
+    /// The optional new owner id for the key.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub owner_id: UndefinedOr<String>,
 
-    /// The update operation to perform.
-    pub op: UpdateOp,
+    /// The optional new name for the key.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub name: UndefinedOr<String>,
+
+    /// The optional new dynamic meta mapping for the key.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub meta: UndefinedOr<Value>,
+
+    /// The optional new unix epoch in ms when the key should expire.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub expires: UndefinedOr<usize>,
+
+    /// The optional new number of uses remaining to set for the key.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub remaining: UndefinedOr<usize>,
+
+    /// The optional new ratelimit to set for the key.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub ratelimit: UndefinedOr<Ratelimit>,
+
+    /// The optional new refill to set for the key.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub refill: UndefinedOr<Refill>,
+
+    /// The optional new enabled state for the key.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub enabled: UndefinedOr<bool>,
+
+    /// The optional new roles for the key, replacing any existing roles.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub roles: UndefinedOr<Vec<String>>,
+
+    /// The optional new permissions for the key, replacing any existing
+    /// permissions.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub permissions: UndefinedOr<Vec<String>>,
+
+    /// Additional fields sent at the top level of the body, i.e. for api
+    /// features the sdk doesn't model yet.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
-impl UpdateRemainingRequest {
-    /// Creates a new update remaining request.
+impl UpdateKeyRequest {
+    /// Creates a new update key request.
     ///
     /// # Arguments
-    /// - `key_id`: The id of the key to update remaining for.
-    /// - `value`: The value to perform the operation on.
-    /// - `op`: The update operation to perform.
+    /// - `key_id`: The id of the key to update.
     ///
     /// # Returns
-    /// The update remaining request.
+    /// The new update key request.
     ///
     /// # Example
     /// ```
-    /// # use unkey::models::UpdateRemainingRequest;
-    /// # use unkey::models::UpdateOp;
-    /// let r = UpdateRemainingRequest::new("test_ABC123", Some(100), UpdateOp::Set);
+    /// # use unkey::models::UpdateKeyRequest;
+    /// # use unkey::models::UndefinedOr;
+    /// let r = UpdateKeyRequest::new("test_123");
     ///
-    /// assert_eq!(r.key_id, String::from("test_ABC123"));
-    /// assert_eq!(r.value.unwrap(), 100);
-    /// assert_eq!(r.op, UpdateOp::Set);
+    /// assert_eq!(r.key_id, String::from("test_123"));
+    /// assert_eq!(r.owner_id, UndefinedOr::Undefined);
+    /// assert_eq!(r.name, UndefinedOr::Undefined);
+    /// assert_eq!(r.meta, UndefinedOr::Undefined);
+    /// assert_eq!(r.expires, UndefinedOr::Undefined);
+    /// assert_eq!(r.remaining, UndefinedOr::Undefined);
+    /// assert_eq!(r.ratelimit, UndefinedOr::Undefined);
+    /// assert_eq!(r.refill, UndefinedOr::Undefined);
+    /// assert_eq!(r.enabled, UndefinedOr::Undefined);
+    /// assert_eq!(r.roles, UndefinedOr::Undefined);
+    /// assert_eq!(r.permissions, UndefinedOr::Undefined);
+    /// assert!(r.extra.is_empty());
     /// ```
     #[must_use]
-    #[rustfmt::skip]
-    pub fn new<T: Into<String>>(key_id: T, value: Option<usize>, op: UpdateOp) -> Self {
-        Self { key_id: key_id.into(), value, op }
+    #[inline]
+    pub fn new<T: Into<String>>(key_id: T) -> Self {
+        Self {
+            key_id: key_id.into(),
+            ..Default::default()
+        }
     }
-}
 
-/// An incoming update remaining request.
-#[derive(Debug, Clone, Deserialize)]
-pub struct UpdateRemainingResponse {
-    /// The number of remaining verifications for the key.
-    pub remaining: usize,
+    /// Sets or unsets the external id for the key.
+    ///
+    /// ## Note
+    /// Unkey is renaming `ownerId` to `externalId`. The value is stored in
+    /// `owner_id` and sent as `ownerId`, which [`ApiVersion::V1`] expects.
+    ///
+    /// [`ApiVersion::V1`]: crate::models::ApiVersion::V1
+    ///
+    /// # Arguments
+    /// - `external_id`: The external id to set or unset.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateKeyRequest;
+    /// # use unkey::models::UndefinedOr;
+    /// let r = UpdateKeyRequest::new("test");
+    ///
+    /// assert_eq!(r.owner_id, UndefinedOr::Undefined);
+    /// assert_eq!(r.owner_id.inner(), None);
+    ///
+    /// let r = r.set_external_id(Some("jonxslays"));
+    ///
+    /// assert_eq!(r.owner_id, UndefinedOr::Value(String::from("jonxslays")));
+    /// assert_eq!(r.owner_id.inner(), Some(&String::from("jonxslays")));
+    ///
+    /// let r = r.set_external_id(None);
+    ///
+    /// assert_eq!(r.owner_id, UndefinedOr::Null);
+    /// assert_eq!(r.owner_id.inner(), None);
+    /// ```
+    #[must_use]
+    pub fn set_external_id(mut self, external_id: Option<&str>) -> Self {
+        self.owner_id = match external_id {
+            Some(id) => Some(id.into()).into(),
+            None => None.into(),
+        };
+
+        self
+    }
+
+    /// Sets or unsets the owner id for the key.
+    ///
+    /// # Arguments
+    /// - `owner_id`: The owner id to set or unset.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    #[deprecated(since = "0.7.0", note = "use `set_external_id` instead")]
+    #[must_use]
+    pub fn set_owner_id(self, owner_id: Option<&str>) -> Self {
+        self.set_external_id(owner_id)
+    }
+
+    /// Sets or unsets the name for the key.
+    ///
+    /// # Arguments
+    /// - `name`: The name to set or unset.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateKeyRequest;
+    /// # use unkey::models::UndefinedOr;
+    /// let r = UpdateKeyRequest::new("test");
+    ///
+    /// assert_eq!(r.name, UndefinedOr::Undefined);
+    /// assert_eq!(r.name.inner(), None);
+    ///
+    /// let r = r.set_name(Some("test_key"));
+    ///
+    /// assert_eq!(r.name, UndefinedOr::Value(String::from("test_key")));
+    /// assert_eq!(r.name.inner(), Some(&String::from("test_key")));
+    ///
+    /// let r = r.set_name(None);
+    ///
+    /// assert_eq!(r.name, UndefinedOr::Null);
+    /// assert_eq!(r.name.inner(), None);
+    /// ```
+    #[must_use]
+    pub fn set_name(mut self, name: Option<&str>) -> Self {
+        self.name = match name {
+            Some(n) => Some(n.into()).into(),
+            None => None.into(),
+        };
+
+        self
+    }
+
+    /// Sets or unsets the dynamic meta mapping for the key.
+    ///
+    /// # Arguments
+    /// - `meta`: The meta to set or unset.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateKeyRequest;
+    /// # use unkey::models::UndefinedOr;
+    /// # use serde_json::json;
+    /// let r = UpdateKeyRequest::new("test");
+    ///
+    /// assert_eq!(r.meta, UndefinedOr::Undefined);
+    /// assert_eq!(r.meta.inner(), None);
+    ///
+    /// let r = r.set_meta(Some(json!({"test": 69})));
+    ///
+    /// assert_eq!(r.meta, UndefinedOr::Value(json!({"test": 69})));
+    /// assert_eq!(r.meta.inner(), Some(&json!({"test": 69})));
+    ///
+    /// let r = r.set_meta(None);
+    ///
+    /// assert_eq!(r.meta, UndefinedOr::Null);
+    /// assert_eq!(r.meta.inner(), None);
+    /// ```
+    #[must_use]
+    pub fn set_meta(mut self, meta: Option<Value>) -> Self {
+        self.meta = match meta {
+            Some(m) => Some(m).into(),
+            None => None.into(),
+        };
+
+        self
+    }
+
+    /// Sets or unsets the unix epoch in ms indicating when this key expires.
+    ///
+    /// # Arguments
+    /// - `expires`: The expiration epoch to set or unset.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateKeyRequest;
+    /// # use unkey::models::UndefinedOr;
+    /// let r = UpdateKeyRequest::new("test");
+    ///
+    /// assert_eq!(r.expires, UndefinedOr::Undefined);
+    /// assert_eq!(r.expires.inner(), None);
+    ///
+    /// let r = r.set_expires(Some(42));
+    ///
+    /// assert_eq!(r.expires, UndefinedOr::Value(42));
+    /// assert_eq!(r.expires.inner(), Some(&42));
+    ///
+    /// let r = r.set_expires(None);
+    ///
+    /// assert_eq!(r.expires, UndefinedOr::Null);
+    /// assert_eq!(r.expires.inner(), None);
+    /// ```
+    #[must_use]
+    pub fn set_expires(mut self, expires: Option<usize>) -> Self {
+        self.expires = expires.into();
+        self
+    }
+
+    /// Sets or unsets the remaining uses for the key.
+    ///
+    /// # Arguments
+    /// - `remaining`: The number of remaining uses to set or unset.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateKeyRequest;
+    /// # use unkey::models::UndefinedOr;
+    /// let r = UpdateKeyRequest::new("test");
+    ///
+    /// assert_eq!(r.remaining, UndefinedOr::Undefined);
+    /// assert_eq!(r.remaining.inner(), None);
+    ///
+    /// let r = r.set_remaining(Some(420));
+    ///
+    /// assert_eq!(r.remaining, UndefinedOr::Value(420));
+    /// assert_eq!(r.remaining.inner(), Some(&420));
+    ///
+    /// let r = r.set_remaining(None);
+    ///
+    /// assert_eq!(r.remaining, UndefinedOr::Null);
+    /// assert_eq!(r.remaining.inner(), None);
+    /// ```
+    #[must_use]
+    pub fn set_remaining(mut self, remaining: Option<usize>) -> Self {
+        self.remaining = remaining.into();
+        self
+    }
+
+    /// Sets or unsets the ratelimit for the key.
+    ///
+    /// # Arguments
+    /// - `ratelimit`: The ratelimit to set or unset.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateKeyRequest;
+    /// # use unkey::models::Ratelimit;
+    /// # use unkey::models::RatelimitType;
+    /// # use unkey::models::UndefinedOr;
+    /// let r = UpdateKeyRequest::new("test");
+    ///
+    /// assert_eq!(r.ratelimit, UndefinedOr::Undefined);
+    /// assert_eq!(r.ratelimit.inner(), None);
+    ///
+    /// let ratelimit = Ratelimit::new(
+    ///     RatelimitType::Fast,
+    ///     10,
+    ///     10000,
+    ///     100
+    /// );
+    ///
+    /// let r = r.set_ratelimit(Some(ratelimit.clone()));
+    ///
+    /// assert_eq!(r.ratelimit, UndefinedOr::Value(ratelimit.clone()));
+    /// assert_eq!(r.ratelimit.inner(), Some(&ratelimit));
+    ///
+    /// let r = r.set_ratelimit(None);
+    ///
+    /// assert_eq!(r.ratelimit, UndefinedOr::Null);
+    /// assert_eq!(r.ratelimit.inner(), None);
+    /// ```
+    #[must_use]
+    pub fn set_ratelimit(mut self, ratelimit: Option<Ratelimit>) -> Self {
+        self.ratelimit = ratelimit.into();
+        self
+    }
+
+    /// Sets or unsets the refill for the key.
+    ///
+    /// # Arguments
+    /// - `refill`: The refill to set or unset.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateKeyRequest;
+    /// # use unkey::models::Refill;
+    /// # use unkey::models::RefillInterval;
+    /// # use unkey::models::UndefinedOr;
+    /// let r = UpdateKeyRequest::new("test");
+    ///
+    /// assert_eq!(r.ratelimit, UndefinedOr::Undefined);
+    /// assert_eq!(r.ratelimit.inner(), None);
+    ///
+    /// let refill = Refill::new(100, RefillInterval::Daily);
+    ///
+    /// let r = r.set_refill(Some(refill.clone()));
+    ///
+    /// assert_eq!(r.refill, UndefinedOr::Value(refill.clone()));
+    /// assert_eq!(r.refill.inner(), Some(&refill));
+    ///
+    /// let r = r.set_refill(None);
+    ///
+    /// assert_eq!(r.refill, UndefinedOr::Null);
+    /// assert_eq!(r.refill.inner(), None);
+    /// ```
+    #[must_use]
+    pub fn set_refill(mut self, refill: Option<Refill>) -> Self {
+        self.refill = refill.into();
+        self
+    }
+
+    /// Sets or unsets the enabled state for the key.
+    ///
+    /// # Arguments
+    /// - `enabled`: The enabled state to set or unset.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateKeyRequest;
+    /// # use unkey::models::UndefinedOr;
+    /// let r = UpdateKeyRequest::new("test");
+    ///
+    /// assert_eq!(r.enabled, UndefinedOr::Undefined);
+    ///
+    /// let r = r.set_enabled(Some(false));
+    ///
+    /// assert_eq!(r.enabled, UndefinedOr::Value(false));
+    ///
+    /// let r = r.set_enabled(None);
+    ///
+    /// assert_eq!(r.enabled, UndefinedOr::Null);
+    /// ```
+    #[must_use]
+    pub fn set_enabled(mut self, enabled: Option<bool>) -> Self {
+        self.enabled = enabled.into();
+        self
+    }
+
+    /// Sets or unsets the roles for the key.
+    ///
+    /// # Arguments
+    /// - `roles`: The names of the roles to set or unset.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateKeyRequest;
+    /// # use unkey::models::UndefinedOr;
+    /// let r = UpdateKeyRequest::new("test");
+    ///
+    /// assert_eq!(r.roles, UndefinedOr::Undefined);
+    ///
+    /// let r = r.set_roles(Some(&["admin"]));
+    ///
+    /// assert_eq!(r.roles, UndefinedOr::Value(vec![String::from("admin")]));
+    ///
+    /// let r = r.set_roles(None);
+    ///
+    /// assert_eq!(r.roles, UndefinedOr::Null);
+    /// ```
+    #[must_use]
+    pub fn set_roles(mut self, roles: Option<&[&str]>) -> Self {
+        self.roles = roles
+            .map(|r| r.iter().map(|s| (*s).to_string()).collect())
+            .into();
+
+        self
+    }
+
+    /// Sets or unsets the permissions for the key.
+    ///
+    /// # Arguments
+    /// - `permissions`: The names of the permissions to set or unset.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateKeyRequest;
+    /// # use unkey::models::UndefinedOr;
+    /// let r = UpdateKeyRequest::new("test");
+    ///
+    /// assert_eq!(r.permissions, UndefinedOr::Undefined);
+    ///
+    /// let r = r.set_permissions(Some(&["keys.read"]));
+    ///
+    /// assert_eq!(r.permissions, UndefinedOr::Value(vec![String::from("keys.read")]));
+    ///
+    /// let r = r.set_permissions(None);
+    ///
+    /// assert_eq!(r.permissions, UndefinedOr::Null);
+    /// ```
+    #[must_use]
+    pub fn set_permissions(mut self, permissions: Option<&[&str]>) -> Self {
+        self.permissions = permissions
+            .map(|p| p.iter().map(|s| (*s).to_string()).collect())
+            .into();
+
+        self
+    }
+
+    /// Sets an additional field to send at the top level of the body, i.e.
+    /// for api features the sdk doesn't model yet.
+    ///
+    /// ## Note
+    /// An extra field sharing its name with a modeled field is sent twice,
+    /// so prefer the dedicated setter when one exists.
+    ///
+    /// # Arguments
+    /// - `key`: The name of the field, as the api expects it.
+    /// - `value`: The value of the field.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateKeyRequest;
+    /// # use serde_json::json;
+    /// let r = UpdateKeyRequest::new("test").set_extra("newField", json!(null));
+    ///
+    /// assert!(r.has_changes());
+    /// ```
+    #[must_use]
+    pub fn set_extra<T: Into<String>>(mut self, key: T, value: Value) -> Self {
+        self.extra.insert(key.into(), value);
+        self
+    }
+
+    /// Clears every optional field on the key, setting each to null.
+    ///
+    /// ## Note
+    /// Null fields are sent to the api, which removes them from the key,
+    /// i.e. the key will no longer expire or be ratelimited. Extra fields
+    /// are left untouched.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateKeyRequest;
+    /// # use unkey::models::UndefinedOr;
+    /// let r = UpdateKeyRequest::new("test").set_name(Some("name")).clear_all();
+    ///
+    /// assert_eq!(r.name, UndefinedOr::Null);
+    /// assert_eq!(r.ratelimit, UndefinedOr::Null);
+    /// ```
+    #[must_use]
+    pub fn clear_all(self) -> Self {
+        Self {
+            key_id: self.key_id,
+            owner_id: UndefinedOr::Null,
+            name: UndefinedOr::Null,
+            meta: UndefinedOr::Null,
+            expires: UndefinedOr::Null,
+            remaining: UndefinedOr::Null,
+            ratelimit: UndefinedOr::Null,
+            refill: UndefinedOr::Null,
+            enabled: UndefinedOr::Null,
+            roles: UndefinedOr::Null,
+            permissions: UndefinedOr::Null,
+            extra: self.extra,
+        }
+    }
+
+    /// Whether the request changes anything about the key.
+    ///
+    /// # Returns
+    /// True if any field other than `key_id` is set, including to null.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateKeyRequest;
+    /// let r = UpdateKeyRequest::new("test");
+    ///
+    /// assert!(!r.has_changes());
+    ///
+    /// let r = r.set_name(None);
+    ///
+    /// assert!(r.has_changes());
+    /// ```
+    #[must_use]
+    pub fn has_changes(&self) -> bool {
+        !(self.owner_id.is_undefined()
+            && self.name.is_undefined()
+            && self.meta.is_undefined()
+            && self.expires.is_undefined()
+            && self.remaining.is_undefined()
+            && self.ratelimit.is_undefined()
+            && self.refill.is_undefined()
+            && self.enabled.is_undefined()
+            && self.roles.is_undefined()
+            && self.permissions.is_undefined()
+            && self.extra.is_empty())
+    }
+
+    /// Validates the request before it is sent.
+    ///
+    /// # Returns
+    /// A [`Result`] indicating whether the request is valid.
+    ///
+    /// # Errors
+    /// An [`HttpError`] with [`ErrorCode::BadRequest`] if the request has no
+    /// changes, see [`UpdateKeyRequest::has_changes`], or if the serialized
+    /// meta exceeds the limit, see [`DEFAULT_META_SIZE_LIMIT`].
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateKeyRequest;
+    /// # use unkey::models::ErrorCode;
+    /// let r = UpdateKeyRequest::new("test");
+    ///
+    /// assert_eq!(r.validate().unwrap_err().code, ErrorCode::BadRequest);
+    ///
+    /// let r = r.set_enabled(Some(false));
+    ///
+    /// assert!(r.validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> Result<(), HttpError> {
+        self.validate_with_meta_limit(DEFAULT_META_SIZE_LIMIT)
+    }
+
+    /// Validates the request before it is sent, using the given meta limit.
+    ///
+    /// # Arguments
+    /// - `meta_size_limit`: The maximum size of the serialized meta, in bytes.
+    ///
+    /// # Returns
+    /// A [`Result`] indicating whether the request is valid.
+    ///
+    /// # Errors
+    /// An [`HttpError`] with [`ErrorCode::BadRequest`] if the request is invalid.
+    pub(crate) fn validate_with_meta_limit(&self, meta_size_limit: usize) -> Result<(), HttpError> {
+        if !self.has_changes() {
+            return Err(HttpError::new(
+                ErrorCode::BadRequest,
+                String::from("At least one field must be updated."),
+            ));
+        }
+
+        validate_meta_size(&self.meta, meta_size_limit)
+    }
+}
+
+/// An outgoing get key request.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetKeyRequest {
+    /// The unique id of the key to get.
+    pub key_id: String,
+
+    /// Whether to include the decrypted plaintext key in the response.
+    pub decrypt: Option<bool>,
+}
+
+impl GetKeyRequest {
+    /// Creates a new get key request.
+    ///
+    /// # Arguments
+    /// - `key_id`: The id of the key to get.
+    ///
+    /// # Returns
+    /// The get key request.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::GetKeyRequest;
+    /// let r = GetKeyRequest::new("test_ABC123");
+    ///
+    /// assert_eq!(r.key_id, String::from("test_ABC123"));
+    /// assert_eq!(r.decrypt, None);
+    /// ```
+    #[must_use]
+    #[rustfmt::skip]
+    #[inline]
+    pub fn new<T: Into<String>>(key_id: T) -> Self {
+        Self { key_id: key_id.into(), decrypt: None }
+    }
+
+    /// Sets whether to include the decrypted plaintext key in the response.
+    ///
+    /// ## Note
+    /// This only works for workspaces with key recovery enabled.
+    ///
+    /// # Arguments
+    /// - `decrypt`: Whether to decrypt the key.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::GetKeyRequest;
+    /// let r = GetKeyRequest::new("test_ABC123").set_decrypt(true);
+    ///
+    /// assert_eq!(r.decrypt, Some(true));
+    /// ```
+    #[must_use]
+    pub fn set_decrypt(mut self, decrypt: bool) -> Self {
+        self.decrypt = Some(decrypt);
+        self
+    }
+}
+
+impl From<&str> for GetKeyRequest {
+    fn from(key_id: &str) -> Self {
+        Self::new(key_id)
+    }
+}
+
+impl From<String> for GetKeyRequest {
+    fn from(key_id: String) -> Self {
+        Self::new(key_id)
+    }
+}
+
+/// An outgoing update remaining request.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRemainingRequest {
+    /// The unique id of the key to updating remaining for.
+    pub key_id: String,
+
+    /// The value to perform the operation on.
+    ///
+    /// *Note*: A value is required for every operation, see
+    /// [`UpdateRemainingRequest::validate`].
+    pub value: Option<usize>,
+
+    /// The update operation to perform.
+    pub op: UpdateOp,
+}
+
+impl UpdateRemainingRequest {
+    /// Creates a new update remaining request.
+    ///
+    /// # Arguments
+    /// - `key_id`: The id of the key to update remaining for.
+    /// - `value`: The value to perform the operation on.
+    /// - `op`: The update operation to perform.
+    ///
+    /// # Returns
+    /// The update remaining request.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateRemainingRequest;
+    /// # use unkey::models::UpdateOp;
+    /// let r = UpdateRemainingRequest::new("test_ABC123", Some(100), UpdateOp::Set);
+    ///
+    /// assert_eq!(r.key_id, String::from("test_ABC123"));
+    /// assert_eq!(r.value.unwrap(), 100);
+    /// assert_eq!(r.op, UpdateOp::Set);
+    /// ```
+    #[must_use]
+    #[rustfmt::skip]
+    #[inline]
+    pub fn new<T: Into<String>>(key_id: T, value: Option<usize>, op: UpdateOp) -> Self {
+        Self { key_id: key_id.into(), value, op }
+    }
+    /// Validates the request before it is sent.
+    ///
+    /// # Returns
+    /// A [`Result`] indicating whether the request is valid.
+    ///
+    /// # Errors
+    /// An [`HttpError`] with [`ErrorCode::BadRequest`] if the request has no
+    /// value, as there is nothing to increment, decrement or set the
+    /// remaining verifications to. To remove the limit entirely, use
+    /// [`UpdateKeyRequest::set_remaining`] instead.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateRemainingRequest;
+    /// # use unkey::models::UpdateOp;
+    /// # use unkey::models::ErrorCode;
+    /// let r = UpdateRemainingRequest::new("test_ABC123", None, UpdateOp::Decrement);
+    ///
+    /// assert_eq!(r.validate().unwrap_err().code, ErrorCode::BadRequest);
+    ///
+    /// let r = UpdateRemainingRequest::new("test_ABC123", None, UpdateOp::Set);
+    ///
+    /// assert_eq!(r.validate().unwrap_err().code, ErrorCode::BadRequest);
+    ///
+    /// let r = UpdateRemainingRequest::new("test_ABC123", Some(0), UpdateOp::Set);
+    ///
+    /// assert!(r.validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> Result<(), HttpError> {
+        if self.value.is_none() {
+            return Err(HttpError::new(
+                ErrorCode::BadRequest,
+                format!("A value is required for the {:?} operation.", self.op),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// An incoming update remaining response.
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct UpdateRemainingResponse {
+    /// The number of remaining verifications for the key.
+    pub remaining: usize,
+}
+
+impl UpdateRemainingResponse {
+    /// Whether the key has no verifications remaining.
+    ///
+    /// # Returns
+    /// True if the key is exhausted.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UpdateRemainingResponse;
+    /// let r: UpdateRemainingResponse = serde_json::from_str(r#"{"remaining":0}"#).unwrap();
+    ///
+    /// assert!(r.is_exhausted());
+    /// ```
+    #[must_use]
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
+/// An outgoing get usage numbers request, for a key or all keys of an owner.
+///
+/// ## Note
+/// Either `key_id` or `owner_id` must be set, otherwise the request is
+/// rejected locally without being sent.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUsageNumbersRequest {
+    /// The id of the key to get usage numbers for.
+    pub key_id: Option<String>,
+
+    /// The owner id to get usage numbers for, across all their keys.
+    pub owner_id: Option<String>,
+
+    /// The unix epoch in ms to start the time window at, inclusive.
+    pub start: Option<usize>,
+
+    /// The unix epoch in ms to end the time window at, inclusive.
+    pub end: Option<usize>,
+}
+
+impl GetUsageNumbersRequest {
+    /// Creates a new get usage numbers request.
+    ///
+    /// # Returns
+    /// The get usage numbers request.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::GetUsageNumbersRequest;
+    /// let r = GetUsageNumbersRequest::new();
+    ///
+    /// assert_eq!(r.key_id, None);
+    /// assert_eq!(r.owner_id, None);
+    /// assert_eq!(r.start, None);
+    /// assert_eq!(r.end, None);
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the id of the key to get usage numbers for.
+    ///
+    /// # Arguments
+    /// - `key_id`: The key id to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::GetUsageNumbersRequest;
+    /// let r = GetUsageNumbersRequest::new().set_key_id("key_123");
+    ///
+    /// assert_eq!(r.key_id.unwrap(), String::from("key_123"));
+    /// ```
+    #[must_use]
+    pub fn set_key_id<T: Into<String>>(mut self, key_id: T) -> Self {
+        self.key_id = Some(key_id.into());
+        self
+    }
+
+    /// Sets the owner id to get usage numbers for.
+    ///
+    /// # Arguments
+    /// - `owner_id`: The owner id to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::GetUsageNumbersRequest;
+    /// let r = GetUsageNumbersRequest::new().set_owner_id("jonxslays");
+    ///
+    /// assert_eq!(r.owner_id.unwrap(), String::from("jonxslays"));
+    /// ```
+    #[must_use]
+    pub fn set_owner_id<T: Into<String>>(mut self, owner_id: T) -> Self {
+        self.owner_id = Some(owner_id.into());
+        self
+    }
+
+    /// Sets the start of the time window to get usage numbers for.
+    ///
+    /// # Arguments
+    /// - `start`: The unix epoch in ms to start at.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::GetUsageNumbersRequest;
+    /// let r = GetUsageNumbersRequest::new().set_start(1_700_000_000_000);
+    ///
+    /// assert_eq!(r.start.unwrap(), 1_700_000_000_000);
+    /// ```
+    #[must_use]
+    pub fn set_start(mut self, start: usize) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Sets the end of the time window to get usage numbers for.
+    ///
+    /// # Arguments
+    /// - `end`: The unix epoch in ms to end at.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::GetUsageNumbersRequest;
+    /// let r = GetUsageNumbersRequest::new().set_end(1_700_000_000_000);
+    ///
+    /// assert_eq!(r.end.unwrap(), 1_700_000_000_000);
+    /// ```
+    #[must_use]
+    pub fn set_end(mut self, end: usize) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    /// Validates the request before it is sent.
+    ///
+    /// # Returns
+    /// A [`Result`] indicating whether the request is valid.
+    ///
+    /// # Errors
+    /// An [`HttpError`] with [`ErrorCode::BadRequest`] if neither `key_id`
+    /// nor `owner_id` is set, or if `start` is after `end`.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::GetUsageNumbersRequest;
+    /// # use unkey::models::ErrorCode;
+    /// let r = GetUsageNumbersRequest::new();
+    ///
+    /// assert_eq!(r.validate().unwrap_err().code, ErrorCode::BadRequest);
+    ///
+    /// let r = r.set_key_id("key_123");
+    /// assert!(r.validate().is_ok());
+    ///
+    /// let r = r.set_start(2).set_end(1);
+    /// assert_eq!(r.validate().unwrap_err().code, ErrorCode::BadRequest);
+    /// ```
+    pub fn validate(&self) -> Result<(), HttpError> {
+        if self.key_id.is_none() && self.owner_id.is_none() {
+            return Err(HttpError::new(
+                ErrorCode::BadRequest,
+                String::from("Either key_id or owner_id must be set."),
+            ));
+        }
+
+        if let (Some(start), Some(end)) = (self.start, self.end) {
+            if start > end {
+                return Err(HttpError::new(
+                    ErrorCode::BadRequest,
+                    String::from("The start must not be after the end."),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The verification counts for a single time bucket.
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct VerificationBucket {
+    /// The unix epoch in ms marking the start of this bucket.
+    pub time: usize,
+
+    /// The number of successful verifications.
+    pub success: usize,
+
+    /// The number of verifications rejected due to ratelimiting.
+    pub rate_limited: usize,
+
+    /// The number of verifications rejected due to exceeded usage.
+    pub usage_exceeded: usize,
+}
+
+/// An incoming get usage numbers response.
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct GetUsageNumbersResponse {
+    /// The verification counts, bucketed over time.
+    pub verifications: Vec<VerificationBucket>,
+}
+
+impl GetUsageNumbersResponse {
+    /// The total number of successful verifications across all buckets.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::GetUsageNumbersResponse;
+    /// let r: GetUsageNumbersResponse = serde_json::from_str(r#"{"verifications":[
+    ///     {"time":0,"success":3,"rateLimited":0,"usageExceeded":0},
+    ///     {"time":86400000,"success":4,"rateLimited":1,"usageExceeded":0}
+    /// ]}"#).unwrap();
+    ///
+    /// assert_eq!(r.total_success(), 7);
+    /// ```
+    #[must_use]
+    pub fn total_success(&self) -> usize {
+        self.verifications.iter().map(|b| b.success).sum()
+    }
+}
+
+/// Serializes permission names into the objects the unkey api expects.
+fn serialize_permission_names<S>(names: &[String], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    #[derive(Serialize)]
+    struct Named<'a> {
+        name: &'a str,
+    }
+
+    serializer.collect_seq(names.iter().map(|name| Named { name }))
+}
+
+/// An outgoing add permissions request.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddPermissionsRequest {
+    /// The id of the key to add permissions to.
+    pub key_id: String,
+
+    /// The names of the permissions to add.
+    #[serde(serialize_with = "serialize_permission_names")]
+    pub permissions: Vec<String>,
+}
+
+impl AddPermissionsRequest {
+    /// Creates a new add permissions request.
+    ///
+    /// # Arguments
+    /// - `key_id`: The id of the key to add permissions to.
+    /// - `permissions`: The names of the permissions to add.
+    ///
+    /// # Returns
+    /// The add permissions request.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::AddPermissionsRequest;
+    /// let r = AddPermissionsRequest::new("key_123", &["keys.read"]);
+    ///
+    /// assert_eq!(r.key_id, String::from("key_123"));
+    /// assert_eq!(r.permissions, vec![String::from("keys.read")]);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn new<T: Into<String>>(key_id: T, permissions: &[&str]) -> Self {
+        Self {
+            key_id: key_id.into(),
+            permissions: permissions.iter().map(|p| (*p).to_string()).collect(),
+        }
+    }
+}
+
+/// An outgoing remove permissions request.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovePermissionsRequest {
+    /// The id of the key to remove permissions from.
+    pub key_id: String,
+
+    /// The names of the permissions to remove.
+    #[serde(serialize_with = "serialize_permission_names")]
+    pub permissions: Vec<String>,
+}
+
+impl RemovePermissionsRequest {
+    /// Creates a new remove permissions request.
+    ///
+    /// # Arguments
+    /// - `key_id`: The id of the key to remove permissions from.
+    /// - `permissions`: The names of the permissions to remove.
+    ///
+    /// # Returns
+    /// The remove permissions request.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::RemovePermissionsRequest;
+    /// let r = RemovePermissionsRequest::new("key_123", &["keys.write"]);
+    ///
+    /// assert_eq!(r.key_id, String::from("key_123"));
+    /// assert_eq!(r.permissions, vec![String::from("keys.write")]);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn new<T: Into<String>>(key_id: T, permissions: &[&str]) -> Self {
+        Self {
+            key_id: key_id.into(),
+            permissions: permissions.iter().map(|p| (*p).to_string()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+
+    use crate::models::AddPermissionsRequest;
+    use crate::models::ApiKey;
+    use crate::models::CreateKeyRequest;
+    use crate::models::CreateKeyResponse;
+    use crate::models::DeleteKeyRequest;
+    use crate::models::ErrorCode;
+    use crate::models::GetKeyRequest;
+    use crate::models::GetUsageNumbersRequest;
+    use crate::models::GetUsageNumbersResponse;
+    use crate::models::Identity;
+    use crate::models::Refill;
+    use crate::models::RefillInterval;
+    use crate::models::RemovePermissionsRequest;
+    use crate::models::UndefinedOr;
+    use crate::models::UpdateKeyRequest;
+    use crate::models::UpdateOp;
+    use crate::models::UpdateRemainingRequest;
+    use crate::models::UpdateRemainingResponse;
+    use crate::models::VerifyKeyRequest;
+    use crate::models::VerifyKeyResponse;
+
+    use super::validate_meta_size;
+
+    #[test]
+    fn verify_key_request_serialize_without_cost() {
+        let r = VerifyKeyRequest::new("test_abc", "api_123");
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(res.as_str(), r#"{"key":"test_abc","apiId":"api_123"}"#);
+    }
+
+    #[test]
+    fn verify_key_request_serialize_with_cost() {
+        let r = VerifyKeyRequest::new("test_abc", "api_123").set_ratelimit_cost(3);
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(
+            res.as_str(),
+            r#"{"key":"test_abc","apiId":"api_123","ratelimit":{"cost":3}}"#
+        );
+    }
+
+    #[test]
+    fn verify_key_request_serialize_with_identifier() {
+        let r = VerifyKeyRequest::new("test_abc", "api_123")
+            .set_ratelimit_identifier("tenant_123")
+            .set_ratelimit_cost(2);
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(
+            res.as_str(),
+            r#"{"key":"test_abc","apiId":"api_123","ratelimit":{"cost":2,"identifier":"tenant_123"}}"#
+        );
+    }
+
+    #[test]
+    fn key_requests_from_id() {
+        let get = GetKeyRequest::from("key_123");
+        let delete: DeleteKeyRequest = String::from("key_123").into();
+
+        assert_eq!(get.key_id, "key_123");
+        assert_eq!(get.decrypt, None);
+        assert_eq!(delete.key_id, "key_123");
+    }
+
+    #[test]
+    fn verify_key_response_unlimited() {
+        let r: VerifyKeyResponse = serde_json::from_str(r#"{"valid":true}"#).unwrap();
+
+        assert_eq!(r.remaining_or_zero(), usize::MAX);
+        assert!(r.should_allow());
+    }
+
+    #[test]
+    fn verify_key_response_limited() {
+        let r: VerifyKeyResponse =
+            serde_json::from_str(r#"{"valid":true,"remaining":10}"#).unwrap();
+
+        assert_eq!(r.remaining_or_zero(), 10);
+        assert!(r.should_allow());
+
+        let r: VerifyKeyResponse =
+            serde_json::from_str(r#"{"valid":false,"remaining":10,"code":"DISABLED"}"#).unwrap();
+
+        assert!(!r.should_allow());
+    }
+
+    #[test]
+    fn verify_key_response_zero_remaining() {
+        let r: VerifyKeyResponse = serde_json::from_str(r#"{"valid":true,"remaining":0}"#).unwrap();
+
+        assert_eq!(r.remaining_or_zero(), 0);
+        assert!(!r.should_allow());
+    }
+
+    #[test]
+    fn create_key_request_serialize_environment() {
+        let r = CreateKeyRequest::new("api_123").set_environment("test");
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(res.as_str(), r#"{"apiId":"api_123","environment":"test"}"#);
+    }
+
+    #[test]
+    fn create_key_request_serialize_without_environment() {
+        let r = CreateKeyRequest::new("api_123");
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(res.as_str(), r#"{"apiId":"api_123"}"#);
+    }
+
+    #[test]
+    fn verify_key_response_with_environment() {
+        let body = r#"{"valid":true,"environment":"live"}"#;
+        let res: VerifyKeyResponse = serde_json::from_str(body).unwrap();
+
+        assert_eq!(res.environment.as_deref(), Some("live"));
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Meta {
+        plan: String,
+        seats: u32,
+    }
+
+    #[test]
+    fn api_key_get_meta() {
+        let body = r#"{"id":"key_123","apiId":"api_123","workspaceId":"ws_123","start":"test_","createdAt":0,"meta":{"plan":"pro","seats":5}}"#;
+        let key: ApiKey = serde_json::from_str(body).unwrap();
+        let meta: Option<Meta> = key.get_meta().unwrap();
+
+        assert_eq!(
+            meta,
+            Some(Meta {
+                plan: String::from("pro"),
+                seats: 5
+            })
+        );
+    }
+
+    #[test]
+    fn api_key_get_meta_missing() {
+        let body = r#"{"id":"key_123","apiId":"api_123","workspaceId":"ws_123","start":"test_","createdAt":0}"#;
+        let key: ApiKey = serde_json::from_str(body).unwrap();
+
+        assert_eq!(key.get_meta::<Meta>().unwrap(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn api_key_created_at_datetime() {
+        use chrono::TimeZone;
+
+        let body = r#"{"id":"key_123","apiId":"api_123","workspaceId":"ws_123","start":"test_","createdAt":1700000000000}"#;
+        let key: ApiKey = serde_json::from_str(body).unwrap();
+
+        assert_eq!(
+            key.created_at_datetime(),
+            chrono::Utc
+                .with_ymd_and_hms(2023, 11, 14, 22, 13, 20)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn api_key_expires_datetime() {
+        use chrono::TimeZone;
+
+        let body = r#"{"id":"key_123","apiId":"api_123","workspaceId":"ws_123","start":"test_","createdAt":0,"expires":1700000000123}"#;
+        let mut key: ApiKey = serde_json::from_str(body).unwrap();
+
+        let expected = chrono::Utc
+            .with_ymd_and_hms(2023, 11, 14, 22, 13, 20)
+            .unwrap()
+            + chrono::Duration::milliseconds(123);
+
+        assert_eq!(key.expires_datetime(), Some(expected));
+
+        key.expires = None;
+
+        assert_eq!(key.expires_datetime(), None);
+    }
+
+    #[test]
+    fn verify_key_response_get_meta_mismatch() {
+        let body = r#"{"valid":true,"meta":{"plan":5}}"#;
+        let res: VerifyKeyResponse = serde_json::from_str(body).unwrap();
+
+        assert!(res.get_meta::<Meta>().is_err());
+    }
+
+    #[test]
+    fn verify_key_response_with_code() {
+        let body = r#"{"valid":false,"code":"RATE_LIMITED"}"#;
+        let res: VerifyKeyResponse = serde_json::from_str(body).unwrap();
+
+        assert!(!res.valid);
+        assert_eq!(res.code, Some(ErrorCode::RateLimited));
+    }
+
+    #[test]
+    fn verify_key_response_without_code() {
+        let body = r#"{"valid":true,"keyId":"key_123"}"#;
+        let res: VerifyKeyResponse = serde_json::from_str(body).unwrap();
+
+        assert!(res.valid);
+        assert_eq!(res.code, None);
+        assert_eq!(res.key_id.as_deref(), Some("key_123"));
+    }
+
+    #[test]
+    fn get_usage_numbers_request_requires_key_or_owner() {
+        let err = GetUsageNumbersRequest::new().validate().unwrap_err();
+
+        assert_eq!(err.code, ErrorCode::BadRequest);
+        assert_eq!(err.message, "Either key_id or owner_id must be set.");
+    }
+
+    #[test]
+    fn get_usage_numbers_request_with_key_or_owner() {
+        let r = GetUsageNumbersRequest::new();
+
+        assert!(r.clone().set_key_id("key_123").validate().is_ok());
+        assert!(r.set_owner_id("jonxslays").validate().is_ok());
+    }
+
+    #[test]
+    fn update_remaining_request_validate() {
+        let cases = [
+            (None, UpdateOp::Increment, false),
+            (None, UpdateOp::Decrement, false),
+            (None, UpdateOp::Set, false),
+            (Some(1), UpdateOp::Increment, true),
+            (Some(1), UpdateOp::Decrement, true),
+            (Some(1), UpdateOp::Set, true),
+        ];
+
+        for (value, op, valid) in cases {
+            let r = UpdateRemainingRequest::new("key_123", value, op);
+
+            match r.validate() {
+                Ok(()) => assert!(valid),
+                Err(e) => {
+                    assert!(!valid);
+                    assert_eq!(e.code, ErrorCode::BadRequest);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn update_remaining_request_set_requires_value() {
+        let err = UpdateRemainingRequest::new("key_123", None, UpdateOp::Set)
+            .validate()
+            .unwrap_err();
+
+        assert_eq!(err.code, ErrorCode::BadRequest);
+        assert_eq!(err.message, "A value is required for the Set operation.");
+    }
+
+    #[test]
+    fn update_remaining_response_is_exhausted() {
+        let res: UpdateRemainingResponse = serde_json::from_str(r#"{"remaining":0}"#).unwrap();
+        assert!(res.is_exhausted());
+
+        let res: UpdateRemainingResponse = serde_json::from_str(r#"{"remaining":3}"#).unwrap();
+        assert!(!res.is_exhausted());
+    }
+
+    #[test]
+    fn get_usage_numbers_request_validate_window() {
+        let r = GetUsageNumbersRequest::new().set_key_id("key_123");
+
+        assert!(r.clone().set_start(1).set_end(1).validate().is_ok());
+        assert!(r.clone().set_start(1).set_end(2).validate().is_ok());
+        assert!(r.clone().set_start(2).validate().is_ok());
+        assert!(r.clone().set_end(1).validate().is_ok());
+        assert_eq!(
+            r.set_start(2).set_end(1).validate().unwrap_err().code,
+            ErrorCode::BadRequest
+        );
+    }
+
+    #[test]
+    fn create_key_request_serialize_enabled() {
+        let r = CreateKeyRequest::new("api_123").set_enabled(false);
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(res.as_str(), r#"{"apiId":"api_123","enabled":false}"#);
+    }
+
+    #[test]
+    fn update_key_request_serialize_enabled() {
+        let r = UpdateKeyRequest::new("key_123");
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(res.as_str(), r#"{"keyId":"key_123"}"#);
+
+        let res = serde_json::to_string(&r.set_enabled(Some(true))).unwrap();
+
+        assert_eq!(res.as_str(), r#"{"keyId":"key_123","enabled":true}"#);
+    }
+
+    #[test]
+    fn api_key_deserialize_enabled() {
+        let body = r#"{"id":"key_123","apiId":"api_123","workspaceId":"ws_123","start":"t_","createdAt":0,"enabled":false}"#;
+        let res: ApiKey = serde_json::from_str(body).unwrap();
+
+        assert_eq!(res.enabled, Some(false));
+
+        let body = r#"{"id":"key_123","apiId":"api_123","workspaceId":"ws_123","start":"t_","createdAt":0}"#;
+        let res: ApiKey = serde_json::from_str(body).unwrap();
+
+        assert_eq!(res.enabled, None);
+    }
+
+    #[test]
+    fn verify_key_response_code_helpers() {
+        let cases = [
+            (
+                r#"{"valid":false,"code":"RATE_LIMITED"}"#,
+                (true, false, false),
+            ),
+            (r#"{"valid":false,"code":"EXPIRED"}"#, (false, true, false)),
+            (r#"{"valid":false,"code":"DISABLED"}"#, (false, false, true)),
+            (
+                r#"{"valid":false,"code":"NOT_FOUND"}"#,
+                (false, false, false),
+            ),
+            (r#"{"valid":true}"#, (false, false, false)),
+        ];
+
+        for (body, expected) in cases {
+            let res: VerifyKeyResponse = serde_json::from_str(body).unwrap();
+            let actual = (res.is_rate_limited(), res.is_expired(), res.is_disabled());
+
+            assert_eq!(actual, expected, "{body}");
+        }
+    }
+
+    #[test]
+    fn verify_key_response_deserialize_disabled() {
+        let body = r#"{"valid":false,"code":"DISABLED","enabled":false}"#;
+        let res: VerifyKeyResponse = serde_json::from_str(body).unwrap();
+
+        assert_eq!(res.code, Some(ErrorCode::Disabled));
+        assert_eq!(res.enabled, Some(false));
+    }
+
+    #[test]
+    fn create_key_request_serialize_rbac() {
+        let r = CreateKeyRequest::new("api_123")
+            .set_roles(&["admin"])
+            .set_permissions(&["keys.read", "keys.write"]);
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(
+            res.as_str(),
+            r#"{"apiId":"api_123","roles":["admin"],"permissions":["keys.read","keys.write"]}"#
+        );
+    }
+
+    #[test]
+    fn update_key_request_serialize_rbac_undefined() {
+        let r = UpdateKeyRequest::new("key_123");
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(res.as_str(), r#"{"keyId":"key_123"}"#);
+    }
+
+    #[test]
+    fn update_key_request_serialize_rbac_set() {
+        let r = UpdateKeyRequest::new("key_123")
+            .set_roles(Some(&["admin"]))
+            .set_permissions(Some(&["keys.read"]));
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(
+            res.as_str(),
+            r#"{"keyId":"key_123","roles":["admin"],"permissions":["keys.read"]}"#
+        );
+    }
+
+    #[test]
+    fn update_key_request_serialize_rbac_clear() {
+        let r = UpdateKeyRequest::new("key_123")
+            .set_roles(None)
+            .set_permissions(None);
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(
+            res.as_str(),
+            r#"{"keyId":"key_123","roles":null,"permissions":null}"#
+        );
+    }
+
+    #[test]
+    fn api_key_deserialize_rbac() {
+        let body = r#"{"id":"key_123","apiId":"api_123","workspaceId":"ws_123","start":"t_","createdAt":0,"roles":["admin"],"permissions":["keys.read"]}"#;
+        let res: ApiKey = serde_json::from_str(body).unwrap();
+
+        assert_eq!(res.roles, Some(vec![String::from("admin")]));
+        assert_eq!(res.permissions, Some(vec![String::from("keys.read")]));
+    }
+
+    #[test]
+    fn add_permissions_request_serialize() {
+        let r = AddPermissionsRequest::new("key_123", &["keys.read", "keys.write"]);
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(
+            res.as_str(),
+            r#"{"keyId":"key_123","permissions":[{"name":"keys.read"},{"name":"keys.write"}]}"#
+        );
+    }
+
+    #[test]
+    fn remove_permissions_request_serialize() {
+        let r = RemovePermissionsRequest::new("key_123", &["keys.write"]);
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(
+            res.as_str(),
+            r#"{"keyId":"key_123","permissions":[{"name":"keys.write"}]}"#
+        );
+    }
+
+    #[test]
+    fn create_key_response_debug_redacted() {
+        let body = r#"{"keyId":"key_123","key":"test_abc"}"#;
+        let res: CreateKeyResponse = serde_json::from_str(body).unwrap();
+
+        assert_eq!(
+            format!("{res:?}"),
+            r#"CreateKeyResponse { key_id: "key_123", key: "***" }"#
+        );
+        assert_eq!(res.reveal(), "test_abc");
+    }
+
+    #[test]
+    fn api_key_debug_redacted() {
+        let body = r#"{"id":"key_123","apiId":"api_123","workspaceId":"ws_123","start":"test_","createdAt":0,"plaintext":"test_abc"}"#;
+        let res: ApiKey = serde_json::from_str(body).unwrap();
+        let debug = format!("{res:?}");
+
+        assert!(debug.contains(r#"start: "test_""#));
+        assert!(debug.contains(r#"plaintext: Some("***")"#));
+        assert!(!debug.contains("test_abc"));
+    }
+
+    #[test]
+    fn validate_meta_size_under_limit() {
+        let meta = UndefinedOr::Value(serde_json::json!({"plan": "pro"}));
+
+        assert!(validate_meta_size(&meta, 14).is_ok());
+        assert!(validate_meta_size(&UndefinedOr::Null, 0).is_ok());
+        assert!(validate_meta_size(&UndefinedOr::Undefined, 0).is_ok());
+    }
+
+    #[test]
+    fn validate_meta_size_over_limit() {
+        let meta = UndefinedOr::Value(serde_json::json!({"plan": "pro"}));
+        let err = validate_meta_size(&meta, 13).unwrap_err();
+
+        assert_eq!(err.code, ErrorCode::BadRequest);
+        assert!(err.message.contains("14 bytes"));
+    }
+
+    #[test]
+    fn update_key_request_validates_meta_size() {
+        let meta = serde_json::json!("a".repeat(2 * 1024 * 1024));
+        let r = UpdateKeyRequest::new("key_123").set_meta(Some(meta));
+
+        assert_eq!(r.validate().unwrap_err().code, ErrorCode::BadRequest);
+    }
+
+    #[test]
+    #[cfg(feature = "strict")]
+    fn strict_rejects_unknown_fields() {
+        let body = r#"{"valid":true,"keyId":"key_123","newField":1}"#;
+        let err = serde_json::from_str::<VerifyKeyResponse>(body).unwrap_err();
+
+        assert!(err.to_string().contains("newField"));
+
+        let body = r#"{"id":"key_123","apiId":"api_123","workspaceId":"ws_123","start":"t_","createdAt":0,"newField":1}"#;
+
+        assert!(serde_json::from_str::<ApiKey>(body).is_err());
+    }
+
+    #[test]
+    fn api_key_deserialize_external_id() {
+        let body = r#"{"id":"key_123","apiId":"api_123","workspaceId":"ws_123","start":"t_","createdAt":0,"ownerId":"jonxslays"}"#;
+        let res: ApiKey = serde_json::from_str(body).unwrap();
+
+        assert_eq!(res.owner_id.as_deref(), Some("jonxslays"));
+
+        let body = r#"{"id":"key_123","apiId":"api_123","workspaceId":"ws_123","start":"t_","createdAt":0,"externalId":"jonxslays"}"#;
+        let res: ApiKey = serde_json::from_str(body).unwrap();
+
+        assert_eq!(res.owner_id.as_deref(), Some("jonxslays"));
+    }
+
+    #[test]
+    fn verify_key_response_deserialize_external_id() {
+        let body = r#"{"valid":true,"ownerId":"jonxslays"}"#;
+        let res: VerifyKeyResponse = serde_json::from_str(body).unwrap();
+
+        assert_eq!(res.owner_id.as_deref(), Some("jonxslays"));
+
+        let body = r#"{"valid":true,"externalId":"jonxslays"}"#;
+        let res: VerifyKeyResponse = serde_json::from_str(body).unwrap();
+
+        assert_eq!(res.owner_id.as_deref(), Some("jonxslays"));
+    }
+
+    #[test]
+    fn create_key_request_serialize_external_id() {
+        let r = CreateKeyRequest::new("api_123").set_external_id("jonxslays");
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(res, r#"{"apiId":"api_123","ownerId":"jonxslays"}"#);
+    }
+
+    #[test]
+    fn update_key_request_serialize_all_undefined() {
+        let r = UpdateKeyRequest::new("key_123");
+
+        assert_eq!(serde_json::to_string(&r).unwrap(), r#"{"keyId":"key_123"}"#);
+    }
+
+    #[test]
+    fn update_key_request_serialize_all_null() {
+        let r = UpdateKeyRequest::new("key_123")
+            .set_external_id(None)
+            .set_name(None)
+            .set_meta(None)
+            .set_expires(None)
+            .set_remaining(None)
+            .set_ratelimit(None)
+            .set_refill(None)
+            .set_enabled(None)
+            .set_roles(None)
+            .set_permissions(None);
+
+        let expected = r#"{"keyId":"key_123","ownerId":null,"name":null,"meta":null,"expires":null,"remaining":null,"ratelimit":null,"refill":null,"enabled":null,"roles":null,"permissions":null}"#;
+
+        assert_eq!(serde_json::to_string(&r).unwrap(), expected);
+        assert_eq!(
+            serde_json::to_string(&UpdateKeyRequest::new("key_123").clear_all()).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn update_key_request_clear_all_keeps_extra() {
+        let r = UpdateKeyRequest::new("key_123")
+            .set_name(Some("test"))
+            .set_extra("newField", serde_json::json!(1))
+            .clear_all();
+
+        assert_eq!(r.key_id, "key_123");
+        assert_eq!(r.name, UndefinedOr::Null);
+        assert_eq!(r.extra["newField"], serde_json::json!(1));
+    }
+
+    #[test]
+    fn api_key_deserialize_identity() {
+        let body = r#"{"id":"key_123","apiId":"api_123","workspaceId":"ws_123","start":"t_","createdAt":0,"identity":{"id":"id_123","externalId":"user_123","meta":{"plan":"pro"}}}"#;
+        let res: ApiKey = serde_json::from_str(body).unwrap();
+        let identity = res.identity.unwrap();
+
+        assert_eq!(identity.id, "id_123");
+        assert_eq!(identity.external_id, "user_123");
+        assert_eq!(identity.meta, Some(serde_json::json!({"plan": "pro"})));
+    }
+
+    #[test]
+    fn verify_key_response_deserialize_identity() {
+        let body = r#"{"valid":true,"identity":{"id":"id_123","externalId":"user_123"}}"#;
+        let res: VerifyKeyResponse = serde_json::from_str(body).unwrap();
+
+        assert_eq!(
+            res.identity,
+            Some(Identity {
+                id: String::from("id_123"),
+                external_id: String::from("user_123"),
+                meta: None,
+                ratelimits: None,
+            })
+        );
+
+        let res: VerifyKeyResponse = serde_json::from_str(r#"{"valid":true}"#).unwrap();
+
+        assert_eq!(res.identity, None);
+    }
+
+    #[test]
+    fn create_key_request_serialize_identity_id() {
+        let r = CreateKeyRequest::new("api_123").set_identity_id("id_123");
+
+        assert_eq!(
+            serde_json::to_string(&r).unwrap(),
+            r#"{"apiId":"api_123","identityId":"id_123"}"#
+        );
+    }
+
+    #[test]
+    fn create_key_request_refill_requires_remaining() {
+        let refill = Refill::new(100, RefillInterval::Daily);
+        let err = CreateKeyRequest::new("api_123")
+            .set_refill(refill)
+            .validate()
+            .unwrap_err();
+
+        assert_eq!(err.code, ErrorCode::BadRequest);
+        assert_eq!(err.message, "A refill requires remaining to be set.");
+    }
+
+    #[test]
+    fn create_key_request_refill_with_remaining() {
+        let refill = Refill::new(100, RefillInterval::Daily);
+        let r = CreateKeyRequest::new("api_123")
+            .set_remaining(100)
+            .set_refill(refill);
+
+        assert!(r.validate().is_ok());
+    }
+
+    #[test]
+    fn create_key_request_serialize_extra() {
+        let r = CreateKeyRequest::new("api_123")
+            .set_name("test")
+            .set_extra("newField", serde_json::json!({"nested": 1}));
+        let res: serde_json::Value = serde_json::to_value(&r).unwrap();
+
+        assert_eq!(
+            res,
+            serde_json::json!({"apiId": "api_123", "name": "test", "newField": {"nested": 1}})
+        );
+    }
+
+    #[test]
+    fn update_key_request_serialize_extra() {
+        let r = UpdateKeyRequest::new("key_123").set_extra("newField", serde_json::Value::Null);
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(res, r#"{"keyId":"key_123","newField":null}"#);
+        assert!(r.validate().is_ok());
+    }
+
+    #[test]
+    fn api_key_identity_is_id_based() {
+        let body = r#"{"id":"key_123","apiId":"api_123","workspaceId":"ws_123","start":"t_","createdAt":0}"#;
+        let first: ApiKey = serde_json::from_str(body).unwrap();
+
+        let body = r#"{"id":"key_123","apiId":"api_123","workspaceId":"ws_123","start":"t_","createdAt":0,"name":"renamed"}"#;
+        let renamed: ApiKey = serde_json::from_str(body).unwrap();
+
+        let body = r#"{"id":"key_000","apiId":"api_123","workspaceId":"ws_123","start":"t_","createdAt":0}"#;
+        let other: ApiKey = serde_json::from_str(body).unwrap();
+
+        let keys: std::collections::HashSet<ApiKey> =
+            vec![first.clone(), renamed.clone(), other.clone()]
+                .into_iter()
+                .collect();
+
+        assert_eq!(first, renamed);
+        assert_eq!(keys.len(), 2);
+        assert!(other < first);
+    }
+
+    #[test]
+    fn create_key_request_set_expires_is_relative() {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as usize;
+
+        let r = CreateKeyRequest::new("api_123").set_expires(60_000);
+        let expires = *r.expires.inner().unwrap();
+
+        assert!(expires >= now + 60_000);
+        assert!(expires < now + 61_000);
+    }
+
+    #[test]
+    fn create_key_request_set_expires_saturates() {
+        let r = CreateKeyRequest::new("api_123").set_expires(usize::MAX);
+
+        assert_eq!(r.expires.inner(), Some(&usize::MAX));
+    }
+
+    #[test]
+    fn create_key_request_set_expires_at_is_absolute() {
+        let r = CreateKeyRequest::new("api_123").set_expires_at(1_900_000_000_000);
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(r.expires.inner(), Some(&1_900_000_000_000));
+        assert_eq!(
+            res.as_str(),
+            r#"{"apiId":"api_123","expires":1900000000000}"#
+        );
+    }
+
+    #[test]
+    fn api_key_deserialize_updated_at() {
+        let body = r#"{"id":"key_123","apiId":"api_123","workspaceId":"ws_123","start":"t_","createdAt":0,"updatedAt":1700000000000}"#;
+        let res: ApiKey = serde_json::from_str(body).unwrap();
+
+        assert_eq!(res.updated_at, Some(1_700_000_000_000));
+
+        let body = r#"{"id":"key_123","apiId":"api_123","workspaceId":"ws_123","start":"t_","createdAt":0}"#;
+        let res: ApiKey = serde_json::from_str(body).unwrap();
+
+        assert_eq!(res.updated_at, None);
+    }
+
+    #[test]
+    fn get_usage_numbers_response_multi_day() {
+        let body = r#"{"verifications":[
+            {"time":1700006400000,"success":10,"rateLimited":2,"usageExceeded":0},
+            {"time":1700092800000,"success":25,"rateLimited":0,"usageExceeded":1},
+            {"time":1700179200000,"success":0,"rateLimited":0,"usageExceeded":3}
+        ]}"#;
+        let res: GetUsageNumbersResponse = serde_json::from_str(body).unwrap();
+
+        assert_eq!(res.verifications.len(), 3);
+        assert_eq!(res.verifications[1].time, 1_700_092_800_000);
+        assert_eq!(res.verifications[0].rate_limited, 2);
+        assert_eq!(res.verifications[2].usage_exceeded, 3);
+        assert_eq!(res.total_success(), 35);
+    }
+
+    #[test]
+    fn get_usage_numbers_response_empty() {
+        let res: GetUsageNumbersResponse = serde_json::from_str(r#"{"verifications":[]}"#).unwrap();
+
+        assert_eq!(res.total_success(), 0);
+    }
 }
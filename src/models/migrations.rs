@@ -0,0 +1,545 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::keys::validate_meta_size;
+use super::HttpError;
+use super::Ratelimit;
+use super::Refill;
+use super::UndefinedOr;
+use super::DEFAULT_META_SIZE_LIMIT;
+
+/// The algorithm and encoding used to hash a migrated key.
+#[derive(Debug, Clone, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyHashVariant {
+    /// A sha256 hash of the key, encoded as base64.
+    Sha256Base64,
+}
+
+/// The hash of an existing key being migrated into unkey.
+#[derive(Debug, Clone, Serialize, Eq, PartialEq)]
+pub struct KeyHash {
+    /// The encoded hash of the key.
+    pub value: String,
+
+    /// The algorithm and encoding used to produce the hash.
+    pub variant: KeyHashVariant,
+}
+
+impl KeyHash {
+    /// Creates a new key hash from a base64 encoded sha256 hash.
+    ///
+    /// # Arguments
+    /// - `value`: The base64 encoded sha256 hash of the key.
+    ///
+    /// # Returns
+    /// The new key hash.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::KeyHash;
+    /// # use unkey::models::KeyHashVariant;
+    /// let h = KeyHash::sha256_base64("n4bQgYhMfWWaL+qgxVrQFaO/TxsrC4Is0V1sFbDwCgg=");
+    ///
+    /// assert_eq!(h.variant, KeyHashVariant::Sha256Base64);
+    /// ```
+    #[must_use]
+    #[rustfmt::skip]
+    pub fn sha256_base64<T: Into<String>>(value: T) -> Self {
+        Self { value: value.into(), variant: KeyHashVariant::Sha256Base64 }
+    }
+}
+
+/// An outgoing migrate key request, importing an existing hashed key.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrateKeyRequest {
+    /// The api id to migrate this key into.
+    pub api_id: String,
+
+    /// The hash of the existing key.
+    pub hash: KeyHash,
+
+    /// The optional visible start of the key, i.e. `sk_abc`.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub start: UndefinedOr<String>,
+
+    /// The optional owner id for the key.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub owner_id: UndefinedOr<String>,
+
+    /// The optional prefix for the key.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub prefix: UndefinedOr<String>,
+
+    /// The optional name for the key.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub name: UndefinedOr<String>,
+
+    /// The optional dynamic meta mapping for the key.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub meta: UndefinedOr<Value>,
+
+    /// The optional unix epoch in ms when the key should expire.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub expires: UndefinedOr<usize>,
+
+    /// The optional number of uses remaining to set for the key.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub remaining: UndefinedOr<usize>,
+
+    /// The optional ratelimit to set for the key.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub ratelimit: UndefinedOr<Ratelimit>,
+
+    /// The keys refill state, if any.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub refill: UndefinedOr<Refill>,
+
+    /// The optional environment for the key, i.e. `test` or `live`.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub environment: UndefinedOr<String>,
+
+    /// Whether the key should be enabled, defaults to true.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub enabled: UndefinedOr<bool>,
+
+    /// The optional roles to attach to the key.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub roles: UndefinedOr<Vec<String>>,
+
+    /// The optional permissions to attach to the key.
+    #[serde(skip_serializing_if = "UndefinedOr::is_undefined")]
+    pub permissions: UndefinedOr<Vec<String>>,
+}
+
+impl MigrateKeyRequest {
+    /// Creates a new request for key migration.
+    ///
+    /// # Arguments
+    /// - `api_id`: The api id to migrate this key into.
+    /// - `hash`: The hash of the existing key.
+    ///
+    /// # Returns
+    /// The new migrate key request.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::KeyHash;
+    /// # use unkey::models::MigrateKeyRequest;
+    /// # use unkey::models::UndefinedOr;
+    /// let r = MigrateKeyRequest::new("test", KeyHash::sha256_base64("abc="));
+    ///
+    /// assert_eq!(r.api_id, String::from("test"));
+    /// assert_eq!(r.hash.value, String::from("abc="));
+    /// assert_eq!(r.start, UndefinedOr::Undefined);
+    /// assert_eq!(r.owner_id, UndefinedOr::Undefined);
+    /// assert_eq!(r.meta, UndefinedOr::Undefined);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn new<T: Into<String>>(api_id: T, hash: KeyHash) -> Self {
+        Self {
+            api_id: api_id.into(),
+            hash,
+            start: UndefinedOr::Undefined,
+            owner_id: UndefinedOr::Undefined,
+            prefix: UndefinedOr::Undefined,
+            name: UndefinedOr::Undefined,
+            meta: UndefinedOr::Undefined,
+            expires: UndefinedOr::Undefined,
+            remaining: UndefinedOr::Undefined,
+            ratelimit: UndefinedOr::Undefined,
+            refill: UndefinedOr::Undefined,
+            environment: UndefinedOr::Undefined,
+            enabled: UndefinedOr::Undefined,
+            roles: UndefinedOr::Undefined,
+            permissions: UndefinedOr::Undefined,
+        }
+    }
+
+    /// Sets the visible start of the migrated key.
+    ///
+    /// # Arguments
+    /// - `start`: The start to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::KeyHash;
+    /// # use unkey::models::MigrateKeyRequest;
+    /// let r = MigrateKeyRequest::new("test", KeyHash::sha256_base64("abc="))
+    ///     .set_start("sk_abc");
+    ///
+    /// assert_eq!(r.start.inner().unwrap(), &String::from("sk_abc"));
+    /// ```
+    #[must_use]
+    pub fn set_start<T: Into<String>>(mut self, start: T) -> Self {
+        self.start = UndefinedOr::Value(start.into());
+        self
+    }
+
+    /// Sets the external id for the migrated key.
+    ///
+    /// # Arguments
+    /// - `external_id`: The external id to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::KeyHash;
+    /// # use unkey::models::MigrateKeyRequest;
+    /// let r = MigrateKeyRequest::new("test", KeyHash::sha256_base64("abc="))
+    ///     .set_external_id("jonxslays");
+    ///
+    /// assert_eq!(r.owner_id.inner().unwrap(), &String::from("jonxslays"));
+    /// ```
+    #[must_use]
+    pub fn set_external_id<T: Into<String>>(mut self, external_id: T) -> Self {
+        self.owner_id = UndefinedOr::Value(external_id.into());
+        self
+    }
+
+    /// Sets the prefix for the migrated key.
+    ///
+    /// # Arguments
+    /// - `prefix`: The prefix to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::KeyHash;
+    /// # use unkey::models::MigrateKeyRequest;
+    /// let r = MigrateKeyRequest::new("test", KeyHash::sha256_base64("abc="))
+    ///     .set_prefix("sk");
+    ///
+    /// assert_eq!(r.prefix.inner().unwrap(), &String::from("sk"));
+    /// ```
+    #[must_use]
+    pub fn set_prefix<T: Into<String>>(mut self, prefix: T) -> Self {
+        self.prefix = UndefinedOr::Value(prefix.into());
+        self
+    }
+
+    /// Sets the name for the migrated key.
+    ///
+    /// # Arguments
+    /// - `name`: The name to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::KeyHash;
+    /// # use unkey::models::MigrateKeyRequest;
+    /// let r = MigrateKeyRequest::new("test", KeyHash::sha256_base64("abc="))
+    ///     .set_name("legacy_key");
+    ///
+    /// assert_eq!(r.name.inner().unwrap(), &String::from("legacy_key"));
+    /// ```
+    #[must_use]
+    pub fn set_name<T: Into<String>>(mut self, name: T) -> Self {
+        self.name = UndefinedOr::Value(name.into());
+        self
+    }
+
+    /// Sets the dynamic meta mapping for the migrated key.
+    ///
+    /// # Arguments
+    /// - `meta`: The meta to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::KeyHash;
+    /// # use unkey::models::MigrateKeyRequest;
+    /// # use serde_json::json;
+    /// let r = MigrateKeyRequest::new("test", KeyHash::sha256_base64("abc="))
+    ///     .set_meta(json!({"test": 1}));
+    ///
+    /// assert_eq!(r.meta.inner().unwrap(), &json!({"test": 1}));
+    /// ```
+    #[must_use]
+    pub fn set_meta(mut self, meta: Value) -> Self {
+        self.meta = UndefinedOr::Value(meta);
+        self
+    }
+
+    /// Sets the absolute unix epoch in ms when the migrated key expires.
+    ///
+    /// # Arguments
+    /// - `epoch_ms`: The unix epoch in ms the key should expire at.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::KeyHash;
+    /// # use unkey::models::MigrateKeyRequest;
+    /// let r = MigrateKeyRequest::new("test", KeyHash::sha256_base64("abc="))
+    ///     .set_expires_at(1_900_000_000_000);
+    ///
+    /// assert_eq!(r.expires.inner().unwrap(), &1_900_000_000_000);
+    /// ```
+    #[must_use]
+    pub fn set_expires_at(mut self, epoch_ms: usize) -> Self {
+        self.expires = UndefinedOr::Value(epoch_ms);
+        self
+    }
+
+    /// Sets the remaining uses for the migrated key.
+    ///
+    /// # Arguments
+    /// - `remaining`: The remaining uses to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::KeyHash;
+    /// # use unkey::models::MigrateKeyRequest;
+    /// let r = MigrateKeyRequest::new("test", KeyHash::sha256_base64("abc="))
+    ///     .set_remaining(100);
+    ///
+    /// assert_eq!(r.remaining.inner().unwrap(), &100);
+    /// ```
+    #[must_use]
+    pub fn set_remaining(mut self, remaining: usize) -> Self {
+        self.remaining = UndefinedOr::Value(remaining);
+        self
+    }
+
+    /// Sets the ratelimit for the migrated key.
+    ///
+    /// # Arguments
+    /// - `ratelimit`: The ratelimit to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::KeyHash;
+    /// # use unkey::models::MigrateKeyRequest;
+    /// # use unkey::models::Ratelimit;
+    /// # use unkey::models::RatelimitType;
+    /// let ratelimit = Ratelimit::new(RatelimitType::Fast, 10, 10000, 100);
+    /// let r = MigrateKeyRequest::new("test", KeyHash::sha256_base64("abc="))
+    ///     .set_ratelimit(ratelimit.clone());
+    ///
+    /// assert_eq!(r.ratelimit.inner().unwrap(), &ratelimit);
+    /// ```
+    #[must_use]
+    pub fn set_ratelimit(mut self, ratelimit: Ratelimit) -> Self {
+        self.ratelimit = UndefinedOr::Value(ratelimit);
+        self
+    }
+
+    /// Sets the refill for the migrated key.
+    ///
+    /// # Arguments
+    /// - `refill`: The refill to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::KeyHash;
+    /// # use unkey::models::MigrateKeyRequest;
+    /// # use unkey::models::Refill;
+    /// # use unkey::models::RefillInterval;
+    /// let refill = Refill::new(100, RefillInterval::Daily);
+    /// let r = MigrateKeyRequest::new("test", KeyHash::sha256_base64("abc="))
+    ///     .set_refill(refill.clone());
+    ///
+    /// assert_eq!(r.refill.inner().unwrap(), &refill);
+    /// ```
+    #[must_use]
+    pub fn set_refill(mut self, refill: Refill) -> Self {
+        self.refill = UndefinedOr::Value(refill);
+        self
+    }
+
+    /// Sets the environment for the migrated key.
+    ///
+    /// # Arguments
+    /// - `environment`: The environment to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::KeyHash;
+    /// # use unkey::models::MigrateKeyRequest;
+    /// let r = MigrateKeyRequest::new("test", KeyHash::sha256_base64("abc="))
+    ///     .set_environment("live");
+    ///
+    /// assert_eq!(r.environment.inner().unwrap(), &String::from("live"));
+    /// ```
+    #[must_use]
+    pub fn set_environment<T: Into<String>>(mut self, environment: T) -> Self {
+        self.environment = UndefinedOr::Value(environment.into());
+        self
+    }
+
+    /// Sets whether the migrated key is enabled.
+    ///
+    /// # Arguments
+    /// - `enabled`: Whether the key is enabled.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::KeyHash;
+    /// # use unkey::models::MigrateKeyRequest;
+    /// let r = MigrateKeyRequest::new("test", KeyHash::sha256_base64("abc="))
+    ///     .set_enabled(false);
+    ///
+    /// assert_eq!(r.enabled.inner().unwrap(), &false);
+    /// ```
+    #[must_use]
+    pub fn set_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = UndefinedOr::Value(enabled);
+        self
+    }
+
+    /// Sets the roles for the migrated key.
+    ///
+    /// # Arguments
+    /// - `roles`: The names of the roles to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::KeyHash;
+    /// # use unkey::models::MigrateKeyRequest;
+    /// let r = MigrateKeyRequest::new("test", KeyHash::sha256_base64("abc="))
+    ///     .set_roles(&["admin"]);
+    ///
+    /// assert_eq!(r.roles.inner().unwrap(), &vec![String::from("admin")]);
+    /// ```
+    #[must_use]
+    pub fn set_roles(mut self, roles: &[&str]) -> Self {
+        self.roles = UndefinedOr::Value(roles.iter().map(|r| (*r).to_string()).collect());
+        self
+    }
+
+    /// Sets the permissions for the migrated key.
+    ///
+    /// # Arguments
+    /// - `permissions`: The names of the permissions to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::KeyHash;
+    /// # use unkey::models::MigrateKeyRequest;
+    /// let r = MigrateKeyRequest::new("test", KeyHash::sha256_base64("abc="))
+    ///     .set_permissions(&["keys.read"]);
+    ///
+    /// assert_eq!(r.permissions.inner().unwrap(), &vec![String::from("keys.read")]);
+    /// ```
+    #[must_use]
+    pub fn set_permissions(mut self, permissions: &[&str]) -> Self {
+        self.permissions =
+            UndefinedOr::Value(permissions.iter().map(|p| (*p).to_string()).collect());
+        self
+    }
+
+    /// Validates the request before it is sent.
+    ///
+    /// # Returns
+    /// A [`Result`] indicating whether the request is valid.
+    ///
+    /// # Errors
+    /// An [`HttpError`] with [`ErrorCode::BadRequest`] if the serialized
+    /// meta exceeds the limit, see [`DEFAULT_META_SIZE_LIMIT`].
+    ///
+    /// [`ErrorCode::BadRequest`]: crate::models::ErrorCode::BadRequest
+    /// [`DEFAULT_META_SIZE_LIMIT`]: crate::models::DEFAULT_META_SIZE_LIMIT
+    pub fn validate(&self) -> Result<(), HttpError> {
+        self.validate_with_meta_limit(DEFAULT_META_SIZE_LIMIT)
+    }
+
+    /// Validates the request before it is sent, using the given meta limit.
+    ///
+    /// # Arguments
+    /// - `meta_size_limit`: The maximum size of the serialized meta, in bytes.
+    ///
+    /// # Returns
+    /// A [`Result`] indicating whether the request is valid.
+    ///
+    /// # Errors
+    /// An [`HttpError`] with [`ErrorCode::BadRequest`] if the serialized
+    /// meta exceeds the limit.
+    ///
+    /// [`ErrorCode::BadRequest`]: crate::models::ErrorCode::BadRequest
+    pub(crate) fn validate_with_meta_limit(&self, meta_size_limit: usize) -> Result<(), HttpError> {
+        validate_meta_size(&self.meta, meta_size_limit)
+    }
+}
+
+/// An incoming migrate key response.
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct MigrateKeyResponse {
+    /// The unique id of the migrated key.
+    pub key_id: String,
+}
+
+/// The raw response to a migration, which unkey sends for a batch of keys.
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub(crate) struct MigrateKeysResponse {
+    /// The ids of the migrated keys, in the order they were sent.
+    pub key_ids: Vec<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::models::KeyHash;
+    use crate::models::MigrateKeyRequest;
+    use crate::models::MigrateKeysResponse;
+
+    #[test]
+    fn migrate_key_request_serialize() {
+        let hash = KeyHash::sha256_base64("n4bQgYhMfWWaL+qgxVrQFaO/TxsrC4Is0V1sFbDwCgg=");
+        let r = MigrateKeyRequest::new("api_123", hash)
+            .set_start("sk_te")
+            .set_external_id("jonxslays")
+            .set_meta(serde_json::json!({"plan": "pro"}));
+
+        assert_eq!(
+            serde_json::to_string(&r).unwrap(),
+            r#"{"apiId":"api_123","hash":{"value":"n4bQgYhMfWWaL+qgxVrQFaO/TxsrC4Is0V1sFbDwCgg=","variant":"sha256_base64"},"start":"sk_te","ownerId":"jonxslays","meta":{"plan":"pro"}}"#
+        );
+    }
+
+    #[test]
+    fn migrate_keys_response_deserialize() {
+        let r: MigrateKeysResponse = serde_json::from_str(r#"{"keyIds":["key_123"]}"#).unwrap();
+
+        assert_eq!(r.key_ids, vec![String::from("key_123")]);
+    }
+}
@@ -6,14 +6,20 @@
 //! like [`Wrapped`] and [`UndefinedOr`].
 mod apis;
 mod http;
+mod identities;
 mod keys;
+mod migrations;
 mod ratelimit;
 mod refill;
+mod retry;
 mod undefined;
 
 pub use apis::*;
 pub use http::*;
+pub use identities::*;
 pub use keys::*;
+pub use migrations::*;
 pub use ratelimit::*;
 pub use refill::*;
+pub use retry::*;
 pub use undefined::*;
@@ -1,9 +1,17 @@
 #![allow(clippy::module_name_repetitions)]
 
+use reqwest::header::HeaderMap;
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "chrono")]
+use super::keys::millis_to_datetime;
+use super::ErrorCode;
+use super::HttpError;
+use crate::clock;
+
 /// A snapshot of the ratelimit status for a key.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct RatelimitState {
     /// The number of burstable requests allowed.
     pub limit: usize,
@@ -15,6 +23,106 @@ pub struct RatelimitState {
     pub reset: usize,
 }
 
+impl RatelimitState {
+    /// Whether the ratelimit has been exceeded for this window.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::RatelimitState;
+    /// let r: RatelimitState = serde_json::from_str(r#"{"limit":10,"remaining":0,"reset":0}"#).unwrap();
+    ///
+    /// assert!(r.is_exceeded());
+    /// ```
+    #[must_use]
+    pub fn is_exceeded(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// The whole seconds until the next window starts, rounded up and
+    /// clamped at zero, i.e. for a `Retry-After` header.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::RatelimitState;
+    /// let r: RatelimitState = serde_json::from_str(r#"{"limit":10,"remaining":0,"reset":0}"#).unwrap();
+    ///
+    /// assert_eq!(r.seconds_until_reset(), 0);
+    /// ```
+    #[must_use]
+    pub fn seconds_until_reset(&self) -> i64 {
+        self.seconds_until_reset_from(clock::now().as_millis())
+    }
+
+    /// The whole seconds from the given time until the next window starts.
+    ///
+    /// # Arguments
+    /// - `now`: The current unix timestamp in ms.
+    fn seconds_until_reset_from(&self, now: u128) -> i64 {
+        let remaining_ms = (self.reset as u128).saturating_sub(now);
+        let seconds = (remaining_ms + 999) / 1000;
+
+        i64::try_from(seconds).unwrap_or(i64::MAX)
+    }
+
+    /// The time the next window starts, as a date time.
+    ///
+    /// ## Note
+    /// Requires the `chrono` feature.
+    ///
+    /// # Returns
+    /// The date time the next window starts, if the timestamp is in range.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::RatelimitState;
+    /// let r: RatelimitState = serde_json::from_str(
+    ///     r#"{"limit":10,"remaining":5,"reset":1700000000000}"#,
+    /// ).unwrap();
+    /// let at = r.reset_datetime().unwrap();
+    ///
+    /// assert_eq!(at.timestamp_millis(), 1_700_000_000_000);
+    /// ```
+    #[cfg(feature = "chrono")]
+    #[must_use]
+    pub fn reset_datetime(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        millis_to_datetime(self.reset)
+    }
+}
+
+/// The ratelimit headers unkey sent with a response.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct RatelimitHeaders {
+    /// The number of requests allowed in the window, from `X-RateLimit-Limit`.
+    pub limit: usize,
+
+    /// The requests remaining in the window, from `X-RateLimit-Remaining`.
+    pub remaining: usize,
+
+    /// When the window resets, from `X-RateLimit-Reset`.
+    pub reset: u64,
+}
+
+impl RatelimitHeaders {
+    /// Parses the ratelimit headers from a response.
+    ///
+    /// # Arguments
+    /// - `headers`: The response headers.
+    ///
+    /// # Returns
+    /// The ratelimit headers, if all of them were present and valid.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        fn parse<T: std::str::FromStr>(headers: &HeaderMap, name: &str) -> Option<T> {
+            headers.get(name)?.to_str().ok()?.trim().parse().ok()
+        }
+
+        Some(Self {
+            limit: parse(headers, "x-ratelimit-limit")?,
+            remaining: parse(headers, "x-ratelimit-remaining")?,
+            reset: parse(headers, "x-ratelimit-reset")?,
+        })
+    }
+}
+
 /// Different rate limit types implemented by unkey.
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -32,6 +140,7 @@ pub enum RatelimitType {
 /// A ratelimit imposed on an api key.
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Ratelimit {
     /// The type for this ratelimit.
     #[serde(rename = "type")]
@@ -45,6 +154,15 @@ pub struct Ratelimit {
 
     /// Total number of burstable requests.
     pub limit: usize,
+
+    /// Whether the ratelimit is checked asynchronously.
+    ///
+    /// This is the newer equivalent of [`RatelimitType`], where `true`
+    /// behaves like [`RatelimitType::Fast`] and `false` behaves like
+    /// [`RatelimitType::Consistent`]. When set, unkey uses this flag over
+    /// the type. When `None` it is omitted, and the type alone applies.
+    #[serde(rename = "async", default, skip_serializing_if = "Option::is_none")]
+    pub async_limiting: Option<bool>,
 }
 
 impl Ratelimit {
@@ -74,6 +192,7 @@ impl Ratelimit {
     /// assert_eq!(r.refill_rate, 10);
     /// assert_eq!(r.refill_interval, 10000);
     /// assert_eq!(r.limit, 100);
+    /// assert_eq!(r.async_limiting, None);
     /// ```
     #[must_use]
     pub fn new(
@@ -87,6 +206,319 @@ impl Ratelimit {
             refill_rate,
             refill_interval,
             limit,
+            async_limiting: None,
+        }
+    }
+
+    /// Creates a new ratelimit, validating its values.
+    ///
+    /// # Arguments
+    /// - `ratelimit_type`: The type for this ratelimit.
+    /// - `refill_rate`: The rate at which the ratelimit refills, per interval.
+    /// - `refill_interval`: The interval at which to refill, in milliseconds.
+    /// - `limit`: Total number of burstable requests.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the new ratelimit, or an error.
+    ///
+    /// # Errors
+    /// An [`HttpError`] with [`ErrorCode::BadRequest`] if any of
+    /// `refill_rate`, `refill_interval`, or `limit` is zero.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::ErrorCode;
+    /// # use unkey::models::Ratelimit;
+    /// # use unkey::models::RatelimitType;
+    /// let r = Ratelimit::try_new(RatelimitType::Fast, 10, 10000, 100).unwrap();
+    ///
+    /// assert_eq!(r, Ratelimit::new(RatelimitType::Fast, 10, 10000, 100));
+    ///
+    /// let e = Ratelimit::try_new(RatelimitType::Fast, 10, 0, 100).unwrap_err();
+    ///
+    /// assert_eq!(e.code, ErrorCode::BadRequest);
+    /// ```
+    pub fn try_new(
+        ratelimit_type: RatelimitType,
+        refill_rate: usize,
+        refill_interval: usize,
+        limit: usize,
+    ) -> Result<Self, HttpError> {
+        let fields = [
+            ("refill_rate", refill_rate),
+            ("refill_interval", refill_interval),
+            ("limit", limit),
+        ];
+
+        if let Some((name, _)) = fields.iter().find(|(_, value)| *value == 0) {
+            return Err(HttpError::new(
+                ErrorCode::BadRequest,
+                format!("The ratelimit {name} must be greater than 0."),
+            ));
+        }
+
+        Ok(Self::new(
+            ratelimit_type,
+            refill_rate,
+            refill_interval,
+            limit,
+        ))
+    }
+
+    /// Sets whether the ratelimit is checked asynchronously.
+    ///
+    /// # Arguments
+    /// - `async_limiting`: Whether to check the ratelimit asynchronously.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::Ratelimit;
+    /// # use unkey::models::RatelimitType;
+    /// let r = Ratelimit::new(RatelimitType::Fast, 10, 10000, 100).set_async(true);
+    ///
+    /// assert_eq!(r.async_limiting, Some(true));
+    /// ```
+    #[must_use]
+    pub fn set_async(mut self, async_limiting: bool) -> Self {
+        self.async_limiting = Some(async_limiting);
+        self
+    }
+}
+
+/// An outgoing standalone ratelimit request.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RatelimitRequest {
+    /// The namespace this ratelimit belongs to.
+    pub namespace: String,
+
+    /// The identifier to ratelimit, i.e. a user or ip address.
+    pub identifier: String,
+
+    /// The maximum number of requests allowed in the window.
+    pub limit: usize,
+
+    /// The duration of the window, in milliseconds.
+    pub duration: usize,
+
+    /// The cost of this request against the limit, defaults to 1.
+    pub cost: usize,
+}
+
+impl RatelimitRequest {
+    /// Creates a new ratelimit request.
+    ///
+    /// # Arguments
+    /// - `namespace`: The namespace this ratelimit belongs to.
+    /// - `identifier`: The identifier to ratelimit.
+    /// - `limit`: The maximum number of requests allowed in the window.
+    /// - `duration`: The duration of the window, in milliseconds.
+    ///
+    /// # Returns
+    /// The new ratelimit request.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::RatelimitRequest;
+    /// let r = RatelimitRequest::new("email.outbound", "user_123", 10, 60000);
+    ///
+    /// assert_eq!(r.namespace, String::from("email.outbound"));
+    /// assert_eq!(r.identifier, String::from("user_123"));
+    /// assert_eq!(r.limit, 10);
+    /// assert_eq!(r.duration, 60000);
+    /// assert_eq!(r.cost, 1);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn new<T: Into<String>>(
+        namespace: T,
+        identifier: T,
+        limit: usize,
+        duration: usize,
+    ) -> Self {
+        Self {
+            namespace: namespace.into(),
+            identifier: identifier.into(),
+            limit,
+            duration,
+            cost: 1,
         }
     }
+
+    /// Sets the cost of this request against the limit.
+    ///
+    /// # Arguments
+    /// - `cost`: The cost to set.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::RatelimitRequest;
+    /// let r = RatelimitRequest::new("email.outbound", "user_123", 10, 60000).set_cost(5);
+    ///
+    /// assert_eq!(r.cost, 5);
+    /// ```
+    #[must_use]
+    pub fn set_cost(mut self, cost: usize) -> Self {
+        self.cost = cost;
+        self
+    }
+}
+
+/// An incoming standalone ratelimit response.
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct RatelimitResponse {
+    /// Whether the request passed the ratelimit.
+    pub success: bool,
+
+    /// The maximum number of requests allowed in the window.
+    pub limit: usize,
+
+    /// The remaining requests in the current window.
+    pub remaining: usize,
+
+    /// The unix timestamp in ms when the next window starts.
+    pub reset: usize,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::models::ErrorCode;
+    use crate::models::Ratelimit;
+    use crate::models::RatelimitRequest;
+    use crate::models::RatelimitState;
+    use crate::models::RatelimitType;
+
+    #[test]
+    fn ratelimit_serialize_without_async() {
+        let r = Ratelimit::new(RatelimitType::Fast, 10, 1000, 100);
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(
+            res.as_str(),
+            r#"{"type":"fast","refillRate":10,"refillInterval":1000,"limit":100}"#
+        );
+    }
+
+    #[test]
+    fn ratelimit_serialize_with_async() {
+        let r = Ratelimit::new(RatelimitType::Consistent, 10, 1000, 100).set_async(false);
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(
+            res.as_str(),
+            r#"{"type":"consistent","refillRate":10,"refillInterval":1000,"limit":100,"async":false}"#
+        );
+    }
+
+    #[test]
+    fn ratelimit_request_serialize() {
+        let r = RatelimitRequest::new("ns", "user", 10, 1000);
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(
+            res.as_str(),
+            r#"{"namespace":"ns","identifier":"user","limit":10,"duration":1000,"cost":1}"#
+        );
+    }
+
+    #[test]
+    fn ratelimit_request_serialize_cost() {
+        let r = RatelimitRequest::new("ns", "user", 10, 1000).set_cost(3);
+        let res = serde_json::to_string(&r).unwrap();
+
+        assert_eq!(
+            res.as_str(),
+            r#"{"namespace":"ns","identifier":"user","limit":10,"duration":1000,"cost":3}"#
+        );
+    }
+
+    #[test]
+    fn ratelimit_try_new_valid() {
+        let r = Ratelimit::try_new(RatelimitType::Consistent, 1, 1, 1).unwrap();
+
+        assert_eq!(r, Ratelimit::new(RatelimitType::Consistent, 1, 1, 1));
+    }
+
+    #[test]
+    fn ratelimit_try_new_zero_refill_rate() {
+        let e = Ratelimit::try_new(RatelimitType::Fast, 0, 1000, 100).unwrap_err();
+
+        assert_eq!(e.code, ErrorCode::BadRequest);
+        assert_eq!(
+            e.message,
+            "The ratelimit refill_rate must be greater than 0."
+        );
+    }
+
+    #[test]
+    fn ratelimit_try_new_zero_refill_interval() {
+        let e = Ratelimit::try_new(RatelimitType::Fast, 10, 0, 100).unwrap_err();
+
+        assert_eq!(e.code, ErrorCode::BadRequest);
+        assert_eq!(
+            e.message,
+            "The ratelimit refill_interval must be greater than 0."
+        );
+    }
+
+    #[test]
+    fn ratelimit_try_new_zero_limit() {
+        let e = Ratelimit::try_new(RatelimitType::Fast, 10, 1000, 0).unwrap_err();
+
+        assert_eq!(e.code, ErrorCode::BadRequest);
+        assert_eq!(e.message, "The ratelimit limit must be greater than 0.");
+    }
+
+    const RESET: usize = 1_700_000_060_000;
+
+    fn state(remaining: usize) -> RatelimitState {
+        RatelimitState {
+            limit: 10,
+            remaining,
+            reset: RESET,
+        }
+    }
+
+    #[test]
+    fn ratelimit_state_is_exceeded() {
+        assert!(state(0).is_exceeded());
+        assert!(!state(1).is_exceeded());
+    }
+
+    #[test]
+    fn ratelimit_state_seconds_until_reset() {
+        let r = state(0);
+        let reset = RESET as u128;
+
+        assert_eq!(r.seconds_until_reset_from(reset - 60_000), 60);
+        assert_eq!(r.seconds_until_reset_from(reset - 1_500), 2);
+        assert_eq!(r.seconds_until_reset_from(reset - 1), 1);
+        assert_eq!(r.seconds_until_reset_from(reset), 0);
+        assert_eq!(r.seconds_until_reset_from(reset + 5_000), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn ratelimit_state_reset_datetime() {
+        use chrono::TimeZone;
+
+        let body = r#"{"limit":10,"remaining":5,"reset":1700000000000}"#;
+        let r: RatelimitState = serde_json::from_str(body).unwrap();
+
+        assert_eq!(
+            r.reset_datetime(),
+            Some(
+                chrono::Utc
+                    .with_ymd_and_hms(2023, 11, 14, 22, 13, 20)
+                    .unwrap()
+            )
+        );
+    }
 }
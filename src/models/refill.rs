@@ -17,6 +17,7 @@ pub enum RefillInterval {
 /// The state of a keys automatic refills.
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Refill {
     /// The number of verifications to refill.
     pub amount: usize,
@@ -54,4 +55,69 @@ impl Refill {
     pub fn new(amount: usize, interval: RefillInterval) -> Self {
         Self { amount, interval, last_refilled_at: None }
     }
+
+    /// The time the key was last refilled, as a date time.
+    ///
+    /// ## Note
+    /// Requires the `chrono` feature.
+    ///
+    /// # Returns
+    /// The date time of the last refill, if the key has been refilled and
+    /// the timestamp is in range.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::Refill;
+    /// let r: Refill = serde_json::from_str(
+    ///     r#"{"amount":100,"interval":"daily","lastRefilledAt":1700000000000}"#,
+    /// ).unwrap();
+    /// let at = r.last_refilled_datetime().unwrap();
+    ///
+    /// assert_eq!(at.timestamp_millis(), 1_700_000_000_000);
+    /// ```
+    #[cfg(feature = "chrono")]
+    #[must_use]
+    pub fn last_refilled_datetime(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let millis = i64::try_from(self.last_refilled_at?).ok()?;
+        chrono::DateTime::from_timestamp_millis(millis)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::models::Refill;
+    use crate::models::RefillInterval;
+
+    #[test]
+    fn refill_deserialize_last_refilled_at() {
+        let body = r#"{"amount":100,"interval":"daily","lastRefilledAt":123}"#;
+        let r: Refill = serde_json::from_str(body).unwrap();
+
+        assert_eq!(r.amount, 100);
+        assert_eq!(r.interval, RefillInterval::Daily);
+        assert_eq!(r.last_refilled_at, Some(123));
+    }
+
+    #[test]
+    fn refill_serialize_skips_last_refilled_at() {
+        let mut r = Refill::new(100, RefillInterval::Monthly);
+        r.last_refilled_at = Some(123);
+
+        assert_eq!(
+            serde_json::to_string(&r).unwrap(),
+            r#"{"amount":100,"interval":"monthly"}"#
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn refill_last_refilled_datetime() {
+        let mut r = Refill::new(100, RefillInterval::Daily);
+
+        assert_eq!(r.last_refilled_datetime(), None);
+
+        r.last_refilled_at = Some(123);
+
+        assert_eq!(r.last_refilled_datetime().unwrap().timestamp_millis(), 123);
+    }
 }
@@ -0,0 +1,166 @@
+#![allow(clippy::module_name_repetitions)]
+
+use std::time::Duration;
+
+use crate::clock;
+
+/// The policy used to retry failed requests with exponential backoff.
+///
+/// Only idempotent requests are retried, and only when the unkey api
+/// responds with a server error or ratelimit, or when the connection fails.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RetryPolicy {
+    /// The maximum number of retries to attempt after the initial request.
+    pub max_retries: usize,
+
+    /// The delay before the first retry, doubled for each subsequent retry.
+    pub base_delay: Duration,
+
+    /// The maximum delay between any two attempts.
+    pub max_delay: Duration,
+
+    /// Whether to randomize the delay between attempts.
+    pub jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Creates a new retry policy.
+    ///
+    /// # Arguments
+    /// - `max_retries`: The maximum number of retries to attempt.
+    /// - `base_delay`: The delay before the first retry.
+    /// - `max_delay`: The maximum delay between any two attempts.
+    /// - `jitter`: Whether to randomize the delay between attempts.
+    ///
+    /// # Returns
+    /// The new retry policy.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::time::Duration;
+    /// # use unkey::models::RetryPolicy;
+    /// let r = RetryPolicy::new(
+    ///     3,
+    ///     Duration::from_millis(100),
+    ///     Duration::from_secs(2),
+    ///     false,
+    /// );
+    ///
+    /// assert_eq!(r.max_retries, 3);
+    /// assert_eq!(r.base_delay, Duration::from_millis(100));
+    /// assert_eq!(r.max_delay, Duration::from_secs(2));
+    /// assert!(!r.jitter);
+    /// ```
+    #[must_use]
+    pub fn new(
+        max_retries: usize,
+        base_delay: Duration,
+        max_delay: Duration,
+        jitter: bool,
+    ) -> Self {
+        Self {
+            max_retries,
+            base_delay,
+            max_delay,
+            jitter,
+        }
+    }
+
+    /// Calculates the delay to wait before the given retry attempt.
+    ///
+    /// # Arguments
+    /// - `attempt`: The zero based retry attempt.
+    ///
+    /// # Returns
+    /// The delay to wait.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::time::Duration;
+    /// # use unkey::models::RetryPolicy;
+    /// let r = RetryPolicy::new(
+    ///     5,
+    ///     Duration::from_millis(100),
+    ///     Duration::from_millis(300),
+    ///     false,
+    /// );
+    ///
+    /// assert_eq!(r.delay_for(0), Duration::from_millis(100));
+    /// assert_eq!(r.delay_for(1), Duration::from_millis(200));
+    /// assert_eq!(r.delay_for(2), Duration::from_millis(300));
+    /// ```
+    #[must_use]
+    pub fn delay_for(&self, attempt: usize) -> Duration {
+        let factor = 1u32
+            .checked_shl(u32::try_from(attempt).unwrap_or(u32::MAX))
+            .unwrap_or(u32::MAX);
+        let delay = self
+            .base_delay
+            .checked_mul(factor)
+            .map_or(self.max_delay, |d| d.min(self.max_delay));
+
+        if !self.jitter {
+            return delay;
+        }
+
+        // Full range randomness isn't needed here, the sub-second clock
+        // is plenty to keep clients from retrying in lockstep
+        let nanos = clock::now().subsec_nanos();
+
+        let half = delay / 2;
+        let spread = u64::try_from(half.as_nanos()).unwrap_or(u64::MAX);
+        let offset = if spread == 0 {
+            0
+        } else {
+            u64::from(nanos) % spread
+        };
+
+        half + Duration::from_nanos(offset)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(3, Duration::from_millis(100), Duration::from_secs(5), true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::models::RetryPolicy;
+
+    #[test]
+    fn default() {
+        let r = RetryPolicy::default();
+
+        assert_eq!(r.max_retries, 3);
+        assert_eq!(r.base_delay, Duration::from_millis(100));
+        assert_eq!(r.max_delay, Duration::from_secs(5));
+        assert!(r.jitter);
+    }
+
+    #[test]
+    fn delay_for_caps_at_max() {
+        let r = RetryPolicy::new(100, Duration::from_secs(1), Duration::from_secs(10), false);
+
+        assert_eq!(r.delay_for(3), Duration::from_secs(8));
+        assert_eq!(r.delay_for(4), Duration::from_secs(10));
+        assert_eq!(r.delay_for(64), Duration::from_secs(10));
+        assert_eq!(r.delay_for(usize::MAX), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn delay_for_with_jitter() {
+        let r = RetryPolicy::new(3, Duration::from_millis(100), Duration::from_secs(1), true);
+
+        for attempt in 0..5 {
+            let delay = r.delay_for(attempt);
+            let max = Duration::from_millis(100 << attempt).min(Duration::from_secs(1));
+
+            assert!(delay >= max / 2);
+            assert!(delay <= max);
+        }
+    }
+}
@@ -1,10 +1,17 @@
 #![allow(clippy::module_name_repetitions)]
 
 use serde::ser::Error;
+use serde::Deserialize;
+use serde::Deserializer;
 use serde::Serialize;
 
 /// Represents the potential absence of a value beyond `None`.
-#[derive(Debug, Clone, Eq, PartialEq)]
+///
+/// ## Note
+/// When deserializing, a missing field can only be detected if the field is
+/// annotated with `#[serde(default)]`. Without it, serde will reject the
+/// missing field rather than producing [`UndefinedOr::Undefined`].
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
 pub enum UndefinedOr<T> {
     /// The value is present (T).
     Value(T),
@@ -13,6 +20,7 @@ pub enum UndefinedOr<T> {
     Null,
 
     /// The value is not present (undefined).
+    #[default]
     Undefined,
 }
 
@@ -79,11 +87,90 @@ impl<T> UndefinedOr<T> {
             _ => None,
         }
     }
-}
 
-impl<T> Default for UndefinedOr<T> {
-    fn default() -> Self {
-        Self::Undefined
+    /// Converts from `&UndefinedOr<T>` to `UndefinedOr<&T>`.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UndefinedOr;
+    /// let val = UndefinedOr::Value(String::from("hi"));
+    ///
+    /// assert_eq!(val.as_ref(), UndefinedOr::Value(&String::from("hi")));
+    ///
+    /// let val = UndefinedOr::<String>::Null;
+    ///
+    /// assert_eq!(val.as_ref(), UndefinedOr::Null);
+    /// ```
+    pub fn as_ref(&self) -> UndefinedOr<&T> {
+        match self {
+            Self::Value(v) => UndefinedOr::Value(v),
+            Self::Null => UndefinedOr::Null,
+            Self::Undefined => UndefinedOr::Undefined,
+        }
+    }
+
+    /// Converts from `&mut UndefinedOr<T>` to `UndefinedOr<&mut T>`.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UndefinedOr;
+    /// let mut val = UndefinedOr::Value(68);
+    ///
+    /// if let UndefinedOr::Value(v) = val.as_mut() {
+    ///     *v += 1;
+    /// }
+    ///
+    /// assert_eq!(val, UndefinedOr::Value(69));
+    /// ```
+    pub fn as_mut(&mut self) -> UndefinedOr<&mut T> {
+        match self {
+            Self::Value(v) => UndefinedOr::Value(v),
+            Self::Null => UndefinedOr::Null,
+            Self::Undefined => UndefinedOr::Undefined,
+        }
+    }
+
+    /// Takes the value out, leaving [`UndefinedOr::Undefined`] in its place.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UndefinedOr;
+    /// let mut val = UndefinedOr::Value(69);
+    ///
+    /// assert_eq!(val.take(), UndefinedOr::Value(69));
+    /// assert_eq!(val, UndefinedOr::Undefined);
+    /// ```
+    #[must_use]
+    pub fn take(&mut self) -> UndefinedOr<T> {
+        std::mem::take(self)
+    }
+
+    /// Inserts the value if there is none, then returns a mutable reference
+    /// to the contained value.
+    ///
+    /// Both [`UndefinedOr::Null`] and [`UndefinedOr::Undefined`] are
+    /// replaced by the value.
+    ///
+    /// # Example
+    /// ```
+    /// # use unkey::models::UndefinedOr;
+    /// let mut val = UndefinedOr::<u8>::Null;
+    ///
+    /// *val.get_or_insert(68) += 1;
+    ///
+    /// assert_eq!(val, UndefinedOr::Value(69));
+    /// assert_eq!(val.get_or_insert(0), &mut 69);
+    /// ```
+    pub fn get_or_insert(&mut self, value: T) -> &mut T {
+        if !self.is_some() {
+            *self = Self::Value(value);
+        }
+
+        match self {
+            Self::Value(v) => v,
+            // The value was inserted above
+            Self::Null | Self::Undefined => unreachable!(),
+        }
     }
 }
 
@@ -100,6 +187,17 @@ impl<T: Serialize> Serialize for UndefinedOr<T> {
     }
 }
 
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for UndefinedOr<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // A missing field never reaches this point, it is handled by the
+        // `#[serde(default)]` attribute on the field instead
+        Option::<T>::deserialize(deserializer).map(Into::into)
+    }
+}
+
 impl<T> From<Option<T>> for UndefinedOr<T> {
     fn from(value: Option<T>) -> Self {
         match value {
@@ -111,6 +209,7 @@ impl<T> From<Option<T>> for UndefinedOr<T> {
 
 #[cfg(test)]
 mod test {
+    use serde::Deserialize;
     use serde::Serialize;
 
     use crate::models::UndefinedOr;
@@ -127,6 +226,12 @@ mod test {
         d: UndefinedOr<u32>,
     }
 
+    #[derive(Deserialize)]
+    struct TestDeStruct {
+        #[serde(default)]
+        a: UndefinedOr<u32>,
+    }
+
     #[test]
     fn default() {
         let d: UndefinedOr<u32> = Default::default();
@@ -185,6 +290,24 @@ mod test {
         assert_eq!(res.as_str(), r#"{"a":69,"b":420,"c":null}"#)
     }
 
+    #[test]
+    fn deserialize_undefined() {
+        let t: TestDeStruct = serde_json::from_str("{}").unwrap();
+        assert_eq!(t.a, UndefinedOr::Undefined);
+    }
+
+    #[test]
+    fn deserialize_null() {
+        let t: TestDeStruct = serde_json::from_str(r#"{"a":null}"#).unwrap();
+        assert_eq!(t.a, UndefinedOr::Null);
+    }
+
+    #[test]
+    fn deserialize_value() {
+        let t: TestDeStruct = serde_json::from_str(r#"{"a":5}"#).unwrap();
+        assert_eq!(t.a, UndefinedOr::Value(5));
+    }
+
     #[test]
     fn from_some() {
         let o = Some(69);
@@ -198,4 +321,55 @@ mod test {
         let res = UndefinedOr::from(o);
         assert_eq!(res, UndefinedOr::Null);
     }
+
+    #[test]
+    fn as_ref_preserves_variant() {
+        assert_eq!(UndefinedOr::Value(69).as_ref(), UndefinedOr::Value(&69));
+        assert_eq!(UndefinedOr::<u8>::Null.as_ref(), UndefinedOr::Null);
+        assert_eq!(
+            UndefinedOr::<u8>::Undefined.as_ref(),
+            UndefinedOr::Undefined
+        );
+    }
+
+    #[test]
+    fn as_mut_preserves_variant() {
+        let mut val = UndefinedOr::Value(69);
+        assert_eq!(val.as_mut(), UndefinedOr::Value(&mut 69));
+
+        let mut val = UndefinedOr::<u8>::Null;
+        assert_eq!(val.as_mut(), UndefinedOr::Null);
+
+        let mut val = UndefinedOr::<u8>::Undefined;
+        assert_eq!(val.as_mut(), UndefinedOr::Undefined);
+    }
+
+    #[test]
+    fn take_resets_to_undefined() {
+        let mut val = UndefinedOr::Value(69);
+        assert_eq!(val.take(), UndefinedOr::Value(69));
+        assert_eq!(val, UndefinedOr::Undefined);
+
+        let mut val = UndefinedOr::<u8>::Null;
+        assert_eq!(val.take(), UndefinedOr::Null);
+        assert_eq!(val, UndefinedOr::Undefined);
+
+        let mut val = UndefinedOr::<u8>::Undefined;
+        assert_eq!(val.take(), UndefinedOr::Undefined);
+        assert_eq!(val, UndefinedOr::Undefined);
+    }
+
+    #[test]
+    fn get_or_insert() {
+        let mut val = UndefinedOr::Value(69);
+        assert_eq!(val.get_or_insert(420), &mut 69);
+
+        let mut val = UndefinedOr::Null;
+        assert_eq!(val.get_or_insert(420), &mut 420);
+        assert_eq!(val, UndefinedOr::Value(420));
+
+        let mut val = UndefinedOr::Undefined;
+        *val.get_or_insert(41) += 1;
+        assert_eq!(val, UndefinedOr::Value(42));
+    }
 }
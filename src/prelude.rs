@@ -0,0 +1,48 @@
+//! Re-exports the client and the types most commonly used alongside it.
+//!
+//! # Example
+//! ```
+//! use unkey::prelude::*;
+//!
+//! let c = Client::new("unkey_ghj");
+//! let req = CreateKeyRequest::new("api_123").set_remaining(100);
+//! let update = UpdateKeyRequest::new("key_123").set_name(None);
+//!
+//! assert_eq!(update.name, UndefinedOr::Null);
+//! ```
+pub use crate::models::AddPermissionsRequest;
+pub use crate::models::ApiKey;
+pub use crate::models::CreateIdentityRequest;
+pub use crate::models::CreateIdentityResponse;
+pub use crate::models::CreateKeyRequest;
+pub use crate::models::CreateKeyResponse;
+pub use crate::models::DeleteApiRequest;
+pub use crate::models::DeleteIdentityRequest;
+pub use crate::models::DeleteKeyRequest;
+pub use crate::models::Error;
+pub use crate::models::ErrorCode;
+pub use crate::models::GetApiRequest;
+pub use crate::models::GetApiResponse;
+pub use crate::models::GetIdentityRequest;
+pub use crate::models::GetKeyRequest;
+pub use crate::models::GetUsageNumbersRequest;
+pub use crate::models::GetUsageNumbersResponse;
+pub use crate::models::HttpError;
+pub use crate::models::Identity;
+pub use crate::models::ListApisRequest;
+pub use crate::models::ListApisResponse;
+pub use crate::models::ListIdentitiesRequest;
+pub use crate::models::ListIdentitiesResponse;
+pub use crate::models::ListKeysRequest;
+pub use crate::models::ListKeysResponse;
+pub use crate::models::RatelimitRequest;
+pub use crate::models::RatelimitResponse;
+pub use crate::models::RemovePermissionsRequest;
+pub use crate::models::UndefinedOr;
+pub use crate::models::UpdateApiRequest;
+pub use crate::models::UpdateKeyRequest;
+pub use crate::models::UpdateRemainingRequest;
+pub use crate::models::UpdateRemainingResponse;
+pub use crate::models::VerifyKeyRequest;
+pub use crate::models::VerifyKeyResponse;
+pub use crate::Client;
@@ -1,3 +1,7 @@
+use std::borrow::Cow;
+
+use percent_encoding::AsciiSet;
+use percent_encoding::NON_ALPHANUMERIC;
 use reqwest::Method;
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -5,22 +9,41 @@ use reqwest::Method;
 ////////////////////////////////////////////////////////////////////////////////
 
 /// The create key endpoint `POST /keys.createKey`
-pub(crate) static CREATE_KEY: Route = Route::new(Method::POST, "/keys.createKey");
+pub(crate) static CREATE_KEY: Route =
+    Route::new(Method::POST, "/keys.createKey").set_idempotent(false);
 
 /// The verify key endpoint `POST /keys.verifyKey`
-pub(crate) static VERIFY_KEY: Route = Route::new(Method::POST, "/keys.verifyKey");
+pub(crate) static VERIFY_KEY: Route =
+    Route::new(Method::POST, "/keys.verifyKey").set_idempotent(false);
 
 /// The delete key endpoint `POST /keys.deleteKey`
-pub(crate) static REVOKE_KEY: Route = Route::new(Method::POST, "/keys.deleteKey");
+pub(crate) static DELETE_KEY: Route = Route::new(Method::POST, "/keys.deleteKey");
 
 /// The update key endpoint `POST /keys.updateKey`
 pub(crate) static UPDATE_KEY: Route = Route::new(Method::POST, "/keys.updateKey");
 
 /// The get key endpoint `GET /keys.getKey`
+///
+/// The key id is sent as the `keyId` query param, the older `GET /keys/{id}`
+/// path form is deprecated by unkey.
 pub(crate) static GET_KEY: Route = Route::new(Method::GET, "/keys.getKey");
 
 /// The update remaining endpoint `POST /keys.updateRemaining`
-pub(crate) static UPDATE_REMAINING: Route = Route::new(Method::POST, "/keys.updateRemaining");
+pub(crate) static UPDATE_REMAINING: Route =
+    Route::new(Method::POST, "/keys.updateRemaining").set_idempotent(false);
+
+/// The get verifications endpoint `GET /keys.getVerifications`
+pub(crate) static GET_VERIFICATIONS: Route = Route::new(Method::GET, "/keys.getVerifications");
+
+/// The add permissions endpoint `POST /keys.addPermissions`
+pub(crate) static ADD_PERMISSIONS: Route = Route::new(Method::POST, "/keys.addPermissions");
+
+/// The remove permissions endpoint `POST /keys.removePermissions`
+pub(crate) static REMOVE_PERMISSIONS: Route = Route::new(Method::POST, "/keys.removePermissions");
+
+/// The migrate keys endpoint `POST /migrations.createKeys`
+pub(crate) static MIGRATE_KEYS: Route =
+    Route::new(Method::POST, "/migrations.createKeys").set_idempotent(false);
 
 ////////////////////////////////////////////////////////////////////////////////
 
@@ -33,10 +56,45 @@ pub(crate) static LIST_KEYS: Route = Route::new(Method::GET, "/apis.listKeys");
 /// The delete api endpoint `POST /apis.deleteApi`
 pub(crate) static DELETE_API: Route = Route::new(Method::POST, "/apis.deleteApi");
 
+/// The list apis endpoint `GET /apis.listApis`
+pub(crate) static LIST_APIS: Route = Route::new(Method::GET, "/apis.listApis");
+
+/// The update api endpoint `POST /apis.updateApi`
+pub(crate) static UPDATE_API: Route = Route::new(Method::POST, "/apis.updateApi");
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// The ratelimit endpoint `POST /ratelimits.limit`
+pub(crate) static LIMIT: Route =
+    Route::new(Method::POST, "/ratelimits.limit").set_idempotent(false);
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// The create identity endpoint `POST /identities.createIdentity`
+pub(crate) static CREATE_IDENTITY: Route =
+    Route::new(Method::POST, "/identities.createIdentity").set_idempotent(false);
+
+/// The get identity endpoint `GET /identities.getIdentity`
+pub(crate) static GET_IDENTITY: Route = Route::new(Method::GET, "/identities.getIdentity");
+
+/// The list identities endpoint `GET /identities.listIdentities`
+pub(crate) static LIST_IDENTITIES: Route = Route::new(Method::GET, "/identities.listIdentities");
+
+/// The delete identity endpoint `POST /identities.deleteIdentity`
+pub(crate) static DELETE_IDENTITY: Route = Route::new(Method::POST, "/identities.deleteIdentity");
+
 ////////////////////////////////////////////////////////////////////////////////
 // END ROUTES
 ////////////////////////////////////////////////////////////////////////////////
 
+/// The characters to percent-encode in query params and path segments,
+/// everything except the unreserved characters from RFC 3986.
+const UNRESERVED: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
 /// A static route mapping to an unkey api endpoint.
 #[derive(Debug, Clone)]
 pub(crate) struct Route {
@@ -45,6 +103,9 @@ pub(crate) struct Route {
 
     /// The routes uri.
     pub uri: &'static str,
+
+    /// Whether the route is safe to retry.
+    pub idempotent: bool,
 }
 
 impl Route {
@@ -61,7 +122,24 @@ impl Route {
     /// The new route.
     #[must_use]
     pub const fn new(method: Method, uri: &'static str) -> Self {
-        Self { method, uri }
+        Self {
+            method,
+            uri,
+            idempotent: true,
+        }
+    }
+
+    /// Sets whether the route is safe to retry.
+    ///
+    /// # Arguments
+    /// - `idempotent`: Whether the route is safe to retry.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    #[must_use]
+    pub const fn set_idempotent(mut self, idempotent: bool) -> Self {
+        self.idempotent = idempotent;
+        self
     }
 
     /// Compiles the properties of this static route into a new object.
@@ -77,14 +155,20 @@ impl Route {
 /// A dynamic route that can be used directly for an outgoing request.
 #[derive(Debug, Clone)]
 pub(crate) struct CompiledRoute {
-    /// The routes uri.
-    pub uri: String,
+    /// The routes uri, only allocated once a param is inserted into it.
+    pub uri: Cow<'static, str>,
 
     /// The http method for the route.
     pub method: Method,
 
     /// The query params for the route.
     pub params: Vec<(String, String)>,
+
+    /// The extra headers to send with this request only.
+    pub headers: Vec<(String, String)>,
+
+    /// Whether the route is safe to retry.
+    pub idempotent: bool,
 }
 
 impl CompiledRoute {
@@ -99,14 +183,43 @@ impl CompiledRoute {
     #[rustfmt::skip]
     pub fn new(route: &Route) -> Self {
         let params = Vec::new();
-        let uri = route.uri.to_string();
+        let headers = Vec::new();
+        let uri = Cow::Borrowed(route.uri);
+        // Cloning the standard methods is a copy, no allocation occurs
         let method = route.method.clone();
+        let idempotent = route.idempotent;
+
+        Self { uri, method, params, headers, idempotent }
+    }
 
-        Self { uri, method, params }
+    /// Creates a new compiled route for an endpoint the library doesn't
+    /// have a static route for.
+    ///
+    /// # Arguments
+    /// - `method`: The http [`Method`] for the route.
+    /// - `path`: The routes uri, a leading slash is added if missing.
+    ///
+    /// # Returns
+    /// The new route, which is only retried if the method is idempotent.
+    #[must_use]
+    #[rustfmt::skip]
+    pub fn custom(method: Method, path: &str) -> Self {
+        let params = Vec::new();
+        let headers = Vec::new();
+        let uri = if path.starts_with('/') {
+            Cow::Owned(path.to_string())
+        } else {
+            Cow::Owned(format!("/{path}"))
+        };
+        let idempotent = method.is_idempotent();
+
+        Self { uri, method, params, headers, idempotent }
     }
 
     /// Inserts the given param into the route uri.
     ///
+    /// The param is percent-encoded, so it always forms a single segment.
+    ///
     /// # Arguments
     /// - `param`: The param to insert.
     ///
@@ -114,7 +227,9 @@ impl CompiledRoute {
     /// Self for chained calls.
     #[allow(dead_code)] // Was used in the past, any may again in the future
     pub fn uri_insert<T: Into<String>>(&mut self, param: T) -> &mut Self {
-        self.uri = self.uri.replacen("{}", &param.into(), 1);
+        let param = param.into();
+        let encoded = percent_encoding::utf8_percent_encode(&param, UNRESERVED).to_string();
+        self.uri = Cow::Owned(self.uri.replacen("{}", &encoded, 1));
         self
     }
 
@@ -131,8 +246,24 @@ impl CompiledRoute {
         self
     }
 
+    /// Inserts a header with the given name and value, sent only with the
+    /// request for this route.
+    ///
+    /// # Arguments
+    /// - `name`: The header name to insert.
+    /// - `value`: The header value to insert.
+    ///
+    /// # Returns
+    /// Self for chained calls.
+    pub fn header_insert<T: Into<String>>(&mut self, name: T, value: T) -> &mut Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
     /// Builds the query string for this route, i.e. `?a=b&c=d`.
     ///
+    /// Both the names and values are percent-encoded.
+    ///
     /// # Returns
     /// The formatted query string.
     #[must_use]
@@ -140,7 +271,11 @@ impl CompiledRoute {
         let mut query = self
             .params
             .iter()
-            .map(|(k, v)| format!("{k}={v}"))
+            .map(|(k, v)| {
+                let k = percent_encoding::utf8_percent_encode(k, UNRESERVED);
+                let v = percent_encoding::utf8_percent_encode(v, UNRESERVED);
+                format!("{k}={v}")
+            })
             .collect::<Vec<String>>()
             .join("&");
 
@@ -154,6 +289,9 @@ impl CompiledRoute {
 
 #[cfg(test)]
 mod test {
+    use std::borrow::Cow;
+    use std::time::Instant;
+
     use crate::routes::CompiledRoute;
     use crate::routes::Route;
     use reqwest::Method;
@@ -164,6 +302,15 @@ mod test {
 
         assert_eq!(r.method, Method::GET);
         assert_eq!(r.uri, "/keys/owo");
+        assert!(r.idempotent);
+    }
+
+    #[test]
+    fn route_set_idempotent() {
+        let r = Route::new(Method::POST, "/keys/owo").set_idempotent(false);
+
+        assert!(!r.idempotent);
+        assert!(!r.compile().idempotent);
     }
 
     #[test]
@@ -175,6 +322,53 @@ mod test {
         assert_eq!(r.uri, String::from("/apis/woot"));
     }
 
+    #[test]
+    fn compiled_route_new_borrows_uri() {
+        let r = Route::new(Method::GET, "/apis/hi");
+        let mut c = CompiledRoute::new(&r);
+
+        assert!(matches!(c.uri, Cow::Borrowed("/apis/hi")));
+
+        c.query_insert("apiId", "api_123");
+
+        assert!(matches!(c.uri, Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn compiled_route_custom() {
+        let c = CompiledRoute::custom(Method::POST, "keys.whoami");
+
+        assert_eq!(c.uri, "/keys.whoami");
+        assert_eq!(c.method, Method::POST);
+        assert!(!c.idempotent);
+
+        let c = CompiledRoute::custom(Method::GET, "/identities.getIdentity");
+
+        assert_eq!(c.uri, "/identities.getIdentity");
+        assert!(c.idempotent);
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore = "micro benchmark"]
+    fn bench_compiled_route_new() {
+        let r = Route::new(Method::GET, "/apis.listKeys");
+        let iterations: u32 = 1_000_000;
+        let mut total = 0;
+        let start = Instant::now();
+
+        // Summing the lengths stops the loop from being optimized away
+        for _ in 0..iterations {
+            total += CompiledRoute::new(&r).uri.len();
+        }
+
+        let elapsed = start.elapsed();
+        println!(
+            "CompiledRoute::new: {:?}/iter ({total})",
+            elapsed / iterations
+        );
+    }
+
     #[test]
     fn compiled_route_new() {
         let r = Route::new(Method::GET, "/apis/hi");
@@ -196,6 +390,15 @@ mod test {
         assert_eq!(c.uri, String::from("/apis/5/keys/1"));
     }
 
+    #[test]
+    fn compiled_route_uri_insert_encodes() {
+        let r = Route::new(Method::POST, "/keys/{}");
+        let mut c = CompiledRoute::new(&r);
+        c.uri_insert("key/1 ?#%");
+
+        assert_eq!(c.uri, String::from("/keys/key%2F1%20%3F%23%25"));
+    }
+
     #[test]
     fn compiled_route_query_insert() {
         let r = Route::new(Method::GET, "/apis/milk");
@@ -210,6 +413,19 @@ mod test {
         );
     }
 
+    #[test]
+    fn compiled_route_header_insert() {
+        let r = Route::new(Method::POST, "/keys.createKey");
+        let mut c = CompiledRoute::new(&r);
+        c.header_insert("Idempotency-Key", "abc");
+
+        assert!(c.params.is_empty());
+        assert_eq!(
+            c.headers,
+            vec![(String::from("Idempotency-Key"), String::from("abc"))]
+        );
+    }
+
     #[test]
     fn compiled_route_build_query() {
         let r = Route::new(Method::GET, "/apis/milk");
@@ -218,4 +434,17 @@ mod test {
 
         assert_eq!(c.build_query(), String::from("?test=value&js=bad"));
     }
+
+    #[test]
+    fn compiled_route_build_query_encodes() {
+        let r = Route::new(Method::GET, "/apis.listKeys");
+        let mut c = CompiledRoute::new(&r);
+        c.query_insert("ownerId", "a b&c=d")
+            .query_insert("a key", "~ok_-.");
+
+        assert_eq!(
+            c.build_query(),
+            String::from("?ownerId=a%20b%26c%3Dd&a%20key=~ok_-.")
+        );
+    }
 }
@@ -1,16 +1,67 @@
+use std::future::Future;
+
+use futures_util::stream;
+use futures_util::Stream;
+use futures_util::StreamExt;
+
 use crate::fetch;
+use crate::models::ApiKey;
 use crate::models::DeleteApiRequest;
+use crate::models::Error;
 use crate::models::GetApiRequest;
 use crate::models::GetApiResponse;
+use crate::models::ListApisRequest;
+use crate::models::ListApisResponse;
 use crate::models::ListKeysRequest;
 use crate::models::ListKeysResponse;
+use crate::models::UpdateApiRequest;
+use crate::models::LIST_KEYS_MAX_LIMIT;
 use crate::parse_empty_response;
 use crate::parse_response;
 use crate::routes;
-use crate::services::HttpService;
+use crate::services::HttpTransport;
+
+/// Walks a cursor paginated endpoint, yielding each item from every page.
+///
+/// The stream ends after the last page, i.e. when no cursor or no items are
+/// returned, or after yielding the first error encountered.
+///
+/// # Arguments
+/// - `cursor`: The cursor to fetch the first page with.
+/// - `fetch_page`: Fetches the page for a cursor, returning its items and
+///   the cursor for the next page.
+///
+/// # Returns
+/// The stream of items.
+fn paginate<'a, T, F, Fut>(
+    cursor: Option<String>,
+    mut fetch_page: F,
+) -> impl Stream<Item = Result<T, Error>> + 'a
+where
+    T: 'a,
+    F: FnMut(Option<String>) -> Fut + 'a,
+    Fut: Future<Output = Result<(Vec<T>, Option<String>), Error>> + 'a,
+{
+    stream::unfold(Some(cursor), move |next| {
+        let page = next.map(&mut fetch_page);
 
-#[allow(unused_imports)]
-use crate::models::HttpError;
+        async move {
+            match page?.await {
+                Err(e) => Some((vec![Err(e)], None)),
+                Ok((items, cursor)) => {
+                    // An empty page would otherwise loop forever on a stale cursor
+                    let next = if items.is_empty() {
+                        None
+                    } else {
+                        cursor.map(Some)
+                    };
+                    Some((items.into_iter().map(Ok).collect(), next))
+                }
+            }
+        }
+    })
+    .flat_map(stream::iter)
+}
 
 /// The service that handles api related requests.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -27,16 +78,20 @@ impl ApiService {
     /// A [`Result`] containing the response, or an error.
     ///
     /// # Errors
-    /// The [`HttpError`], if one occurred.
-    pub async fn list_keys(
+    /// The [`Error`], if one occurred.
+    pub async fn list_keys<H: HttpTransport>(
         &self,
-        http: &HttpService,
+        http: &H,
         req: ListKeysRequest,
-    ) -> Result<ListKeysResponse, HttpError> {
+    ) -> Result<ListKeysResponse, Error> {
+        let limit = req
+            .limit
+            .map_or(LIST_KEYS_MAX_LIMIT, |l| l.clamp(1, LIST_KEYS_MAX_LIMIT));
+
         let mut route = routes::LIST_KEYS.compile();
         route
             .query_insert("apiId", &req.api_id)
-            .query_insert("limit", &req.limit.unwrap_or(100).to_string());
+            .query_insert("limit", &limit.to_string());
 
         if let Some(revalidate) = &req.revalidate_cache {
             route.query_insert("revalidateKeysCache", &revalidate.to_string());
@@ -53,6 +108,33 @@ impl ApiService {
         parse_response(fetch!(http, route).await).await
     }
 
+    /// Retrieves every key for an api, fetching further pages as the stream
+    /// is consumed.
+    ///
+    /// # Arguments
+    /// - `http`: The http service to use for the requests.
+    /// - `req`: The request to send for the first page.
+    ///
+    /// # Returns
+    /// The stream of keys, or the error that ended it.
+    pub fn list_all_keys<'a, H: HttpTransport>(
+        &'a self,
+        http: &'a H,
+        req: ListKeysRequest,
+    ) -> impl Stream<Item = Result<ApiKey, Error>> + 'a {
+        paginate(req.cursor.clone(), move |cursor| {
+            let req = ListKeysRequest {
+                cursor,
+                ..req.clone()
+            };
+
+            async move {
+                let res = self.list_keys(http, req).await?;
+                Ok((res.keys, res.cursor.map(String::from)))
+            }
+        })
+    }
+
     /// Retrieves api information.
     ///
     /// # Arguments
@@ -63,12 +145,12 @@ impl ApiService {
     /// A [`Result`] containing the response, or an error.
     ///
     /// # Errors
-    /// The [`HttpError`], if one occurred.
-    pub async fn get_api(
+    /// The [`Error`], if one occurred.
+    pub async fn get_api<H: HttpTransport>(
         &self,
-        http: &HttpService,
+        http: &H,
         req: GetApiRequest,
-    ) -> Result<GetApiResponse, HttpError> {
+    ) -> Result<GetApiResponse, Error> {
         let mut route = routes::GET_API.compile();
         route.query_insert("apiId", &req.api_id);
 
@@ -85,14 +167,198 @@ impl ApiService {
     /// A [`Result`] containing the response, or an error.
     ///
     /// # Errors
-    /// The [`HttpError`], if one occurred.
-    pub async fn delete_api(
+    /// The [`Error`], if one occurred.
+    pub async fn delete_api<H: HttpTransport>(
         &self,
-        http: &HttpService,
+        http: &H,
         req: DeleteApiRequest,
-    ) -> Result<(), HttpError> {
+    ) -> Result<(), Error> {
         let route = routes::DELETE_API.compile();
 
         parse_empty_response(fetch!(http, route, req).await).await
     }
+
+    /// Updates an existing api.
+    ///
+    /// # Arguments
+    /// - `http`: The http service to use for the request.
+    /// - `req`: The request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    pub async fn update_api<H: HttpTransport>(
+        &self,
+        http: &H,
+        req: UpdateApiRequest,
+    ) -> Result<(), Error> {
+        let route = routes::UPDATE_API.compile();
+
+        parse_empty_response(fetch!(http, route, req).await).await
+    }
+
+    /// Retrieves a paginated list of apis in the workspace.
+    ///
+    /// # Arguments
+    /// - `http`: The http service to use for the request.
+    /// - `req`: The request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    pub async fn list_apis<H: HttpTransport>(
+        &self,
+        http: &H,
+        req: ListApisRequest,
+    ) -> Result<ListApisResponse, Error> {
+        let mut route = routes::LIST_APIS.compile();
+        route.query_insert("limit", &req.limit.unwrap_or(100).to_string());
+
+        if let Some(cursor) = &req.cursor {
+            route.query_insert("cursor", cursor);
+        }
+
+        parse_response(fetch!(http, route).await).await
+    }
+
+    /// Retrieves every api in the workspace, fetching further pages as the
+    /// stream is consumed.
+    ///
+    /// # Arguments
+    /// - `http`: The http service to use for the requests.
+    /// - `req`: The request to send for the first page.
+    ///
+    /// # Returns
+    /// The stream of apis, or the error that ended it.
+    pub fn list_all_apis<'a, H: HttpTransport>(
+        &'a self,
+        http: &'a H,
+        req: ListApisRequest,
+    ) -> impl Stream<Item = Result<GetApiResponse, Error>> + 'a {
+        paginate(req.cursor.clone(), move |cursor| {
+            let req = ListApisRequest {
+                cursor,
+                ..req.clone()
+            };
+
+            async move {
+                let res = self.list_apis(http, req).await?;
+                Ok((res.apis, res.cursor))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_util::StreamExt;
+
+    use crate::mock::block_on;
+    #[cfg(feature = "compression")]
+    use crate::mock::header;
+    use crate::mock::MockServer;
+    use crate::models::ErrorCode;
+    use crate::models::ListApisRequest;
+    use crate::models::ListKeysRequest;
+    use crate::services::ApiService;
+    use crate::services::HttpService;
+
+    #[test]
+    fn list_apis() {
+        let server = MockServer::new(vec![(
+            200,
+            r#"{"apis":[{"id":"api_1","name":"one","workspaceId":"ws_1"},{"id":"api_2","name":"two","workspaceId":"ws_1"}],"cursor":"api_2"}"#,
+        )]);
+        let http = HttpService::with_url("", &server.url);
+        let req = ListApisRequest::new().set_limit(2).set_cursor("api_0");
+
+        let res = block_on(ApiService.list_apis(&http, req)).unwrap();
+        let request = &server.requests()[0];
+
+        assert!(request.starts_with("GET /apis.listApis?limit=2&cursor=api_0 "));
+        assert_eq!(res.apis.len(), 2);
+        assert_eq!(res.apis[0].api_id, "api_1");
+        assert_eq!(res.apis[1].name, "two");
+        assert_eq!(res.cursor.as_deref(), Some("api_2"));
+    }
+
+    #[test]
+    fn list_all_apis_walks_cursors() {
+        let server = MockServer::new(vec![
+            (
+                200,
+                r#"{"apis":[{"id":"api_1","name":"one","workspaceId":"ws_1"},{"id":"api_2","name":"two","workspaceId":"ws_1"}],"cursor":"api_2"}"#,
+            ),
+            (
+                200,
+                r#"{"apis":[{"id":"api_3","name":"three","workspaceId":"ws_1"}],"cursor":null}"#,
+            ),
+        ]);
+        let http = HttpService::with_url("", &server.url);
+        let req = ListApisRequest::new().set_limit(2);
+
+        let res: Vec<_> = block_on(ApiService.list_all_apis(&http, req).collect());
+        let requests = server.requests();
+        let ids: Vec<_> = res
+            .iter()
+            .map(|r| r.as_ref().unwrap().api_id.as_str())
+            .collect();
+
+        assert_eq!(ids, vec!["api_1", "api_2", "api_3"]);
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].starts_with("GET /apis.listApis?limit=2 "));
+        assert!(requests[1].starts_with("GET /apis.listApis?limit=2&cursor=api_2 "));
+    }
+
+    #[test]
+    fn list_all_keys_stops_on_error() {
+        let server = MockServer::new(vec![
+            (
+                200,
+                r#"{"keys":[{"id":"key_1","apiId":"api_123","workspaceId":"ws_123","start":"t_","createdAt":0}],"total":2,"cursor":"key_1"}"#,
+            ),
+            (
+                500,
+                r#"{"error":{"code":"INTERNAL_SERVER_ERROR","message":"oops"}}"#,
+            ),
+        ]);
+        let http = HttpService::with_url("", &server.url);
+
+        let res: Vec<_> = block_on(
+            ApiService
+                .list_all_keys(&http, ListKeysRequest::new("api_123"))
+                .collect(),
+        );
+
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[0].as_ref().unwrap().id, "key_1");
+        assert_eq!(
+            res[1].as_ref().unwrap_err().code(),
+            ErrorCode::InternalServerError
+        );
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "compression")]
+    fn list_keys_gzip() {
+        let server = MockServer::gzip(vec![(
+            200,
+            r#"{"keys":[{"id":"key_123","apiId":"api_123","workspaceId":"ws_123","start":"t_","createdAt":0}],"total":1}"#,
+        )]);
+        let http = HttpService::with_url("", &server.url);
+        let req = ListKeysRequest::new("api_123");
+
+        let res = block_on(ApiService.list_keys(&http, req)).unwrap();
+        let request = &server.requests()[0];
+
+        assert!(header(request, "accept-encoding").unwrap().contains("gzip"));
+        assert_eq!(res.keys.len(), 1);
+        assert_eq!(res.keys[0].id, "key_123");
+        assert_eq!(res.total, 1);
+    }
 }
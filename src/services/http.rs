@@ -1,44 +1,150 @@
-use reqwest::header::{HeaderMap, HeaderValue};
+use std::future::Future;
+use std::pin::Pin;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::PoisonError;
+use std::sync::RwLock;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, IntoHeaderName, AUTHORIZATION};
+use reqwest::RequestBuilder;
 use serde::Serialize;
 
+// The wasm32 specific code paths below can't be tested natively, check them
+// with `cargo check --target wasm32-unknown-unknown` when changing them.
+
 use crate::logging;
+use crate::models::ApiVersion;
+use crate::models::ErrorCode;
+use crate::models::HttpError;
 use crate::models::HttpResult;
+use crate::models::RatelimitHeaders;
+use crate::models::RetryPolicy;
+use crate::models::DEFAULT_META_SIZE_LIMIT;
 use crate::routes::CompiledRoute;
 
-// TODO: implement versioning at some point
-/// The unkey api production base url.
-static BASE_API_URL: &str = "https://api.unkey.dev/v1";
+/// A boxed future resolving to the result of an http request.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) type FetchFuture<'a> = Pin<Box<dyn Future<Output = HttpResult> + Send + 'a>>;
+
+/// A boxed future resolving to the result of an http request, which can't
+/// be `Send` on wasm as the browser fetch api is single threaded.
+#[cfg(target_arch = "wasm32")]
+pub(crate) type FetchFuture<'a> = Pin<Box<dyn Future<Output = HttpResult> + 'a>>;
+
+/// The environment variable overriding the default base url.
+const BASE_URL_ENV: &str = "UNKEY_BASE_URL";
+
+/// The synthetic response body returned for requests made in dry run mode.
+#[cfg(not(target_arch = "wasm32"))]
+const DRY_RUN_BODY: &str =
+    r#"{"error":{"code":"DRY_RUN","message":"The request was not sent, dry run mode is enabled"}}"#;
+
+/// A hook applied to every outgoing request just before it is sent.
+pub(crate) type RequestHook = Arc<dyn Fn(RequestBuilder) -> RequestBuilder + Send + Sync>;
+
+/// The transport the services use to send requests, allowing the real http
+/// service to be swapped out for a fake one in tests.
+pub(crate) trait HttpTransport {
+    /// Sends the http request.
+    ///
+    /// # Arguments
+    /// - `route`: The compiled route to fetch.
+    /// - `payload`: The optional json payload.
+    ///
+    /// # Returns
+    /// A future resolving to the result of the http request.
+    fn fetch<'a, T>(&'a self, route: CompiledRoute, payload: Option<T>) -> FetchFuture<'a>
+    where
+        T: std::fmt::Debug + Serialize + Send + 'a;
+
+    /// The maximum size of a keys serialized meta, in bytes.
+    ///
+    /// # Returns
+    /// The meta size limit, [`DEFAULT_META_SIZE_LIMIT`] unless overridden.
+    fn meta_size_limit(&self) -> usize {
+        DEFAULT_META_SIZE_LIMIT
+    }
+}
 
 /// The http service used for handling requests.
+///
+/// The url and headers sit behind locks so they can be updated through a
+/// shared reference, i.e. to rotate the root key while requests are in
+/// flight on other tasks.
 #[allow(clippy::module_name_repetitions)]
-#[derive(Debug, Clone)]
 pub(crate) struct HttpService {
     /// The base url to use for requests.
-    url: String,
+    url: RwLock<String>,
 
     /// The request client to use for requests.
     client: reqwest::Client,
 
     /// The request headers to send with each request.
-    headers: HeaderMap,
+    headers: RwLock<HeaderMap>,
+
+    /// The policy for retrying failed requests, if any.
+    retry: Option<RetryPolicy>,
+
+    /// The hook to apply to each request before it is sent, if any.
+    request_hook: RwLock<Option<RequestHook>>,
+
+    /// The ratelimit headers from the most recent response, if any.
+    last_ratelimit: RwLock<Option<RatelimitHeaders>>,
+
+    /// The maximum size of a keys serialized meta, in bytes.
+    meta_size_limit: AtomicUsize,
+
+    /// Whether requests are logged instead of sent.
+    #[cfg(not(target_arch = "wasm32"))]
+    dry_run: AtomicBool,
 }
 
 impl HttpService {
-    /// Creates a new http service.
+    /// Creates a new http service using the default base url.
     ///
     /// # Arguments
     /// - `key`: The root api key to use.
     ///
     /// # Returns
     /// The new http service.
+    ///
+    /// # Panics
+    /// If the key is not a valid header value, see [`HttpService::try_new`].
     #[must_use]
-    #[rustfmt::skip]
     pub fn new(key: &str) -> Self {
-        let headers = Self::generate_headers(key);
-        let client = reqwest::Client::new();
-        let url = BASE_API_URL.to_string();
+        Self::try_new(key).expect("the root api key must be a valid header value")
+    }
 
-        Self { url, client, headers }
+    /// Creates a new http service using the default base url, failing if
+    /// the key is not a valid header value.
+    ///
+    /// # Arguments
+    /// - `key`: The root api key to use.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the new http service, or an error.
+    ///
+    /// # Errors
+    /// An [`HttpError`] with [`ErrorCode::BadRequest`] if the key contains
+    /// characters not allowed in a header, i.e. a newline.
+    pub fn try_new(key: &str) -> Result<Self, HttpError> {
+        Self::try_with_client(key, &Self::default_url(), reqwest::Client::new())
+    }
+
+    /// Creates a new http service pinned to the given api version.
+    ///
+    /// # Arguments
+    /// - `key`: The root api key to use.
+    /// - `version`: The api version to use.
+    ///
+    /// # Returns
+    /// The new http service.
+    #[must_use]
+    pub fn with_version(key: &str, version: ApiVersion) -> Self {
+        Self::with_url(key, &version.base_url())
     }
 
     /// Creates a new http service that does not use the production
@@ -50,14 +156,83 @@ impl HttpService {
     ///
     /// # Returns
     /// The new http service.
+    ///
+    /// # Panics
+    /// If the key is not a valid header value.
     #[must_use]
-    #[rustfmt::skip]
     pub fn with_url(key: &str, url: &str) -> Self {
-        let headers = Self::generate_headers(key);
-        let client = reqwest::Client::new();
-        let url = url.to_string();
+        Self::with_client(key, url, reqwest::Client::new())
+    }
+
+    /// Creates a new http service using a preconfigured request client.
+    ///
+    /// # Arguments
+    /// - `key`: The root api key to use.
+    /// - `url`: The base url to use.
+    /// - `client`: The request client to use.
+    ///
+    /// # Returns
+    /// The new http service.
+    ///
+    /// # Panics
+    /// If the key is not a valid header value.
+    #[must_use]
+    pub fn with_client(key: &str, url: &str, client: reqwest::Client) -> Self {
+        Self::try_with_client(key, url, client)
+            .expect("the root api key must be a valid header value")
+    }
 
-        Self { url, client, headers }
+    /// Creates a new http service using a preconfigured request client,
+    /// failing if the key is not a valid header value.
+    ///
+    /// # Arguments
+    /// - `key`: The root api key to use.
+    /// - `url`: The base url to use.
+    /// - `client`: The request client to use.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the new http service, or an error.
+    ///
+    /// # Errors
+    /// An [`HttpError`] with [`ErrorCode::BadRequest`] if the key contains
+    /// characters not allowed in a header.
+    #[rustfmt::skip]
+    pub fn try_with_client(
+        key: &str,
+        url: &str,
+        client: reqwest::Client,
+    ) -> Result<Self, HttpError> {
+        let headers = RwLock::new(Self::generate_headers(key)?);
+        let url = RwLock::new(Self::normalize_url(url));
+        let retry = None;
+        let request_hook = RwLock::new(None);
+        let last_ratelimit = RwLock::new(None);
+        let meta_size_limit = AtomicUsize::new(DEFAULT_META_SIZE_LIMIT);
+
+        Ok(Self {
+            url,
+            client,
+            headers,
+            retry,
+            request_hook,
+            last_ratelimit,
+            meta_size_limit,
+            #[cfg(not(target_arch = "wasm32"))]
+            dry_run: AtomicBool::new(false),
+        })
+    }
+
+    /// The base url to use when none is given, read from the
+    /// `UNKEY_BASE_URL` environment variable if it is set, otherwise the
+    /// production url for the default api version.
+    ///
+    /// # Returns
+    /// The default base url.
+    pub fn default_url() -> String {
+        match std::env::var(BASE_URL_ENV) {
+            Ok(url) if !url.is_empty() => Self::normalize_url(&url),
+            _ => ApiVersion::default().base_url(),
+        }
     }
 
     /// Generates the headers to send with requests.
@@ -66,55 +241,287 @@ impl HttpService {
     /// - `key`: The root api key to use.
     ///
     /// # Returns
-    /// The header map to use.
-    fn generate_headers(key: &str) -> HeaderMap {
-        let mut headers = HeaderMap::with_capacity(3);
+    /// A [`Result`] containing the header map to use, or an error.
+    ///
+    /// # Errors
+    /// An [`HttpError`] with [`ErrorCode::BadRequest`] if any header value
+    /// is invalid.
+    fn generate_headers(key: &str) -> Result<HeaderMap, HttpError> {
+        let mut headers = HeaderMap::with_capacity(5);
         let key = format!("Bearer {key}");
-        let version = env!("CARGO_PKG_VERSION");
-        let user_agent = format!("unkey.rs@v{version}");
+        let user_agent = Self::default_user_agent();
 
-        let buffer: [(&'static str, Result<HeaderValue, _>); 5] = [
-            ("Accept", HeaderValue::from_str("application/json")),
-            ("x-user-agent", HeaderValue::from_str(&user_agent)),
-            ("User-Agent", HeaderValue::from_str(&user_agent)),
-            ("Unkey-SDK", HeaderValue::from_str(&user_agent)),
-            ("Authorization", HeaderValue::from_str(&key)),
+        let buffer: [(&'static str, &str); 5] = [
+            ("Accept", "application/json"),
+            ("x-user-agent", &user_agent),
+            ("User-Agent", &user_agent),
+            ("Unkey-SDK", &user_agent),
+            ("Authorization", &key),
         ];
 
-        for (k, v) in &buffer {
-            match v {
-                Ok(h) => headers.insert(*k, h.clone()),
-                Err(e) => {
-                    eprintln!("Invalid header value: {e:?}");
-                    std::process::exit(1);
-                }
-            };
+        for (k, v) in buffer {
+            let value = HeaderValue::from_str(v).map_err(|e| {
+                HttpError::new(
+                    ErrorCode::BadRequest,
+                    format!("Invalid value for header {k}: {e}"),
+                )
+            })?;
+
+            headers.insert(k, value);
         }
 
-        headers
+        Ok(headers)
+    }
+
+    /// The user agent identifying this sdk and its version.
+    ///
+    /// # Returns
+    /// The default user agent.
+    fn default_user_agent() -> String {
+        let version = env!("CARGO_PKG_VERSION");
+        format!("unkey.rs@v{version}")
+    }
+
+    /// A snapshot of the headers to send with requests.
+    ///
+    /// # Returns
+    /// A copy of the current headers.
+    fn headers(&self) -> HeaderMap {
+        self.headers
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Inserts the given header, replacing any existing value.
+    ///
+    /// # Arguments
+    /// - `name`: The header name.
+    /// - `value`: The header value.
+    fn insert_header<K: IntoHeaderName>(&self, name: K, value: HeaderValue) {
+        self.headers
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(name, value);
     }
 
     /// Updates the root api key to send with requests.
     ///
     /// # Arguments
     /// - `key`: The new root api key to use.
-    pub fn set_key(&mut self, key: &str) {
-        let header = HeaderValue::from_str(key);
+    pub fn set_key(&self, key: &str) {
+        let header = HeaderValue::from_str(&format!("Bearer {key}"));
 
         match header {
             Err(e) => eprintln!("Error setting header value: {e:?}"),
+            Ok(h) => self.insert_header("Authorization", h),
+        }
+    }
+
+    /// Creates a copy of this http service that sends a different root api
+    /// key, leaving this service untouched.
+    ///
+    /// # Arguments
+    /// - `key`: The root api key the copy should use.
+    ///
+    /// # Returns
+    /// The new http service.
+    #[must_use]
+    pub fn with_key(&self, key: &str) -> Self {
+        let http = self.clone();
+        http.set_key(key);
+        http
+    }
+
+    /// Sets the user agent to send with requests, replacing the default.
+    ///
+    /// The `Unkey-SDK` header always identifies this sdk, regardless of the
+    /// user agent in use.
+    ///
+    /// # Arguments
+    /// - `user_agent`: The new user agent to use, or an empty string to
+    ///   restore the default.
+    pub fn set_user_agent(&self, user_agent: &str) {
+        let user_agent = if user_agent.is_empty() {
+            Self::default_user_agent()
+        } else {
+            user_agent.to_string()
+        };
+
+        match HeaderValue::from_str(&user_agent) {
+            Err(e) => logging::error!(format!("Invalid user agent {user_agent:?}: {e:?}")),
             Ok(h) => {
-                self.headers.insert("Authorization", h);
+                self.insert_header("x-user-agent", h.clone());
+                self.insert_header("User-Agent", h);
             }
         }
     }
 
-    /// Sets the base url to use for the api.
+    /// Sets a custom header to send with every request, replacing any
+    /// existing value, i.e. to override the `Accept` header.
+    ///
+    /// The `Authorization` header is reserved for the root api key and can
+    /// only be changed with [`HttpService::set_key`]. Attempts to set it, or
+    /// to set an invalid header, are logged and ignored.
+    ///
+    /// # Arguments
+    /// - `name`: The header name.
+    /// - `value`: The header value.
+    pub fn set_header(&self, name: &str, value: &str) {
+        let name = match HeaderName::from_bytes(name.as_bytes()) {
+            Ok(n) => n,
+            Err(e) => {
+                logging::error!(format!("Invalid header name {name:?}: {e:?}"));
+                return;
+            }
+        };
+
+        if name == AUTHORIZATION {
+            logging::error!(format!("Refusing to set reserved header {name:?}"));
+            return;
+        }
+
+        match HeaderValue::from_str(value) {
+            Err(e) => logging::error!(format!("Invalid header value for {name:?}: {e:?}")),
+            Ok(h) => self.insert_header(name, h),
+        }
+    }
+
+    /// The base url used for requests.
+    ///
+    /// # Returns
+    /// The base url.
+    pub fn url(&self) -> String {
+        self.url
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Sets the base url to use for the api, stripping any trailing slash.
     ///
     /// # Arguments
     /// - `url`: The new api base url to use.
-    pub fn set_url(&mut self, url: &str) {
-        self.url = url.to_string();
+    pub fn set_url(&self, url: &str) {
+        *self.url.write().unwrap_or_else(PoisonError::into_inner) = Self::normalize_url(url);
+    }
+
+    /// Sets the base url to use for the api, failing if it is invalid.
+    ///
+    /// # Arguments
+    /// - `url`: The new api base url to use.
+    ///
+    /// # Returns
+    /// A [`Result`] indicating whether the url was set.
+    ///
+    /// # Errors
+    /// An [`HttpError`] with [`ErrorCode::BadRequest`] if the url can't be
+    /// parsed, or its scheme is not `http` or `https`.
+    pub fn try_set_url(&self, url: &str) -> Result<(), HttpError> {
+        let parsed = reqwest::Url::parse(url).map_err(|e| {
+            HttpError::new(ErrorCode::BadRequest, format!("Invalid url {url:?}: {e}"))
+        })?;
+
+        if !matches!(parsed.scheme(), "http" | "https") {
+            let message = format!("Invalid url {url:?}: the scheme must be http or https");
+            return Err(HttpError::new(ErrorCode::BadRequest, message));
+        }
+
+        self.set_url(url);
+        Ok(())
+    }
+
+    /// Strips any trailing slashes from the url, so joining it with a route
+    /// doesn't produce a double slash.
+    ///
+    /// # Arguments
+    /// - `url`: The url to normalize.
+    ///
+    /// # Returns
+    /// The normalized url.
+    fn normalize_url(url: &str) -> String {
+        url.trim_end_matches('/').to_string()
+    }
+
+    /// Sets the policy for retrying failed requests.
+    ///
+    /// # Arguments
+    /// - `retry`: The new retry policy to use, or `None` to disable retries.
+    pub fn set_retry_policy(&mut self, retry: Option<RetryPolicy>) {
+        self.retry = retry;
+    }
+
+    /// Sets the hook to apply to each request just before it is sent.
+    ///
+    /// # Arguments
+    /// - `hook`: The new request hook to use, or `None` to remove it.
+    pub fn set_request_hook(&self, hook: Option<RequestHook>) {
+        *self
+            .request_hook
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = hook;
+    }
+
+    /// The hook applied to each request before it is sent.
+    ///
+    /// # Returns
+    /// The request hook, if one is set.
+    fn request_hook(&self) -> Option<RequestHook> {
+        self.request_hook
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Sets whether requests are logged instead of sent.
+    ///
+    /// # Arguments
+    /// - `enabled`: Whether dry run mode is enabled.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_dry_run(&self, enabled: bool) {
+        self.dry_run.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Sets the maximum size of a keys serialized meta.
+    ///
+    /// # Arguments
+    /// - `limit`: The new limit, in bytes.
+    pub fn set_meta_size_limit(&self, limit: usize) {
+        self.meta_size_limit.store(limit, Ordering::Relaxed);
+    }
+
+    /// The maximum size of a keys serialized meta.
+    ///
+    /// # Returns
+    /// The meta size limit, in bytes.
+    pub fn meta_size_limit(&self) -> usize {
+        self.meta_size_limit.load(Ordering::Relaxed)
+    }
+
+    /// The ratelimit headers from the most recent response that had them.
+    ///
+    /// # Returns
+    /// The ratelimit headers, if any response has included them.
+    pub fn last_ratelimit(&self) -> Option<RatelimitHeaders> {
+        *self
+            .last_ratelimit
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Whether the given result warrants another attempt.
+    ///
+    /// # Arguments
+    /// - `result`: The result of the previous attempt.
+    ///
+    /// # Returns
+    /// True if the request should be retried.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn is_retryable(result: &HttpResult) -> bool {
+        match result {
+            Ok(r) => r.status().is_server_error() || r.status().as_u16() == 429,
+            Err(e) => e.is_connect() || e.is_timeout(),
+        }
     }
 
     /// Sends the http request.
@@ -133,20 +540,433 @@ impl HttpService {
         T: std::fmt::Debug + Serialize,
     {
         let query = route.build_query();
-        let endpoint = route.uri.clone() + &query;
+        let endpoint = format!("{}{query}", route.uri);
         logging::info!(format!("OUTGOING: {} {endpoint}", &route.method));
 
-        let url = self.url.clone() + &endpoint;
+        let url = self.url() + &endpoint;
         let mut req = self
             .client
             .request(route.method, url)
-            .headers(self.headers.clone());
+            .headers(self.headers());
+
+        for (name, value) in &route.headers {
+            req = req.header(name, value);
+        }
 
         if let Some(p) = payload {
             logging::debug!(format!("PAYLOAD : {p:?}"));
             req = req.json(&p);
         }
 
+        if let Some(hook) = self.request_hook() {
+            req = hook(req);
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.dry_run.load(Ordering::Relaxed) {
+            return Self::dry_run_response(req);
+        }
+
+        let result = self.send(req, route.idempotent).await;
+
+        if let Some(ratelimit) = result
+            .as_ref()
+            .ok()
+            .and_then(|r| RatelimitHeaders::from_headers(r.headers()))
+        {
+            *self
+                .last_ratelimit
+                .write()
+                .unwrap_or_else(PoisonError::into_inner) = Some(ratelimit);
+        }
+
+        result
+    }
+
+    /// Logs the request that would have been sent, without sending it. The
+    /// body is truncated and any plaintext keys in it are redacted.
+    ///
+    /// # Arguments
+    /// - `req`: The request to log.
+    ///
+    /// # Returns
+    /// A synthetic response containing an [`ErrorCode::DryRun`] error.
+    ///
+    /// # Errors
+    /// The reqwest error if the request could not be built.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn dry_run_response(req: RequestBuilder) -> HttpResult {
+        let req = req.build()?;
+        let body = req
+            .body()
+            .and_then(reqwest::Body::as_bytes)
+            .map(String::from_utf8_lossy)
+            .unwrap_or_default();
+
+        // The body may contain plaintext keys, so only a redacted snippet is logged
+        let body = crate::body_snippet(&body);
+        logging::info!(format!("DRY RUN : {} {} {body}", req.method(), req.url()));
+
+        Ok(http::Response::new(DRY_RUN_BODY).into())
+    }
+
+    /// Sends the built request, retrying it according to the retry policy.
+    ///
+    /// # Arguments
+    /// - `req`: The request to send.
+    /// - `idempotent`: Whether the request is safe to retry.
+    ///
+    /// # Returns
+    /// The result of the final attempt.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn send(&self, req: RequestBuilder, idempotent: bool) -> HttpResult {
+        let policy = match &self.retry {
+            Some(p) if idempotent => p,
+            _ => return req.send().await,
+        };
+
+        let mut attempt = 0;
+        loop {
+            let result = match req.try_clone() {
+                Some(r) => r.send().await,
+                None => return req.send().await,
+            };
+
+            if attempt >= policy.max_retries || !Self::is_retryable(&result) {
+                return result;
+            }
+
+            let delay = policy.delay_for(attempt);
+            attempt += 1;
+            logging::info!(format!("RETRYING: attempt {attempt} in {delay:?}"));
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Sends the built request.
+    ///
+    /// ## Note
+    /// There is no timer to back off with on wasm, so the retry policy is
+    /// ignored and requests are never retried.
+    ///
+    /// # Arguments
+    /// - `req`: The request to send.
+    ///
+    /// # Returns
+    /// The result of the request.
+    #[cfg(target_arch = "wasm32")]
+    async fn send(&self, req: RequestBuilder, _idempotent: bool) -> HttpResult {
         req.send().await
     }
 }
+
+impl std::fmt::Debug for HttpService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("HttpService");
+        debug
+            .field("url", &self.url)
+            .field("client", &self.client)
+            .field("headers", &self.headers)
+            .field("retry", &self.retry)
+            .field("request_hook", &self.request_hook().map(|_| "Fn"))
+            .field("last_ratelimit", &self.last_ratelimit)
+            .field("meta_size_limit", &self.meta_size_limit);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        debug.field("dry_run", &self.dry_run);
+
+        debug.finish()
+    }
+}
+
+impl Clone for HttpService {
+    fn clone(&self) -> Self {
+        // Clones are independent, updating one never affects the other
+        Self {
+            url: RwLock::new(self.url()),
+            client: self.client.clone(),
+            headers: RwLock::new(self.headers()),
+            retry: self.retry.clone(),
+            request_hook: RwLock::new(self.request_hook()),
+            last_ratelimit: RwLock::new(self.last_ratelimit()),
+            meta_size_limit: AtomicUsize::new(self.meta_size_limit()),
+            #[cfg(not(target_arch = "wasm32"))]
+            dry_run: AtomicBool::new(self.dry_run.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl HttpTransport for HttpService {
+    fn fetch<'a, T>(&'a self, route: CompiledRoute, payload: Option<T>) -> FetchFuture<'a>
+    where
+        T: std::fmt::Debug + Serialize + Send + 'a,
+    {
+        // Resolves to the inherent method, which does the actual work
+        Box::pin(HttpService::fetch(self, route, payload))
+    }
+
+    fn meta_size_limit(&self) -> usize {
+        HttpService::meta_size_limit(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use reqwest::Method;
+
+    use crate::mock::block_on;
+    use crate::mock::header;
+    use crate::mock::MockServer;
+    use crate::models::ErrorCode;
+    use crate::models::RetryPolicy;
+    use crate::routes::Route;
+    use crate::services::HttpService;
+
+    #[test]
+    fn fetch_applies_request_hook() {
+        let server = MockServer::new(vec![(500, "{}"), (200, "{}")]);
+        let mut http = HttpService::with_url("", &server.url);
+        http.set_retry_policy(Some(policy()));
+        http.set_request_hook(Some(Arc::new(|req| req.header("traceparent", "00-abc-01"))));
+
+        let route = Route::new(Method::GET, "/test").compile();
+        block_on(http.fetch(route, None::<u8>)).unwrap();
+        let requests = server.requests();
+
+        assert_eq!(requests.len(), 2);
+        assert_eq!(header(&requests[0], "traceparent").unwrap(), "00-abc-01");
+        assert_eq!(header(&requests[1], "traceparent").unwrap(), "00-abc-01");
+    }
+
+    #[test]
+    fn fetch_records_ratelimit_headers() {
+        let server = MockServer::with_headers(
+            vec![(200, "{}")],
+            &[
+                ("X-RateLimit-Limit", "100"),
+                ("X-RateLimit-Remaining", "99"),
+                ("X-RateLimit-Reset", "1700000000000"),
+            ],
+        );
+        let http = HttpService::with_url("", &server.url);
+
+        assert_eq!(http.last_ratelimit(), None);
+
+        let route = Route::new(Method::GET, "/test").compile();
+        block_on(http.fetch(route, None::<u8>)).unwrap();
+        let ratelimit = http.last_ratelimit().unwrap();
+
+        assert_eq!(ratelimit.limit, 100);
+        assert_eq!(ratelimit.remaining, 99);
+        assert_eq!(ratelimit.reset, 1_700_000_000_000);
+    }
+
+    #[test]
+    fn fetch_keeps_ratelimit_without_headers() {
+        let server = MockServer::new(vec![(200, "{}")]);
+        let http = HttpService::with_url("", &server.url);
+
+        let route = Route::new(Method::GET, "/test").compile();
+        block_on(http.fetch(route, None::<u8>)).unwrap();
+
+        assert_eq!(http.last_ratelimit(), None);
+    }
+
+    #[test]
+    fn set_header() {
+        let http = HttpService::with_url("unkey_abc", "");
+        http.set_header("X-Request-Source", "billing");
+        http.set_header("Accept", "application/vnd.unkey+json");
+        let headers = http.headers();
+
+        assert_eq!(headers["x-request-source"], "billing");
+        assert_eq!(headers["accept"], "application/vnd.unkey+json");
+    }
+
+    #[test]
+    fn set_header_rejects_reserved_and_invalid() {
+        let http = HttpService::with_url("unkey_abc", "");
+        http.set_header("authorization", "Bearer stolen");
+        http.set_header("bad header", "value");
+        http.set_header("X-Ok", "bad\nvalue");
+        let headers = http.headers();
+
+        assert_eq!(headers["authorization"], "Bearer unkey_abc");
+        assert!(!headers.contains_key("x-ok"));
+        assert_eq!(headers.len(), 5);
+    }
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy::new(3, Duration::from_millis(1), Duration::from_millis(5), false)
+    }
+
+    #[test]
+    fn fetch_retries_until_success() {
+        let server = MockServer::new(vec![(500, "{}"), (500, "{}"), (200, "{}")]);
+        let mut http = HttpService::with_url("", &server.url);
+        http.set_retry_policy(Some(policy()));
+
+        let route = Route::new(Method::GET, "/test").compile();
+        let res = block_on(http.fetch(route, None::<u8>)).unwrap();
+
+        assert_eq!(res.status().as_u16(), 200);
+        assert_eq!(server.attempts(), 3);
+    }
+
+    #[test]
+    fn fetch_stops_at_max_retries() {
+        let server = MockServer::new(vec![(500, "{}")]);
+        let mut http = HttpService::with_url("", &server.url);
+        http.set_retry_policy(Some(policy()));
+
+        let route = Route::new(Method::GET, "/test").compile();
+        let res = block_on(http.fetch(route, None::<u8>)).unwrap();
+
+        assert_eq!(res.status().as_u16(), 500);
+        assert_eq!(server.attempts(), 4);
+    }
+
+    #[test]
+    fn fetch_does_not_retry_non_idempotent() {
+        let server = MockServer::new(vec![(500, "{}"), (500, "{}"), (200, "{}")]);
+        let mut http = HttpService::with_url("", &server.url);
+        http.set_retry_policy(Some(policy()));
+
+        let route = Route::new(Method::POST, "/test")
+            .set_idempotent(false)
+            .compile();
+        let res = block_on(http.fetch(route, Some(1))).unwrap();
+
+        assert_eq!(res.status().as_u16(), 500);
+        assert_eq!(server.attempts(), 1);
+    }
+
+    #[test]
+    fn fetch_without_policy() {
+        let server = MockServer::new(vec![(500, "{}"), (200, "{}")]);
+        let http = HttpService::with_url("", &server.url);
+
+        let route = Route::new(Method::GET, "/test").compile();
+        let res = block_on(http.fetch(route, None::<u8>)).unwrap();
+
+        assert_eq!(res.status().as_u16(), 500);
+        assert_eq!(server.attempts(), 1);
+    }
+
+    #[test]
+    fn with_client_keeps_sdk_headers() {
+        let server = MockServer::new(vec![(200, "{}")]);
+        let client = reqwest::Client::builder()
+            .user_agent("custom-agent")
+            .build()
+            .unwrap();
+        let http = HttpService::with_client("unkey_abc", &server.url, client);
+
+        let route = Route::new(Method::GET, "/test").compile();
+        block_on(http.fetch(route, None::<u8>)).unwrap();
+
+        let request = &server.requests()[0];
+        let version = env!("CARGO_PKG_VERSION");
+        let user_agent = format!("unkey.rs@v{version}");
+
+        assert_eq!(
+            header(request, "authorization").unwrap(),
+            "Bearer unkey_abc"
+        );
+        assert_eq!(header(request, "user-agent").unwrap(), user_agent);
+        assert_eq!(header(request, "unkey-sdk").unwrap(), user_agent);
+    }
+
+    #[test]
+    fn set_user_agent() {
+        let server = MockServer::new(vec![(200, "{}")]);
+        let http = HttpService::with_url("", &server.url);
+        http.set_user_agent("billing-service");
+
+        let route = Route::new(Method::GET, "/test").compile();
+        block_on(http.fetch(route, None::<u8>)).unwrap();
+
+        let request = &server.requests()[0];
+        let version = env!("CARGO_PKG_VERSION");
+
+        assert_eq!(header(request, "x-user-agent").unwrap(), "billing-service");
+        assert_eq!(header(request, "user-agent").unwrap(), "billing-service");
+        assert_eq!(
+            header(request, "unkey-sdk").unwrap(),
+            format!("unkey.rs@v{version}")
+        );
+    }
+
+    #[test]
+    fn set_user_agent_empty_restores_default() {
+        let server = MockServer::new(vec![(200, "{}")]);
+        let http = HttpService::with_url("", &server.url);
+        http.set_user_agent("billing-service");
+        http.set_user_agent("");
+
+        let route = Route::new(Method::GET, "/test").compile();
+        block_on(http.fetch(route, None::<u8>)).unwrap();
+
+        let request = &server.requests()[0];
+        let version = env!("CARGO_PKG_VERSION");
+
+        assert_eq!(
+            header(request, "x-user-agent").unwrap(),
+            format!("unkey.rs@v{version}")
+        );
+    }
+
+    #[test]
+    fn with_key_leaves_original_untouched() {
+        let server = MockServer::new(vec![(200, "{}")]);
+        let http = HttpService::with_url("unkey_abc", &server.url);
+        let other = http.with_key("unkey_def");
+        other.set_url("http://localhost:1");
+
+        let route = Route::new(Method::GET, "/test").compile();
+        block_on(http.fetch(route, None::<u8>)).unwrap();
+
+        let request = &server.requests()[0];
+
+        assert_eq!(
+            header(request, "authorization").unwrap(),
+            "Bearer unkey_abc"
+        );
+        assert_eq!(http.url(), server.url);
+    }
+
+    #[test]
+    fn set_url_strips_trailing_slash() {
+        let http = HttpService::with_url("unkey_abc", "http://x/");
+        assert_eq!(http.url(), "http://x");
+
+        http.set_url("http://y//");
+        assert_eq!(http.url(), "http://y");
+    }
+
+    #[test]
+    fn try_set_url_normalizes_valid_url() {
+        let http = HttpService::new("unkey_abc");
+
+        assert!(http.try_set_url("http://x/").is_ok());
+        assert_eq!(http.url(), "http://x");
+
+        assert!(http.try_set_url("https://api.unkey.dev/v1").is_ok());
+        assert_eq!(http.url(), "https://api.unkey.dev/v1");
+    }
+
+    #[test]
+    fn try_set_url_rejects_invalid_url() {
+        let http = HttpService::with_url("unkey_abc", "http://x");
+
+        for url in ["x.com", "ftp://x.com", ""] {
+            let err = http.try_set_url(url).unwrap_err();
+            assert_eq!(err.code, ErrorCode::BadRequest);
+        }
+
+        assert_eq!(http.url(), "http://x");
+    }
+}
@@ -0,0 +1,230 @@
+use crate::fetch;
+use crate::models::CreateIdentityRequest;
+use crate::models::CreateIdentityResponse;
+use crate::models::DeleteIdentityRequest;
+use crate::models::Error;
+use crate::models::GetIdentityRequest;
+use crate::models::Identity;
+use crate::models::ListIdentitiesRequest;
+use crate::models::ListIdentitiesResponse;
+use crate::parse_empty_response;
+use crate::parse_response;
+use crate::routes;
+use crate::services::HttpTransport;
+
+/// The service that handles identity related requests.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct IdentityService;
+
+impl IdentityService {
+    /// Creates a new identity.
+    ///
+    /// # Arguments
+    /// - `http`: The http service to use for the request.
+    /// - `req`: The request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    pub async fn create_identity<H: HttpTransport>(
+        &self,
+        http: &H,
+        req: CreateIdentityRequest,
+    ) -> Result<CreateIdentityResponse, Error> {
+        let route = routes::CREATE_IDENTITY.compile();
+
+        parse_response(fetch!(http, route, req).await).await
+    }
+
+    /// Retrieves an identity by its id or external id.
+    ///
+    /// # Arguments
+    /// - `http`: The http service to use for the request.
+    /// - `req`: The request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    pub async fn get_identity<H: HttpTransport>(
+        &self,
+        http: &H,
+        req: GetIdentityRequest,
+    ) -> Result<Identity, Error> {
+        let mut route = routes::GET_IDENTITY.compile();
+
+        if let Some(identity_id) = &req.identity_id {
+            route.query_insert("identityId", identity_id);
+        }
+
+        if let Some(external_id) = &req.external_id {
+            route.query_insert("externalId", external_id);
+        }
+
+        parse_response(fetch!(http, route).await).await
+    }
+
+    /// Retrieves a paginated list of identities in the workspace.
+    ///
+    /// # Arguments
+    /// - `http`: The http service to use for the request.
+    /// - `req`: The request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    pub async fn list_identities<H: HttpTransport>(
+        &self,
+        http: &H,
+        req: ListIdentitiesRequest,
+    ) -> Result<ListIdentitiesResponse, Error> {
+        let mut route = routes::LIST_IDENTITIES.compile();
+
+        if let Some(limit) = req.limit {
+            route.query_insert("limit", &limit.to_string());
+        }
+
+        if let Some(cursor) = &req.cursor {
+            route.query_insert("cursor", cursor);
+        }
+
+        parse_response(fetch!(http, route).await).await
+    }
+
+    /// Permanently deletes an identity.
+    ///
+    /// # Arguments
+    /// - `http`: The http service to use for the request.
+    /// - `req`: The request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    pub async fn delete_identity<H: HttpTransport>(
+        &self,
+        http: &H,
+        req: DeleteIdentityRequest,
+    ) -> Result<(), Error> {
+        let route = routes::DELETE_IDENTITY.compile();
+
+        parse_empty_response(fetch!(http, route, req).await).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::mock::block_on;
+    use crate::mock::body;
+    use crate::mock::MockServer;
+    use crate::models::CreateIdentityRequest;
+    use crate::models::DeleteIdentityRequest;
+    use crate::models::GetIdentityRequest;
+    use crate::models::IdentityRatelimit;
+    use crate::models::ListIdentitiesRequest;
+    use crate::services::HttpService;
+    use crate::services::IdentityService;
+
+    #[test]
+    fn create_and_get_identity() {
+        let server = MockServer::new(vec![
+            (200, r#"{"identityId":"id_123"}"#),
+            (
+                200,
+                r#"{"id":"id_123","externalId":"user_123","meta":{"plan":"pro"},"ratelimits":[{"name":"requests","limit":10,"duration":1000}]}"#,
+            ),
+        ]);
+        let http = HttpService::with_url("", &server.url);
+        let req = CreateIdentityRequest::new("user_123")
+            .set_meta(json!({"plan": "pro"}))
+            .set_ratelimits(vec![IdentityRatelimit::new("requests", 10, 1000)]);
+
+        let created = block_on(IdentityService.create_identity(&http, req)).unwrap();
+        let req = GetIdentityRequest::new(&created.identity_id);
+        let identity = block_on(IdentityService.get_identity(&http, req)).unwrap();
+        let requests = server.requests();
+
+        assert!(requests[0].starts_with("POST /identities.createIdentity "));
+        assert_eq!(
+            body(&requests[0]),
+            r#"{"externalId":"user_123","meta":{"plan":"pro"},"ratelimits":[{"name":"requests","limit":10,"duration":1000}]}"#
+        );
+        assert!(requests[1].starts_with("GET /identities.getIdentity?identityId=id_123 "));
+        assert_eq!(created.identity_id, "id_123");
+        assert_eq!(identity.id, created.identity_id);
+        assert_eq!(identity.external_id, "user_123");
+        assert_eq!(identity.meta, Some(json!({"plan": "pro"})));
+        assert_eq!(
+            identity.ratelimits,
+            Some(vec![IdentityRatelimit::new("requests", 10, 1000)])
+        );
+    }
+
+    #[test]
+    fn get_identity_by_external_id() {
+        let server = MockServer::new(vec![(200, r#"{"id":"id_123","externalId":"user_123"}"#)]);
+        let http = HttpService::with_url("", &server.url);
+        let req = GetIdentityRequest::with_external_id("user_123");
+
+        let res = block_on(IdentityService.get_identity(&http, req)).unwrap();
+        let request = &server.requests()[0];
+
+        assert!(request.starts_with("GET /identities.getIdentity?externalId=user_123 "));
+        assert_eq!(res.id, "id_123");
+    }
+
+    #[test]
+    fn list_identities() {
+        let server = MockServer::new(vec![(
+            200,
+            r#"{"identities":[{"id":"id_1","externalId":"user_1"}],"cursor":"id_1","total":1}"#,
+        )]);
+        let http = HttpService::with_url("", &server.url);
+        let req = ListIdentitiesRequest::new().set_limit(1).set_cursor("id_0");
+
+        let res = block_on(IdentityService.list_identities(&http, req)).unwrap();
+        let request = &server.requests()[0];
+
+        assert!(request.starts_with("GET /identities.listIdentities?limit=1&cursor=id_0 "));
+        assert_eq!(res.identities[0].id, "id_1");
+        assert_eq!(res.total, 1);
+    }
+
+    #[test]
+    fn list_identities_clamps_limit() {
+        let server = MockServer::new(vec![
+            (200, r#"{"identities":[],"cursor":null,"total":0}"#),
+            (200, r#"{"identities":[],"cursor":null,"total":0}"#),
+        ]);
+        let http = HttpService::with_url("", &server.url);
+        let req = ListIdentitiesRequest::new().set_limit(500);
+
+        block_on(IdentityService.list_identities(&http, req)).unwrap();
+        block_on(IdentityService.list_identities(&http, ListIdentitiesRequest::new())).unwrap();
+        let requests = server.requests();
+
+        assert!(requests[0].starts_with("GET /identities.listIdentities?limit=100 "));
+        assert!(requests[1].starts_with("GET /identities.listIdentities "));
+    }
+
+    #[test]
+    fn delete_identity() {
+        let server = MockServer::new(vec![(200, "{}")]);
+        let http = HttpService::with_url("", &server.url);
+
+        block_on(IdentityService.delete_identity(&http, DeleteIdentityRequest::from("id_123")))
+            .unwrap();
+        let request = &server.requests()[0];
+
+        assert!(request.starts_with("POST /identities.deleteIdentity "));
+        assert_eq!(body(request), r#"{"identityId":"id_123"}"#);
+    }
+}
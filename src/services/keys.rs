@@ -1,9 +1,22 @@
+use futures_util::stream;
+use futures_util::StreamExt;
+
 use crate::fetch;
+use crate::models::AddPermissionsRequest;
 use crate::models::ApiKey;
 use crate::models::CreateKeyRequest;
 use crate::models::CreateKeyResponse;
+use crate::models::DeleteKeyRequest;
+use crate::models::Error;
+use crate::models::ErrorCode;
 use crate::models::GetKeyRequest;
-use crate::models::RevokeKeyRequest;
+use crate::models::GetUsageNumbersRequest;
+use crate::models::GetUsageNumbersResponse;
+use crate::models::HttpError;
+use crate::models::MigrateKeyRequest;
+use crate::models::MigrateKeyResponse;
+use crate::models::MigrateKeysResponse;
+use crate::models::RemovePermissionsRequest;
 use crate::models::UpdateKeyRequest;
 use crate::models::UpdateRemainingRequest;
 use crate::models::UpdateRemainingResponse;
@@ -12,10 +25,7 @@ use crate::models::VerifyKeyResponse;
 use crate::parse_empty_response;
 use crate::parse_response;
 use crate::routes;
-use crate::services::HttpService;
-
-#[allow(unused_imports)]
-use crate::models::HttpError;
+use crate::services::HttpTransport;
 
 /// The service that handles key related requests.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -32,17 +42,48 @@ impl KeyService {
     /// A [`Result`] containing the response, or an error.
     ///
     /// # Errors
-    /// The [`HttpError`], if one occurred.
-    pub async fn create_key(
+    /// The [`Error`], if one occurred. The request is validated locally
+    /// before being sent.
+    pub async fn create_key<H: HttpTransport>(
         &self,
-        http: &HttpService,
+        http: &H,
         req: CreateKeyRequest,
-    ) -> Result<CreateKeyResponse, HttpError> {
-        let route = routes::CREATE_KEY.compile();
+    ) -> Result<CreateKeyResponse, Error> {
+        req.validate_with_meta_limit(http.meta_size_limit())?;
+        let mut route = routes::CREATE_KEY.compile();
+
+        if let Some(key) = &req.idempotency_key {
+            // Resending the same idempotency key is safe, so allow retries
+            route.header_insert("Idempotency-Key", key);
+            route.idempotent = true;
+        }
 
         parse_response(fetch!(http, route, req).await).await
     }
 
+    /// Creates many new api keys, sending up to `concurrency` requests at
+    /// a time.
+    ///
+    /// # Arguments
+    /// - `http`: The http service to use for the requests.
+    /// - `reqs`: The requests to send.
+    /// - `concurrency`: The maximum number of requests in flight at once.
+    ///
+    /// # Returns
+    /// The result of each request, in the same order as `reqs`.
+    pub async fn create_keys<H: HttpTransport>(
+        &self,
+        http: &H,
+        reqs: Vec<CreateKeyRequest>,
+        concurrency: usize,
+    ) -> Vec<Result<CreateKeyResponse, Error>> {
+        stream::iter(reqs)
+            .map(|req| self.create_key(http, req))
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
     /// Verifies an existing api key.
     ///
     /// # Arguments
@@ -53,18 +94,42 @@ impl KeyService {
     /// A [`Result`] containing the response, or an error.
     ///
     /// # Errors
-    /// The [`HttpError`], if one occurred.
-    pub async fn verify_key(
+    /// The [`Error`], if one occurred.
+    pub async fn verify_key<H: HttpTransport>(
         &self,
-        http: &HttpService,
+        http: &H,
         req: VerifyKeyRequest,
-    ) -> Result<VerifyKeyResponse, HttpError> {
+    ) -> Result<VerifyKeyResponse, Error> {
         let route = routes::VERIFY_KEY.compile();
 
         parse_response(fetch!(http, route, req).await).await
     }
 
-    /// Revokes an existing api key.
+    /// Verifies many api keys, sending up to `concurrency` requests at a
+    /// time.
+    ///
+    /// # Arguments
+    /// - `http`: The http service to use for the requests.
+    /// - `reqs`: The requests to send.
+    /// - `concurrency`: The maximum number of requests in flight at once.
+    ///
+    /// # Returns
+    /// The result of each request, in the same order as `reqs`, even when
+    /// they complete out of order.
+    pub async fn verify_keys<H: HttpTransport>(
+        &self,
+        http: &H,
+        reqs: Vec<VerifyKeyRequest>,
+        concurrency: usize,
+    ) -> Vec<Result<VerifyKeyResponse, Error>> {
+        stream::iter(reqs)
+            .map(|req| self.verify_key(http, req))
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// Deletes an existing api key.
     ///
     /// # Arguments
     /// - `http`: The http service to use for the request.
@@ -74,17 +139,43 @@ impl KeyService {
     /// A [`Result`] containing the response, or an error.
     ///
     /// # Errors
-    /// The [`HttpError`], if one occurred.
-    pub async fn revoke_key(
+    /// The [`Error`], if one occurred.
+    pub async fn delete_key<H: HttpTransport>(
         &self,
-        http: &HttpService,
-        req: RevokeKeyRequest,
-    ) -> Result<(), HttpError> {
-        let route = routes::REVOKE_KEY.compile();
+        http: &H,
+        req: DeleteKeyRequest,
+    ) -> Result<(), Error> {
+        let route = routes::DELETE_KEY.compile();
 
         parse_empty_response(fetch!(http, route, req).await).await
     }
 
+    /// Revokes many existing api keys, sending up to `concurrency` requests
+    /// at a time.
+    ///
+    /// # Arguments
+    /// - `http`: The http service to use for the requests.
+    /// - `ids`: The ids of the keys to revoke.
+    /// - `concurrency`: The maximum number of requests in flight at once.
+    ///
+    /// # Returns
+    /// Each key id paired with its result, in the same order as `ids`.
+    pub async fn revoke_keys<H: HttpTransport>(
+        &self,
+        http: &H,
+        ids: Vec<String>,
+        concurrency: usize,
+    ) -> Vec<(String, Result<(), Error>)> {
+        stream::iter(ids)
+            .map(|id| async move {
+                let res = self.delete_key(http, DeleteKeyRequest::new(&id)).await;
+                (id, res)
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
     /// Updates an existing api key.
     ///
     /// # Arguments
@@ -95,17 +186,52 @@ impl KeyService {
     /// A [`Result`] containing the response, or an error.
     ///
     /// # Errors
-    /// The [`HttpError`], if one occurred.
-    pub async fn update_key(
+    /// The [`Error`], if one occurred. The request is validated locally
+    /// before being sent.
+    pub async fn update_key<H: HttpTransport>(
         &self,
-        http: &HttpService,
+        http: &H,
         req: UpdateKeyRequest,
-    ) -> Result<(), HttpError> {
+    ) -> Result<(), Error> {
+        req.validate_with_meta_limit(http.meta_size_limit())?;
         let route = routes::UPDATE_KEY.compile();
 
         parse_empty_response(fetch!(http, route, req).await).await
     }
 
+    /// Migrates an existing hashed key into unkey.
+    ///
+    /// # Arguments
+    /// - `http`: The http service to use for the request.
+    /// - `req`: The request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred. The request is validated locally
+    /// before being sent.
+    pub async fn migrate_key<H: HttpTransport>(
+        &self,
+        http: &H,
+        req: MigrateKeyRequest,
+    ) -> Result<MigrateKeyResponse, Error> {
+        req.validate_with_meta_limit(http.meta_size_limit())?;
+        let route = routes::MIGRATE_KEYS.compile();
+
+        // Unkey migrates keys in batches, so send a batch of one
+        let res: MigrateKeysResponse = parse_response(fetch!(http, route, [req]).await).await?;
+
+        match res.key_ids.into_iter().next() {
+            Some(key_id) => Ok(MigrateKeyResponse { key_id }),
+            None => Err(HttpError::new(
+                ErrorCode::Unknown,
+                String::from("The migration response did not include a key id."),
+            )
+            .into()),
+        }
+    }
+
     /// Gets details about an api key.
     ///
     /// # Arguments
@@ -116,15 +242,19 @@ impl KeyService {
     /// A [`Result`] containing the response, or an error.
     ///
     /// # Errors
-    /// The [`HttpError`], if one occurred.
-    pub async fn get_key(
+    /// The [`Error`], if one occurred.
+    pub async fn get_key<H: HttpTransport>(
         &self,
-        http: &HttpService,
+        http: &H,
         req: GetKeyRequest,
-    ) -> Result<ApiKey, HttpError> {
+    ) -> Result<ApiKey, Error> {
         let mut route = routes::GET_KEY.compile();
         route.query_insert("keyId", &req.key_id);
 
+        if req.decrypt == Some(true) {
+            route.query_insert("decrypt", "true");
+        }
+
         parse_response(fetch!(http, route).await).await
     }
 
@@ -138,14 +268,500 @@ impl KeyService {
     /// A [`Result`] containing the response, or an error.
     ///
     /// # Errors
-    /// The [`HttpError`], if one occurred.
-    pub async fn update_remaining(
+    /// The [`Error`], if one occurred. The request is validated locally
+    /// before being sent.
+    pub async fn update_remaining<H: HttpTransport>(
         &self,
-        http: &HttpService,
+        http: &H,
         req: UpdateRemainingRequest,
-    ) -> Result<UpdateRemainingResponse, HttpError> {
+    ) -> Result<UpdateRemainingResponse, Error> {
+        req.validate()?;
         let route = routes::UPDATE_REMAINING.compile();
 
         parse_response(fetch!(http, route, req).await).await
     }
+
+    /// Gets the verification usage numbers for a key or owner.
+    ///
+    /// # Arguments
+    /// - `http`: The http service to use for the request.
+    /// - `req`: The request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred. The request is validated locally
+    /// before being sent.
+    pub async fn get_verifications<H: HttpTransport>(
+        &self,
+        http: &H,
+        req: GetUsageNumbersRequest,
+    ) -> Result<GetUsageNumbersResponse, Error> {
+        req.validate()?;
+        let mut route = routes::GET_VERIFICATIONS.compile();
+
+        if let Some(key_id) = &req.key_id {
+            route.query_insert("keyId", key_id);
+        }
+
+        if let Some(owner_id) = &req.owner_id {
+            route.query_insert("ownerId", owner_id);
+        }
+
+        if let Some(start) = req.start {
+            route.query_insert("start", &start.to_string());
+        }
+
+        if let Some(end) = req.end {
+            route.query_insert("end", &end.to_string());
+        }
+
+        parse_response(fetch!(http, route).await).await
+    }
+
+    /// Adds permissions to an existing key.
+    ///
+    /// # Arguments
+    /// - `http`: The http service to use for the request.
+    /// - `req`: The request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    pub async fn add_permissions<H: HttpTransport>(
+        &self,
+        http: &H,
+        req: AddPermissionsRequest,
+    ) -> Result<(), Error> {
+        let route = routes::ADD_PERMISSIONS.compile();
+
+        parse_empty_response(fetch!(http, route, req).await).await
+    }
+
+    /// Removes permissions from an existing key.
+    ///
+    /// # Arguments
+    /// - `http`: The http service to use for the request.
+    /// - `req`: The request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    pub async fn remove_permissions<H: HttpTransport>(
+        &self,
+        http: &H,
+        req: RemovePermissionsRequest,
+    ) -> Result<(), Error> {
+        let route = routes::REMOVE_PERMISSIONS.compile();
+
+        parse_empty_response(fetch!(http, route, req).await).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use serde::Serialize;
+
+    use crate::mock::block_on;
+    use crate::mock::body;
+    use crate::mock::header;
+    use crate::mock::MockServer;
+    use crate::mock::MockTransport;
+    use crate::models::AddPermissionsRequest;
+    use crate::models::CreateKeyRequest;
+    use crate::models::ErrorCode;
+    use crate::models::GetKeyRequest;
+    use crate::models::GetUsageNumbersRequest;
+    use crate::models::KeyHash;
+    use crate::models::MigrateKeyRequest;
+    use crate::models::RemovePermissionsRequest;
+    use crate::models::RetryPolicy;
+    use crate::models::UpdateKeyRequest;
+    use crate::models::UpdateOp;
+    use crate::models::UpdateRemainingRequest;
+    use crate::models::VerifyKeyRequest;
+    use crate::routes::CompiledRoute;
+    use crate::services::FetchFuture;
+    use crate::services::HttpService;
+    use crate::services::HttpTransport;
+    use crate::services::KeyService;
+
+    #[test]
+    fn get_verifications_requires_key_or_owner() {
+        let server = MockServer::new(vec![(200, r#"{"verifications":[]}"#)]);
+        let http = HttpService::with_url("", &server.url);
+        let req = GetUsageNumbersRequest::new();
+
+        let err = block_on(KeyService.get_verifications(&http, req)).unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::BadRequest);
+        assert_eq!(server.attempts(), 0);
+    }
+
+    #[test]
+    fn get_verifications() {
+        let server = MockServer::new(vec![(200, r#"{"verifications":[]}"#)]);
+        let http = HttpService::with_url("", &server.url);
+        let req = GetUsageNumbersRequest::new().set_owner_id("jonxslays");
+
+        let res = block_on(KeyService.get_verifications(&http, req)).unwrap();
+        let request = &server.requests()[0];
+
+        assert!(res.verifications.is_empty());
+        assert!(request.starts_with("GET /keys.getVerifications?ownerId=jonxslays "));
+    }
+
+    #[test]
+    fn get_verifications_with_window() {
+        let http = MockTransport::new(200, r#"{"verifications":[]}"#);
+        let req = GetUsageNumbersRequest::new()
+            .set_key_id("key_123")
+            .set_start(100)
+            .set_end(200);
+
+        block_on(KeyService.get_verifications(&http, req)).unwrap();
+
+        assert_eq!(
+            http.sent()[0].0,
+            "/keys.getVerifications?keyId=key_123&start=100&end=200"
+        );
+    }
+
+    #[test]
+    fn get_verifications_rejects_inverted_window() {
+        let http = MockTransport::new(200, r#"{"verifications":[]}"#);
+        let req = GetUsageNumbersRequest::new()
+            .set_key_id("key_123")
+            .set_start(200)
+            .set_end(100);
+
+        let err = block_on(KeyService.get_verifications(&http, req)).unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::BadRequest);
+        assert!(http.sent().is_empty());
+    }
+
+    #[test]
+    fn update_key_requires_changes() {
+        let http = MockTransport::new(200, "{}");
+        let req = UpdateKeyRequest::new("key_123");
+
+        let err = block_on(KeyService.update_key(&http, req)).unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::BadRequest);
+        assert!(http.sent().is_empty());
+    }
+
+    #[test]
+    fn create_key_rejects_large_meta() {
+        let http = MockTransport::new(200, r#"{"keyId":"key_123","key":"test_abc"}"#);
+        let meta = serde_json::json!({"blob": "a".repeat(2 * 1024 * 1024)});
+        let req = CreateKeyRequest::new("api_123").set_meta(meta);
+
+        let err = block_on(KeyService.create_key(&http, req)).unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::BadRequest);
+        assert!(http.sent().is_empty());
+    }
+
+    #[test]
+    fn migrate_key() {
+        let http = MockTransport::new(200, r#"{"keyIds":["key_123"]}"#);
+        let hash = KeyHash::sha256_base64("n4bQgYhMfWWaL+qgxVrQFaO/TxsrC4Is0V1sFbDwCgg=");
+        let req = MigrateKeyRequest::new("api_123", hash).set_prefix("sk");
+
+        let res = block_on(KeyService.migrate_key(&http, req)).unwrap();
+        let sent = http.sent();
+
+        assert_eq!(res.key_id, "key_123");
+        assert_eq!(sent[0].0, "/migrations.createKeys");
+        assert_eq!(
+            sent[0].1.as_deref(),
+            Some(
+                r#"[{"apiId":"api_123","hash":{"value":"n4bQgYhMfWWaL+qgxVrQFaO/TxsrC4Is0V1sFbDwCgg=","variant":"sha256_base64"},"prefix":"sk"}]"#
+            )
+        );
+    }
+
+    #[test]
+    fn migrate_key_without_key_id() {
+        let http = MockTransport::new(200, r#"{"keyIds":[]}"#);
+        let req = MigrateKeyRequest::new("api_123", KeyHash::sha256_base64("abc="));
+
+        let err = block_on(KeyService.migrate_key(&http, req)).unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::Unknown);
+    }
+
+    #[test]
+    fn update_key_with_changes() {
+        let http = MockTransport::new(200, "{}");
+        let req = UpdateKeyRequest::new("key_123").set_external_id(None);
+
+        block_on(KeyService.update_key(&http, req)).unwrap();
+        let sent = http.sent();
+
+        assert_eq!(sent[0].0, "/keys.updateKey");
+        assert_eq!(
+            sent[0].1.as_deref(),
+            Some(r#"{"keyId":"key_123","ownerId":null}"#)
+        );
+    }
+
+    #[test]
+    fn update_remaining_requires_value() {
+        let server = MockServer::new(vec![(200, r#"{"remaining":0}"#)]);
+        let http = HttpService::with_url("", &server.url);
+        let req = UpdateRemainingRequest::new("key_123", None, UpdateOp::Decrement);
+
+        let err = block_on(KeyService.update_remaining(&http, req)).unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::BadRequest);
+        assert_eq!(server.attempts(), 0);
+    }
+
+    #[test]
+    fn verify_key_is_not_retried() {
+        let server = MockServer::new(vec![(500, "{}"), (200, r#"{"valid":true}"#)]);
+        let mut http = HttpService::with_url("", &server.url);
+        let policy = RetryPolicy::new(3, Duration::from_millis(1), Duration::from_millis(5), false);
+        http.set_retry_policy(Some(policy));
+
+        let req = VerifyKeyRequest::new("test_123", "api_123");
+        let err = block_on(KeyService.verify_key(&http, req)).unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::Unknown);
+        assert_eq!(server.attempts(), 1);
+    }
+
+    #[test]
+    fn verify_key_with_mock_transport() {
+        let http = MockTransport::new(200, r#"{"valid":false,"code":"EXPIRED"}"#);
+        let req = VerifyKeyRequest::new("test_abc", "api_123");
+
+        let res = block_on(KeyService.verify_key(&http, req)).unwrap();
+        let sent = http.sent();
+
+        assert!(!res.valid);
+        assert_eq!(res.code, Some(ErrorCode::Expired));
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0, "/keys.verifyKey");
+        assert_eq!(
+            sent[0].1.as_deref(),
+            Some(r#"{"key":"test_abc","apiId":"api_123"}"#)
+        );
+    }
+
+    #[test]
+    fn verify_key_error_with_mock_transport() {
+        let http = MockTransport::new(
+            404,
+            r#"{"error":{"code":"NOT_FOUND","message":"api not found"}}"#,
+        );
+        let req = VerifyKeyRequest::new("test_abc", "api_123");
+
+        let err = block_on(KeyService.verify_key(&http, req)).unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::NotFound);
+        assert_eq!(err.as_api().unwrap().message, "api not found");
+    }
+
+    const KEY: &str = r#"{"id":"key_123","apiId":"api_123","workspaceId":"ws_123","start":"t_","createdAt":0,"plaintext":"t_abc"}"#;
+
+    #[test]
+    fn get_key_with_decrypt() {
+        let http = MockTransport::new(200, KEY);
+        let req = GetKeyRequest::new("key_123").set_decrypt(true);
+
+        let res = block_on(KeyService.get_key(&http, req)).unwrap();
+
+        assert_eq!(http.sent()[0].0, "/keys.getKey?keyId=key_123&decrypt=true");
+        assert_eq!(res.plaintext.as_deref(), Some("t_abc"));
+    }
+
+    #[test]
+    fn get_key_without_decrypt() {
+        let http = MockTransport::new(200, KEY);
+
+        block_on(KeyService.get_key(&http, GetKeyRequest::new("key_123"))).unwrap();
+        block_on(KeyService.get_key(&http, GetKeyRequest::new("key_123").set_decrypt(false)))
+            .unwrap();
+
+        let sent = http.sent();
+
+        assert_eq!(sent[0].0, "/keys.getKey?keyId=key_123");
+        assert_eq!(sent[1].0, "/keys.getKey?keyId=key_123");
+    }
+
+    #[test]
+    fn create_key_with_idempotency_key() {
+        let server = MockServer::new(vec![(200, r#"{"keyId":"key_123","key":"t_abc"}"#)]);
+        let http = HttpService::with_url("", &server.url);
+        let req = CreateKeyRequest::new("api_123").set_idempotency_key("req_123");
+
+        block_on(KeyService.create_key(&http, req)).unwrap();
+        let request = &server.requests()[0];
+
+        assert_eq!(header(request, "idempotency-key").unwrap(), "req_123");
+        assert_eq!(body(request), r#"{"apiId":"api_123"}"#);
+    }
+
+    #[test]
+    fn create_key_without_idempotency_key() {
+        let server = MockServer::new(vec![(200, r#"{"keyId":"key_123","key":"t_abc"}"#)]);
+        let http = HttpService::with_url("", &server.url);
+
+        block_on(KeyService.create_key(&http, CreateKeyRequest::new("api_123"))).unwrap();
+        let request = &server.requests()[0];
+
+        assert_eq!(header(request, "idempotency-key"), None);
+    }
+
+    #[test]
+    fn add_and_remove_permissions() {
+        let http = MockTransport::new(200, r#"[{"id":"perm_123","name":"keys.read"}]"#);
+
+        let add = AddPermissionsRequest::new("key_123", &["keys.read"]);
+        block_on(KeyService.add_permissions(&http, add)).unwrap();
+
+        let remove = RemovePermissionsRequest::new("key_123", &["keys.read"]);
+        block_on(KeyService.remove_permissions(&http, remove)).unwrap();
+
+        let sent = http.sent();
+
+        assert_eq!(sent[0].0, "/keys.addPermissions");
+        assert_eq!(sent[1].0, "/keys.removePermissions");
+    }
+
+    #[test]
+    fn create_keys_aggregates_in_order() {
+        let server = MockServer::new(vec![
+            (200, r#"{"keyId":"key_1","key":"t_1"}"#),
+            (
+                400,
+                r#"{"error":{"code":"BAD_REQUEST","message":"invalid prefix"}}"#,
+            ),
+            (200, r#"{"keyId":"key_3","key":"t_3"}"#),
+        ]);
+        let http = HttpService::with_url("", &server.url);
+        let reqs = vec![
+            CreateKeyRequest::new("api_123"),
+            CreateKeyRequest::new("api_123").set_prefix("bad prefix"),
+            CreateKeyRequest::new("api_123"),
+        ];
+
+        let res = block_on(KeyService.create_keys(&http, reqs, 1));
+
+        assert_eq!(res.len(), 3);
+        assert_eq!(res[0].as_ref().unwrap().key_id, "key_1");
+        assert_eq!(res[1].as_ref().unwrap_err().code(), ErrorCode::BadRequest);
+        assert_eq!(res[2].as_ref().unwrap().key_id, "key_3");
+    }
+
+    #[test]
+    fn create_keys_concurrently() {
+        let http = MockTransport::new(200, r#"{"keyId":"key_123","key":"t_abc"}"#);
+        let reqs = (0..5).map(|_| CreateKeyRequest::new("api_123")).collect();
+
+        let res = block_on(KeyService.create_keys(&http, reqs, 2));
+
+        assert_eq!(res.len(), 5);
+        assert!(res.iter().all(Result::is_ok));
+        assert_eq!(http.sent().len(), 5);
+    }
+
+    /// A transport responding to verifications after a delay, where later
+    /// keys respond sooner, so requests complete in reverse order.
+    struct ReversingTransport {
+        /// The keys in the order their requests completed.
+        completed: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl HttpTransport for ReversingTransport {
+        fn fetch<'a, T>(&'a self, _route: CompiledRoute, payload: Option<T>) -> FetchFuture<'a>
+        where
+            T: std::fmt::Debug + Serialize + Send + 'a,
+        {
+            let payload = serde_json::to_value(payload.unwrap()).unwrap();
+            let key = payload["key"].as_str().unwrap().to_string();
+            let index: u64 = key.trim_start_matches("t_").parse().unwrap();
+            let completed = Arc::clone(&self.completed);
+
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_millis(50 - index * 10)).await;
+                completed.lock().unwrap().push(key.clone());
+
+                let body = format!(r#"{{"valid":true,"keyId":"{key}"}}"#);
+                Ok(http::Response::new(body).into())
+            })
+        }
+    }
+
+    #[test]
+    fn verify_keys_preserves_order() {
+        let completed = Arc::new(Mutex::new(Vec::new()));
+        let http = ReversingTransport {
+            completed: Arc::clone(&completed),
+        };
+        let reqs = (1..=4)
+            .map(|i| VerifyKeyRequest::new(format!("t_{i}"), String::from("api_123")))
+            .collect();
+
+        let res = block_on(KeyService.verify_keys(&http, reqs, 4));
+        let ids: Vec<_> = res
+            .into_iter()
+            .map(|r| r.unwrap().key_id.unwrap())
+            .collect();
+
+        assert_eq!(ids, ["t_1", "t_2", "t_3", "t_4"]);
+        assert_eq!(*completed.lock().unwrap(), ["t_4", "t_3", "t_2", "t_1"]);
+    }
+
+    #[test]
+    fn get_key_encodes_key_id() {
+        let http = MockTransport::new(200, KEY);
+
+        block_on(KeyService.get_key(&http, GetKeyRequest::new("key/../123"))).unwrap();
+
+        assert_eq!(http.sent()[0].0, "/keys.getKey?keyId=key%2F..%2F123");
+    }
+
+    #[test]
+    fn revoke_keys_pairs_ids_with_results() {
+        let server = MockServer::new(vec![
+            (200, "{}"),
+            (
+                404,
+                r#"{"error":{"code":"NOT_FOUND","message":"key not found"}}"#,
+            ),
+            (200, "{}"),
+        ]);
+        let http = HttpService::with_url("", &server.url);
+        let ids = vec![
+            String::from("key_1"),
+            String::from("key_2"),
+            String::from("key_3"),
+        ];
+
+        let res = block_on(KeyService.revoke_keys(&http, ids, 1));
+        let requests = server.requests();
+
+        assert_eq!(res.len(), 3);
+        assert_eq!(res[0].0, "key_1");
+        assert!(res[0].1.is_ok());
+        assert_eq!(res[1].0, "key_2");
+        assert_eq!(res[1].1.as_ref().unwrap_err().code(), ErrorCode::NotFound);
+        assert_eq!(res[2].0, "key_3");
+        assert!(res[2].1.is_ok());
+        assert_eq!(body(&requests[1]), r#"{"keyId":"key_2"}"#);
+    }
 }
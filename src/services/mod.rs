@@ -1,7 +1,11 @@
 mod apis;
 mod http;
+mod identities;
 mod keys;
+mod ratelimits;
 
 pub(crate) use apis::*;
 pub(crate) use http::*;
+pub(crate) use identities::*;
 pub(crate) use keys::*;
+pub(crate) use ratelimits::*;
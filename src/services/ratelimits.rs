@@ -0,0 +1,67 @@
+use crate::fetch;
+use crate::models::Error;
+use crate::models::RatelimitRequest;
+use crate::models::RatelimitResponse;
+use crate::parse_response;
+use crate::routes;
+use crate::services::HttpTransport;
+
+/// The service that handles standalone ratelimit related requests.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct RatelimitService;
+
+impl RatelimitService {
+    /// Applies a ratelimit to the given identifier.
+    ///
+    /// # Arguments
+    /// - `http`: The http service to use for the request.
+    /// - `req`: The request to send.
+    ///
+    /// # Returns
+    /// A [`Result`] containing the response, or an error.
+    ///
+    /// # Errors
+    /// The [`Error`], if one occurred.
+    pub async fn limit<H: HttpTransport>(
+        &self,
+        http: &H,
+        req: RatelimitRequest,
+    ) -> Result<RatelimitResponse, Error> {
+        let route = routes::LIMIT.compile();
+
+        parse_response(fetch!(http, route, req).await).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::mock::block_on;
+    use crate::mock::body;
+    use crate::mock::MockServer;
+    use crate::models::RatelimitRequest;
+    use crate::services::HttpService;
+    use crate::services::RatelimitService;
+
+    #[test]
+    fn limit() {
+        let server = MockServer::new(vec![(
+            200,
+            r#"{"success":true,"limit":10,"remaining":9,"reset":1700000000000}"#,
+        )]);
+        let http = HttpService::with_url("", &server.url);
+        let req = RatelimitRequest::new("ns", "user", 10, 1000);
+
+        let res = block_on(RatelimitService.limit(&http, req)).unwrap();
+        let request = &server.requests()[0];
+
+        assert!(request.starts_with("POST /ratelimits.limit "));
+        assert_eq!(
+            body(request),
+            r#"{"namespace":"ns","identifier":"user","limit":10,"duration":1000,"cost":1}"#
+        );
+        assert!(res.success);
+        assert_eq!(res.limit, 10);
+        assert_eq!(res.remaining, 9);
+        assert_eq!(res.reset, 1_700_000_000_000);
+    }
+}